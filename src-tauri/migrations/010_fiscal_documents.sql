@@ -0,0 +1,25 @@
+-- 010_fiscal_documents.sql
+-- Datos fiscales por tienda y numeración serie-correlativo de comprobantes (boleta / factura)
+
+ALTER TABLE stores ADD COLUMN ruc TEXT;
+ALTER TABLE stores ADD COLUMN logo_path TEXT;
+
+ALTER TABLE orders ADD COLUMN document_type TEXT DEFAULT 'boleta'; -- 'boleta', 'factura'
+ALTER TABLE orders ADD COLUMN series TEXT;
+ALTER TABLE orders ADD COLUMN number INTEGER;
+
+/* =========================
+   DOCUMENT SERIES
+   Último correlativo emitido por tienda y tipo de comprobante.
+========================= */
+CREATE TABLE IF NOT EXISTS document_series (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER NOT NULL,
+    document_type TEXT NOT NULL,
+    series TEXT NOT NULL,
+    last_number INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (store_id, document_type),
+    FOREIGN KEY (store_id) REFERENCES stores(id)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_orders_series_number ON orders(series, number);
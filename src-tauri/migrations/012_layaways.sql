@@ -0,0 +1,56 @@
+-- 012_layaways.sql
+-- Separaciones: reservan stock sin venderlo, reciben abonos en la caja abierta
+-- y se convierten en venta al completarse el pago
+
+CREATE TABLE IF NOT EXISTS layaways (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uuid TEXT UNIQUE NOT NULL,
+    store_id INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    client_document TEXT,
+    client_phone TEXT,
+    client_name TEXT NOT NULL,
+    total REAL NOT NULL DEFAULT 0,
+    paid_amount REAL NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'active', -- 'active', 'completed', 'expired', 'cancelled'
+    expires_at TEXT NOT NULL,
+    order_id INTEGER,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (order_id) REFERENCES orders(id)
+);
+
+CREATE TABLE IF NOT EXISTS layaway_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    layaway_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    product_name TEXT NOT NULL,
+    unit_price REAL NOT NULL,
+    quantity INTEGER NOT NULL,
+    subtotal REAL NOT NULL,
+    FOREIGN KEY (layaway_id) REFERENCES layaways(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE RESTRICT
+);
+
+CREATE TABLE IF NOT EXISTS layaway_payments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    layaway_id INTEGER NOT NULL,
+    cash_session_id INTEGER NOT NULL,
+    amount REAL NOT NULL,
+    payment_method TEXT NOT NULL, -- 'cash', 'virtual'
+    user_id INTEGER NOT NULL,
+    store_id INTEGER NOT NULL,
+    created_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (layaway_id) REFERENCES layaways(id) ON DELETE CASCADE,
+    FOREIGN KEY (cash_session_id) REFERENCES cash_sessions(id),
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (store_id) REFERENCES stores(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_layaways_store_status ON layaways(store_id, status);
+CREATE INDEX IF NOT EXISTS idx_layaway_items_product ON layaway_items(product_id);
+CREATE INDEX IF NOT EXISTS idx_layaway_payments_session ON layaway_payments(cash_session_id);
+
+-- Días de vigencia de una separación antes de liberar el stock
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('layaway_expiry_days', '30');
@@ -0,0 +1,46 @@
+-- 013_stored_value.sql
+-- Tarjetas de regalo y saldo a favor (notas de crédito) con libro de movimientos
+
+CREATE TABLE IF NOT EXISTS stored_value_accounts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    uuid TEXT UNIQUE NOT NULL,
+    code TEXT UNIQUE NOT NULL,
+    kind TEXT NOT NULL, -- 'gift_card', 'store_credit'
+    store_id INTEGER NOT NULL,
+    customer_document TEXT,
+    customer_name TEXT,
+    balance REAL NOT NULL DEFAULT 0,
+    status TEXT NOT NULL DEFAULT 'active', -- 'active', 'void'
+    expires_at TEXT,
+    created_by INTEGER,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+/* =========================
+   STORED VALUE LEDGER
+   Cada fila es un movimiento: emisión / recarga (+) o canje (-).
+========================= */
+CREATE TABLE IF NOT EXISTS stored_value_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    account_id INTEGER NOT NULL,
+    entry_type TEXT NOT NULL, -- 'issue', 'topup', 'redeem'
+    amount REAL NOT NULL,
+    balance_after REAL NOT NULL,
+    order_id INTEGER,
+    cash_session_id INTEGER,
+    user_id INTEGER,
+    note TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (account_id) REFERENCES stored_value_accounts(id),
+    FOREIGN KEY (order_id) REFERENCES orders(id),
+    FOREIGN KEY (cash_session_id) REFERENCES cash_sessions(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stored_value_customer ON stored_value_accounts(customer_document);
+CREATE INDEX IF NOT EXISTS idx_stored_value_ledger_account ON stored_value_ledger(account_id);
+
+-- Parte del total de la venta pagada con tarjeta de regalo / saldo a favor
+ALTER TABLE orders ADD COLUMN stored_value_amount REAL NOT NULL DEFAULT 0;
@@ -0,0 +1,60 @@
+-- 014_loyalty.sql
+-- Clientes (DNI / teléfono), programa de puntos y anulación de ventas
+
+CREATE TABLE IF NOT EXISTS customers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    document TEXT UNIQUE,
+    phone TEXT,
+    name TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_customers_phone ON customers(phone);
+
+/* =========================
+   LOYALTY RULES
+   Multiplicador de puntos por categoría (1.0 si no existe fila).
+========================= */
+CREATE TABLE IF NOT EXISTS loyalty_category_multipliers (
+    category_id INTEGER PRIMARY KEY,
+    multiplier REAL NOT NULL DEFAULT 1,
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE
+);
+
+/* =========================
+   LOYALTY LEDGER
+   'earn' y 'refund' suman puntos con vencimiento; 'remaining' es lo que
+   queda por canjear de ese lote. 'redeem', 'reverse' y 'expire' restan.
+========================= */
+CREATE TABLE IF NOT EXISTS loyalty_ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    customer_id INTEGER NOT NULL,
+    order_id INTEGER,
+    entry_type TEXT NOT NULL, -- 'earn', 'refund', 'redeem', 'reverse', 'expire'
+    points INTEGER NOT NULL,
+    remaining INTEGER NOT NULL DEFAULT 0,
+    expires_at TEXT,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (customer_id) REFERENCES customers(id),
+    FOREIGN KEY (order_id) REFERENCES orders(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_loyalty_ledger_customer ON loyalty_ledger(customer_id);
+CREATE INDEX IF NOT EXISTS idx_loyalty_ledger_order ON loyalty_ledger(order_id);
+
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('loyalty_enabled', '1');
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('loyalty_points_per_sol', '1');
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('loyalty_point_value', '0.05');
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('loyalty_expiry_days', '365');
+
+ALTER TABLE orders ADD COLUMN customer_id INTEGER REFERENCES customers(id);
+-- Parte del total pagada con puntos
+ALTER TABLE orders ADD COLUMN loyalty_amount REAL NOT NULL DEFAULT 0;
+
+-- Anulación de ventas
+ALTER TABLE orders ADD COLUMN status TEXT NOT NULL DEFAULT 'completed'; -- 'completed', 'voided'
+ALTER TABLE orders ADD COLUMN voided_at TEXT;
+ALTER TABLE orders ADD COLUMN voided_by INTEGER REFERENCES users(id);
+ALTER TABLE orders ADD COLUMN void_reason TEXT;
+
+-- stored_value_ledger.entry_type admite además 'refund' (devolución por venta anulada)
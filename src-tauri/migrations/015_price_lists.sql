@@ -0,0 +1,42 @@
+-- 015_price_lists.sql
+-- Listas de precios (minorista, mayorista, personal) con escalas por cantidad
+
+/* =========================
+   PRICE LISTS
+========================= */
+CREATE TABLE IF NOT EXISTS price_lists (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    description TEXT,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+/* =========================
+   PRICE LIST ITEMS
+   Precio unitario de un producto en una lista a partir de 'min_quantity'
+   unidades. Sin fila aplicable se usa products.price.
+========================= */
+CREATE TABLE IF NOT EXISTS price_list_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    price_list_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    min_quantity INTEGER NOT NULL DEFAULT 1,
+    price REAL NOT NULL,
+    UNIQUE (price_list_id, product_id, min_quantity),
+    FOREIGN KEY (price_list_id) REFERENCES price_lists(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_price_list_items_product ON price_list_items(product_id);
+
+INSERT OR IGNORE INTO price_lists (name, description) VALUES ('Minorista', 'Precio de venta al público');
+INSERT OR IGNORE INTO price_lists (name, description) VALUES ('Mayorista', 'Revendedores por volumen');
+INSERT OR IGNORE INTO price_lists (name, description) VALUES ('Personal', 'Compras del personal');
+
+-- Lista asignada al cliente; se aplica si la venta no indica otra
+ALTER TABLE customers ADD COLUMN price_list_id INTEGER REFERENCES price_lists(id);
+
+-- Lista con la que se fijó el precio de cada línea (NULL = precio de góndola o manual)
+ALTER TABLE order_items ADD COLUMN price_list_id INTEGER REFERENCES price_lists(id);
+ALTER TABLE layaway_items ADD COLUMN price_list_id INTEGER REFERENCES price_lists(id);
@@ -0,0 +1,26 @@
+-- 016_commissions.sql
+-- Reglas de comisión por vendedor
+
+/* =========================
+   COMMISSION RULES
+   'rate' es el porcentaje sobre la venta sin IGV. La regla más específica
+   gana (producto > categoría > general); dentro de ella aplica el tramo con
+   mayor 'min_monthly_volume' alcanzado por el vendedor en el mes.
+   store_id NULL = todas las tiendas.
+========================= */
+CREATE TABLE IF NOT EXISTS commission_rules (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER,
+    scope TEXT NOT NULL DEFAULT 'global', -- 'global', 'category', 'product'
+    category_id INTEGER,
+    product_id INTEGER,
+    rate REAL NOT NULL,
+    min_monthly_volume REAL NOT NULL DEFAULT 0,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_commission_rules_store ON commission_rules(store_id);
@@ -0,0 +1,60 @@
+-- 017_money_cents.sql
+-- Montos en céntimos enteros (S/ 12.50 -> 1250) para evitar errores de punto flotante.
+-- SQLite no permite cambiar el tipo declarado de una columna; las columnas REAL
+-- conservan su afinidad pero a partir de aquí solo guardan céntimos exactos.
+
+UPDATE products SET
+    price = CAST(ROUND(price * 100) AS INTEGER),
+    cost = CAST(ROUND(cost * 100) AS INTEGER);
+
+UPDATE orders SET
+    subtotal = CAST(ROUND(subtotal * 100) AS INTEGER),
+    igv = CAST(ROUND(igv * 100) AS INTEGER),
+    total = CAST(ROUND(total * 100) AS INTEGER),
+    stored_value_amount = CAST(ROUND(stored_value_amount * 100) AS INTEGER),
+    loyalty_amount = CAST(ROUND(loyalty_amount * 100) AS INTEGER);
+
+UPDATE order_items SET
+    unit_price = CAST(ROUND(unit_price * 100) AS INTEGER),
+    subtotal = CAST(ROUND(subtotal * 100) AS INTEGER);
+
+UPDATE cash_sessions SET
+    opening_cash = CAST(ROUND(opening_cash * 100) AS INTEGER),
+    opening_virtual = CAST(ROUND(opening_virtual * 100) AS INTEGER),
+    expected_closing_cash = CAST(ROUND(expected_closing_cash * 100) AS INTEGER),
+    expected_closing_virtual = CAST(ROUND(expected_closing_virtual * 100) AS INTEGER),
+    real_closing_cash = CAST(ROUND(real_closing_cash * 100) AS INTEGER),
+    real_closing_virtual = CAST(ROUND(real_closing_virtual * 100) AS INTEGER),
+    difference = CAST(ROUND(difference * 100) AS INTEGER);
+
+UPDATE expenses SET amount = CAST(ROUND(amount * 100) AS INTEGER);
+UPDATE other_income SET amount = CAST(ROUND(amount * 100) AS INTEGER);
+
+UPDATE purchase_orders SET total_cost = CAST(ROUND(total_cost * 100) AS INTEGER);
+UPDATE purchase_order_items SET
+    unit_cost = CAST(ROUND(unit_cost * 100) AS INTEGER),
+    unit_price = CAST(ROUND(unit_price * 100) AS INTEGER);
+
+UPDATE quotations SET
+    subtotal = CAST(ROUND(subtotal * 100) AS INTEGER),
+    igv = CAST(ROUND(igv * 100) AS INTEGER),
+    total = CAST(ROUND(total * 100) AS INTEGER);
+UPDATE quotation_items SET
+    unit_price = CAST(ROUND(unit_price * 100) AS INTEGER),
+    subtotal = CAST(ROUND(subtotal * 100) AS INTEGER);
+
+UPDATE layaways SET
+    total = CAST(ROUND(total * 100) AS INTEGER),
+    paid_amount = CAST(ROUND(paid_amount * 100) AS INTEGER);
+UPDATE layaway_items SET
+    unit_price = CAST(ROUND(unit_price * 100) AS INTEGER),
+    subtotal = CAST(ROUND(subtotal * 100) AS INTEGER);
+UPDATE layaway_payments SET amount = CAST(ROUND(amount * 100) AS INTEGER);
+
+UPDATE stored_value_accounts SET balance = CAST(ROUND(balance * 100) AS INTEGER);
+UPDATE stored_value_ledger SET
+    amount = CAST(ROUND(amount * 100) AS INTEGER),
+    balance_after = CAST(ROUND(balance_after * 100) AS INTEGER);
+
+UPDATE price_list_items SET price = CAST(ROUND(price * 100) AS INTEGER);
+UPDATE commission_rules SET min_monthly_volume = CAST(ROUND(min_monthly_volume * 100) AS INTEGER);
@@ -0,0 +1,44 @@
+-- 018_product_variants.sql
+-- Estilos (modelo padre) con variantes por talla y color.
+-- Cada variante sigue siendo una fila de 'products' con su propio SKU (code),
+-- código de barras, stock y precio, así ventas, compras y separaciones
+-- funcionan sin cambios a nivel de variante.
+
+/* =========================
+   PRODUCT STYLES
+========================= */
+CREATE TABLE IF NOT EXISTS product_styles (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    name TEXT NOT NULL,
+    category_id INTEGER,
+    price REAL NOT NULL DEFAULT 0, -- céntimos; precio de las variantes sin precio propio
+    cost REAL NOT NULL DEFAULT 0,  -- céntimos
+    unit TEXT DEFAULT 'Unidades',
+    image_url TEXT,
+    is_active INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (store_id, code),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
+);
+
+/* =========================
+   VARIANTS
+   products.price es siempre el precio vigente de la variante:
+   price_override si tiene uno, si no el precio del estilo.
+========================= */
+ALTER TABLE products ADD COLUMN style_id INTEGER REFERENCES product_styles(id);
+ALTER TABLE products ADD COLUMN size TEXT;
+ALTER TABLE products ADD COLUMN color TEXT;
+ALTER TABLE products ADD COLUMN barcode TEXT;
+ALTER TABLE products ADD COLUMN price_override REAL; -- céntimos; NULL = precio del estilo
+
+CREATE INDEX IF NOT EXISTS idx_products_style ON products(style_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_style_size_color
+    ON products(style_id, COALESCE(size, ''), COALESCE(color, ''))
+    WHERE style_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_store_barcode
+    ON products(store_id, barcode)
+    WHERE barcode IS NOT NULL;
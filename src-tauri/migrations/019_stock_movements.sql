@@ -0,0 +1,34 @@
+-- 019_stock_movements.sql
+-- Kardex: cada cambio de stock deja un movimiento con el saldo resultante,
+-- escrito en la misma transacción que modifica products.stock.
+
+/* =========================
+   STOCK MOVEMENTS
+   reason: sale | purchase | adjustment | return | transfer
+   document_type / document_id: documento origen (order, purchase_order, ...)
+========================= */
+CREATE TABLE IF NOT EXISTS stock_movements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL,
+    store_id INTEGER NOT NULL,
+    delta INTEGER NOT NULL,
+    balance INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    document_type TEXT,
+    document_id INTEGER,
+    user_id INTEGER,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_movements_product ON stock_movements(product_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_stock_movements_document ON stock_movements(document_type, document_id);
+
+-- Saldo de apertura para los productos que ya tienen stock
+INSERT INTO stock_movements (product_id, store_id, delta, balance, reason, note)
+SELECT id, COALESCE(store_id, 1), stock, stock, 'adjustment', 'Saldo inicial del kardex'
+FROM products
+WHERE stock <> 0;
@@ -0,0 +1,31 @@
+-- 020_stock_adjustments.sql
+-- Ajustes manuales de stock con motivo (merma, robo, sobrante, corrección).
+-- El stock ya no se edita desde el formulario de producto.
+
+/* =========================
+   STOCK ADJUSTMENTS
+   reason_code: damage | theft | found | correction
+   status: pending (espera aprobación) | applied | rejected
+========================= */
+CREATE TABLE IF NOT EXISTS stock_adjustments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    reason_code TEXT NOT NULL,
+    delta INTEGER NOT NULL,
+    note TEXT,
+    status TEXT NOT NULL DEFAULT 'pending',
+    requested_by INTEGER NOT NULL,
+    reviewed_by INTEGER,
+    reviewed_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (requested_by) REFERENCES users(id),
+    FOREIGN KEY (reviewed_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_adjustments_store ON stock_adjustments(store_id, status);
+
+-- '1' = los ajustes de usuarios que no son ADMIN/GERENTE esperan aprobación
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('stock_adjustment_approval', '0');
@@ -0,0 +1,49 @@
+-- 021_products_code_per_store.sql
+-- El código del producto pasa a ser único por tienda (antes era global), para
+-- que el mismo artículo exista con su código en cada tienda y las
+-- transferencias puedan emparejarlo. SQLite no permite quitar un UNIQUE de
+-- columna, así que se reconstruye la tabla. Las migraciones corren con las
+-- claves foráneas apagadas (ver db/mod.rs) y los ids se conservan, por lo que
+-- las tablas que apuntan a products siguen siendo válidas.
+
+CREATE TABLE products_new (
+  id INTEGER PRIMARY KEY AUTOINCREMENT,
+  code TEXT,
+  name TEXT NOT NULL,
+  category_id INTEGER,
+  price REAL NOT NULL DEFAULT 0,
+  cost REAL NOT NULL DEFAULT 0,
+  stock INTEGER NOT NULL DEFAULT 0,
+  min_stock INTEGER DEFAULT 5,
+  unit TEXT DEFAULT 'Unidades',
+  image_url TEXT,
+  is_active INTEGER DEFAULT 1,
+  created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+  store_id INTEGER REFERENCES stores(id),
+  style_id INTEGER REFERENCES product_styles(id),
+  size TEXT,
+  color TEXT,
+  barcode TEXT,
+  price_override REAL,
+  FOREIGN KEY (category_id) REFERENCES categories(id) ON DELETE SET NULL
+);
+
+INSERT INTO products_new (id, code, name, category_id, price, cost, stock, min_stock, unit, image_url,
+                          is_active, created_at, store_id, style_id, size, color, barcode, price_override)
+SELECT id, code, name, category_id, price, cost, stock, min_stock, unit, image_url,
+       is_active, created_at, store_id, style_id, size, color, barcode, price_override
+FROM products;
+
+DROP TABLE products;
+ALTER TABLE products_new RENAME TO products;
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_store_code
+    ON products(store_id, code)
+    WHERE code IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_products_style ON products(style_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_style_size_color
+    ON products(style_id, COALESCE(size, ''), COALESCE(color, ''))
+    WHERE style_id IS NOT NULL;
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_store_barcode
+    ON products(store_id, barcode)
+    WHERE barcode IS NOT NULL;
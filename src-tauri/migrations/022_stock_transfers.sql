@@ -0,0 +1,49 @@
+-- 022_stock_transfers.sql
+-- Transferencias entre tiendas: el origen descuenta al despachar y el destino
+-- suma al recibir. Los productos se emparejan por código en la tienda destino.
+
+/* =========================
+   STOCK TRANSFERS
+   status: draft | dispatched | received | discrepancy | cancelled
+   discrepancy: se recibió una cantidad distinta a la enviada
+========================= */
+CREATE TABLE IF NOT EXISTS stock_transfers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    origin_store_id INTEGER NOT NULL,
+    destination_store_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'draft',
+    notes TEXT,
+    created_by INTEGER NOT NULL,
+    dispatched_by INTEGER,
+    dispatched_at TEXT,
+    received_by INTEGER,
+    received_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (origin_store_id) REFERENCES stores(id),
+    FOREIGN KEY (destination_store_id) REFERENCES stores(id),
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    FOREIGN KEY (dispatched_by) REFERENCES users(id),
+    FOREIGN KEY (received_by) REFERENCES users(id)
+);
+
+/* =========================
+   STOCK TRANSFER ITEMS
+   destination_product_id se completa al recibir
+========================= */
+CREATE TABLE IF NOT EXISTS stock_transfer_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    transfer_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    product_code TEXT NOT NULL,
+    product_name TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    received_quantity INTEGER,
+    destination_product_id INTEGER,
+    FOREIGN KEY (transfer_id) REFERENCES stock_transfers(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (destination_product_id) REFERENCES products(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_stock_transfers_origin ON stock_transfers(origin_store_id, status);
+CREATE INDEX IF NOT EXISTS idx_stock_transfers_destination ON stock_transfers(destination_store_id, status);
+CREATE INDEX IF NOT EXISTS idx_stock_transfer_items_transfer ON stock_transfer_items(transfer_id);
@@ -0,0 +1,74 @@
+-- 023_inventory_counts.sql
+-- Toma de inventario físico: se congela el stock esperado al abrir el conteo,
+-- se escanean códigos (en una o varias pasadas) y al aprobar se ajusta la
+-- diferencia. movement_watermark es el último movimiento del kardex al abrir:
+-- las ventas posteriores se descuentan del esperado hasta el momento en que
+-- se contó cada producto, para que no aparezcan como faltantes.
+
+/* =========================
+   INVENTORY COUNTS
+   status: open | approved | cancelled
+========================= */
+CREATE TABLE IF NOT EXISTS inventory_counts (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    store_id INTEGER NOT NULL,
+    status TEXT NOT NULL DEFAULT 'open',
+    notes TEXT,
+    current_pass INTEGER NOT NULL DEFAULT 1,
+    movement_watermark INTEGER NOT NULL DEFAULT 0,
+    created_by INTEGER NOT NULL,
+    approved_by INTEGER,
+    approved_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (created_by) REFERENCES users(id),
+    FOREIGN KEY (approved_by) REFERENCES users(id)
+);
+
+-- Un solo conteo abierto por tienda
+CREATE UNIQUE INDEX IF NOT EXISTS idx_inventory_counts_open
+    ON inventory_counts(store_id)
+    WHERE status = 'open';
+
+/* =========================
+   INVENTORY COUNT ITEMS
+   counted: NULL mientras el producto no se escanee
+   counted_pass: pasada de la que sale counted (la última que lo escaneó)
+   scan_watermark: último movimiento del kardex al primer escaneo de esa pasada
+   final_expected: esperado congelado al aprobar
+========================= */
+CREATE TABLE IF NOT EXISTS inventory_count_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    count_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    snapshot_stock INTEGER NOT NULL DEFAULT 0,
+    unit_cost INTEGER NOT NULL DEFAULT 0,
+    counted INTEGER,
+    counted_pass INTEGER,
+    scan_watermark INTEGER,
+    final_expected INTEGER,
+    UNIQUE (count_id, product_id),
+    FOREIGN KEY (count_id) REFERENCES inventory_counts(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+/* =========================
+   INVENTORY COUNT SCANS
+   Bitácora de cada lectura (quantity negativa corrige una lectura errada)
+========================= */
+CREATE TABLE IF NOT EXISTS inventory_count_scans (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    count_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    code TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    pass INTEGER NOT NULL,
+    user_id INTEGER NOT NULL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (count_id) REFERENCES inventory_counts(id) ON DELETE CASCADE,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_inventory_count_items_count ON inventory_count_items(count_id);
+CREATE INDEX IF NOT EXISTS idx_inventory_count_scans_count ON inventory_count_scans(count_id);
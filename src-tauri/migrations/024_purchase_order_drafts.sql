@@ -0,0 +1,8 @@
+-- 024_purchase_order_drafts.sql
+-- Órdenes de compra en borrador (p. ej. generadas desde las sugerencias de
+-- reposición): no mueven stock ni registran gasto hasta recibirse.
+-- Las órdenes existentes ya fueron recibidas.
+
+ALTER TABLE purchase_orders ADD COLUMN status TEXT NOT NULL DEFAULT 'received'; -- 'draft', 'received'
+
+CREATE INDEX IF NOT EXISTS idx_purchase_orders_status ON purchase_orders(store_id, status);
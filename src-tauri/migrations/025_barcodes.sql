@@ -0,0 +1,14 @@
+-- 025_barcodes.sql
+-- Códigos de barras internos: correlativo por tienda y simbología.
+-- EAN-13 usa el rango 20 (circulación restringida, uso interno de la tienda):
+-- 20 + tienda (2 dígitos) + correlativo (8 dígitos) + dígito verificador.
+
+CREATE TABLE IF NOT EXISTS barcode_sequences (
+    store_id INTEGER NOT NULL,
+    symbology TEXT NOT NULL, -- 'ean13', 'code128'
+    last_value INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (store_id, symbology),
+    FOREIGN KEY (store_id) REFERENCES stores(id)
+);
+
+INSERT OR IGNORE INTO app_config (key, value) VALUES ('barcode_symbology', 'ean13');
@@ -0,0 +1,75 @@
+-- 026_product_search.sql
+-- Búsqueda de texto completo de productos (FTS5).
+-- El tokenizador unicode61 con remove_diacritics ignora tildes y mayúsculas,
+-- así "pantalon" encuentra "Pantalón". Los atributos se indexan con su
+-- etiqueta ("talla 32 color azul") para que consultas como
+-- "short jean azul talla 32" coincidan palabra por palabra.
+
+/* =========================
+   SEARCH SOURCE
+   Texto indexado por producto; usado por el llenado inicial y los triggers.
+========================= */
+CREATE VIEW IF NOT EXISTS product_search_source AS
+SELECT
+    p.id,
+    p.name,
+    TRIM(COALESCE(p.code, '') || ' ' || COALESCE(p.barcode, '')) AS code,
+    COALESCE(c.name, '') AS category,
+    TRIM(
+        COALESCE('talla ' || p.size, '') || ' ' ||
+        COALESCE('color ' || p.color, '') || ' ' ||
+        COALESCE(p.unit, '')
+    ) AS attributes
+FROM products p
+LEFT JOIN categories c ON c.id = p.category_id;
+
+/* =========================
+   FTS INDEX
+   rowid = products.id
+========================= */
+CREATE VIRTUAL TABLE IF NOT EXISTS products_fts USING fts5(
+    name,
+    code,
+    category,
+    attributes,
+    tokenize = "unicode61 remove_diacritics 2",
+    prefix = '2 3'
+);
+
+INSERT INTO products_fts (rowid, name, code, category, attributes)
+SELECT id, name, code, category, attributes FROM product_search_source;
+
+/* =========================
+   SYNC TRIGGERS
+========================= */
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_insert
+AFTER INSERT ON products
+BEGIN
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_update
+AFTER UPDATE OF name, code, barcode, category_id, size, color, unit ON products
+BEGIN
+    DELETE FROM products_fts WHERE rowid = OLD.id;
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_delete
+AFTER DELETE ON products
+BEGIN
+    DELETE FROM products_fts WHERE rowid = OLD.id;
+END;
+
+-- Renombrar una categoría reindexa sus productos
+CREATE TRIGGER IF NOT EXISTS trg_categories_fts_update
+AFTER UPDATE OF name ON categories
+BEGIN
+    DELETE FROM products_fts
+    WHERE rowid IN (SELECT id FROM products WHERE category_id = NEW.id);
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source
+    WHERE id IN (SELECT id FROM products WHERE category_id = NEW.id);
+END;
@@ -0,0 +1,15 @@
+-- 027_product_images.sql
+-- Imágenes de producto guardadas localmente en la carpeta de datos de la app
+-- (images/<hash>.<ext> y su miniatura en images/thumbs/<hash>.png), para que
+-- el catálogo funcione sin conexión. El hash SHA-256 del contenido es la
+-- clave: la misma foto subida dos veces se guarda una sola vez.
+-- products.image_url y product_styles.image_url guardan 'local-image:<hash>'.
+
+CREATE TABLE IF NOT EXISTS product_images (
+    hash TEXT PRIMARY KEY,
+    extension TEXT NOT NULL, -- 'jpg', 'png', 'gif', 'bmp', 'tiff'
+    width INTEGER NOT NULL,
+    height INTEGER NOT NULL,
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,119 @@
+-- 028_category_tree.sql
+-- Categorías jerárquicas ("Pantalones > Jeans > Slim") y opcionalmente
+-- propias de una tienda (store_id NULL = compartida por todas).
+-- El nombre deja de ser único global: se repite entre tiendas o ramas, pero
+-- no entre hermanos del mismo ámbito. SQLite no permite quitar el UNIQUE, así
+-- que se reconstruye la tabla; antes se sueltan la vista y los triggers de
+-- búsqueda que la usan (el RENAME valida el esquema) y se recrean al final
+-- indexando la ruta completa de la categoría.
+
+DROP TRIGGER IF EXISTS trg_products_fts_insert;
+DROP TRIGGER IF EXISTS trg_products_fts_update;
+DROP TRIGGER IF EXISTS trg_products_fts_delete;
+DROP TRIGGER IF EXISTS trg_categories_fts_update;
+DROP VIEW IF EXISTS product_search_source;
+
+/* =========================
+   CATEGORIES
+========================= */
+CREATE TABLE categories_new (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    parent_id INTEGER REFERENCES categories(id),
+    store_id INTEGER REFERENCES stores(id), -- NULL = todas las tiendas
+    sort_order INTEGER NOT NULL DEFAULT 0   -- orden entre hermanos
+);
+
+INSERT INTO categories_new (id, name) SELECT id, name FROM categories;
+
+DROP TABLE categories;
+ALTER TABLE categories_new RENAME TO categories;
+
+CREATE INDEX IF NOT EXISTS idx_categories_parent ON categories(parent_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_categories_sibling_name
+    ON categories(COALESCE(store_id, 0), COALESCE(parent_id, 0), name COLLATE NOCASE);
+
+/* =========================
+   TREE VIEWS
+========================= */
+-- Cada categoría con todos sus ancestros (incluida ella misma, depth 0)
+CREATE VIEW IF NOT EXISTS category_closure AS
+WITH RECURSIVE closure(ancestor_id, id, depth) AS (
+    SELECT id, id, 0 FROM categories
+    UNION ALL
+    SELECT cl.ancestor_id, c.id, cl.depth + 1
+    FROM categories c
+    INNER JOIN closure cl ON c.parent_id = cl.id
+)
+SELECT ancestor_id, id, depth FROM closure;
+
+-- Ruta legible de cada categoría: "Pantalones > Jeans > Slim"
+CREATE VIEW IF NOT EXISTS category_paths AS
+WITH RECURSIVE paths(id, path, depth) AS (
+    SELECT id, name, 0 FROM categories WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, p.path || ' > ' || c.name, p.depth + 1
+    FROM categories c
+    INNER JOIN paths p ON c.parent_id = p.id
+)
+SELECT id, path, depth FROM paths;
+
+/* =========================
+   SEARCH SOURCE (ver 026)
+========================= */
+CREATE VIEW IF NOT EXISTS product_search_source AS
+SELECT
+    p.id,
+    p.name,
+    TRIM(COALESCE(p.code, '') || ' ' || COALESCE(p.barcode, '')) AS code,
+    COALESCE(cp.path, '') AS category,
+    TRIM(
+        COALESCE('talla ' || p.size, '') || ' ' ||
+        COALESCE('color ' || p.color, '') || ' ' ||
+        COALESCE(p.unit, '')
+    ) AS attributes
+FROM products p
+LEFT JOIN category_paths cp ON cp.id = p.category_id;
+
+DELETE FROM products_fts;
+INSERT INTO products_fts (rowid, name, code, category, attributes)
+SELECT id, name, code, category, attributes FROM product_search_source;
+
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_insert
+AFTER INSERT ON products
+BEGIN
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_update
+AFTER UPDATE OF name, code, barcode, category_id, size, color, unit ON products
+BEGIN
+    DELETE FROM products_fts WHERE rowid = OLD.id;
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source WHERE id = NEW.id;
+END;
+
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_delete
+AFTER DELETE ON products
+BEGIN
+    DELETE FROM products_fts WHERE rowid = OLD.id;
+END;
+
+-- Renombrar o mover una categoría reindexa los productos de toda su rama
+CREATE TRIGGER IF NOT EXISTS trg_categories_fts_update
+AFTER UPDATE OF name, parent_id ON categories
+BEGIN
+    DELETE FROM products_fts
+    WHERE rowid IN (
+        SELECT p.id FROM products p
+        INNER JOIN category_closure cc ON cc.id = p.category_id
+        WHERE cc.ancestor_id = NEW.id
+    );
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT s.id, s.name, s.code, s.category, s.attributes
+    FROM product_search_source s
+    INNER JOIN products p ON p.id = s.id
+    INNER JOIN category_closure cc ON cc.id = p.category_id
+    WHERE cc.ancestor_id = NEW.id;
+END;
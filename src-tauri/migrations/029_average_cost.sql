@@ -0,0 +1,51 @@
+-- 029_average_cost.sql
+-- Costo promedio ponderado: products.cost (por producto y tienda) deja de
+-- sobrescribirse con el costo del último lote y se recalcula en cada ingreso
+-- con el stock existente. Cada cambio queda en el historial, y cada línea de
+-- venta guarda el costo vigente al momento de vender (costo de ventas exacto).
+
+/* =========================
+   PRODUCT COST HISTORY
+   reason: purchase | transfer | manual | import
+   quantity / unit_cost: unidades y costo del ingreso (NULL si fue manual)
+   stock_before: stock físico antes del ingreso
+========================= */
+CREATE TABLE IF NOT EXISTS product_cost_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL,
+    store_id INTEGER NOT NULL,
+    previous_cost INTEGER NOT NULL, -- céntimos
+    new_cost INTEGER NOT NULL,      -- céntimos
+    quantity INTEGER,
+    unit_cost INTEGER,              -- céntimos
+    stock_before INTEGER,
+    reason TEXT NOT NULL,
+    document_type TEXT,
+    document_id INTEGER,
+    user_id INTEGER,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_cost_history_product ON product_cost_history(product_id, created_at);
+
+/* =========================
+   ORDER ITEMS
+   unit_cost: costo unitario del producto al vender (céntimos)
+========================= */
+ALTER TABLE order_items ADD COLUMN unit_cost INTEGER;
+
+-- Las ventas anteriores no guardaron su costo: se usa el costo actual
+UPDATE order_items SET unit_cost = (
+    SELECT CAST(p.cost AS INTEGER) FROM products p WHERE p.id = order_items.product_id
+);
+
+/* =========================
+   STOCK TRANSFER ITEMS
+   unit_cost: costo en la tienda de origen al despachar (céntimos); la
+   tienda destino lo promedia con su stock al recibir
+========================= */
+ALTER TABLE stock_transfer_items ADD COLUMN unit_cost INTEGER;
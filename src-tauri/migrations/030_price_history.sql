@@ -0,0 +1,64 @@
+-- 030_price_history.sql
+-- Historial de precios de venta y cambios programados.
+-- Cada cambio de products.price queda registrado con el precio anterior, el
+-- nuevo, el usuario y su origen. Los cambios programados (p. ej. precios de
+-- temporada desde el lunes) se aplican solos al llegar su fecha. Las órdenes
+-- de compra ya no cambian el precio de productos existentes: el precio del
+-- lote queda pendiente hasta que alguien lo confirme.
+
+/* =========================
+   PRODUCT PRICE HISTORY
+   reason: manual | purchase_order | scheduled | import | style
+========================= */
+CREATE TABLE IF NOT EXISTS product_price_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL,
+    store_id INTEGER NOT NULL,
+    previous_price INTEGER NOT NULL, -- céntimos
+    new_price INTEGER NOT NULL,      -- céntimos
+    reason TEXT NOT NULL,
+    document_type TEXT,
+    document_id INTEGER,
+    user_id INTEGER,
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_price_history_product ON product_price_history(product_id, created_at);
+
+/* =========================
+   SCHEDULED PRICE CHANGES
+   status: pending | applied | cancelled
+   effective_at: fecha y hora local desde la que rige ('YYYY-MM-DD HH:MM:SS')
+========================= */
+CREATE TABLE IF NOT EXISTS scheduled_price_changes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    product_id INTEGER NOT NULL,
+    store_id INTEGER NOT NULL,
+    new_price INTEGER NOT NULL, -- céntimos
+    effective_at TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending',
+    purchase_order_item_id INTEGER, -- si viene de confirmar una orden de compra
+    note TEXT,
+    created_by INTEGER,
+    created_at TEXT NOT NULL DEFAULT (datetime('now', 'localtime')),
+    applied_at TEXT,
+    FOREIGN KEY (product_id) REFERENCES products(id),
+    FOREIGN KEY (store_id) REFERENCES stores(id),
+    FOREIGN KEY (purchase_order_item_id) REFERENCES purchase_order_items(id),
+    FOREIGN KEY (created_by) REFERENCES users(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_scheduled_price_changes_due ON scheduled_price_changes(status, effective_at);
+CREATE INDEX IF NOT EXISTS idx_scheduled_price_changes_product ON scheduled_price_changes(product_id, status);
+
+/* =========================
+   PURCHASE ORDER ITEMS
+   price_status: NULL (el precio no cambia) | pending | applied | scheduled | discarded
+========================= */
+ALTER TABLE purchase_order_items ADD COLUMN price_status TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_purchase_order_items_price_status ON purchase_order_items(price_status);
@@ -0,0 +1,226 @@
+-- 031_product_catalog.sql
+-- Catálogo global de productos separado del inventario de cada tienda.
+-- catalog_products guarda la identidad del artículo, compartida por todas las
+-- tiendas: código (único global), nombre, unidad, imagen, código de barras,
+-- talla, color y precio base. products queda como el inventario por tienda
+-- (una fila por artículo y tienda, con sus ids de siempre): stock, stock
+-- mínimo, costo promedio, categoría, precio local opcional y activo/inactivo.
+-- products conserva una copia de los datos del catálogo que mantienen los
+-- triggers, así las consultas existentes siguen funcionando sin cambios:
+--   * editar la identidad en una tienda la cambia en el catálogo y en las demás
+--   * crear un producto con un código que ya existe en el catálogo lo enlaza
+--     a ese artículo (y toma sus datos) en lugar de duplicarlo; la aplicación
+--     rechaza el alta si los datos no coinciden con los del artículo
+--   * products.price es el precio vigente en la tienda: local_price si tiene
+--     uno, si no el precio base (las variantes siguen con el de su estilo)
+-- Migración: las filas con el mismo código en distintas tiendas pasan a ser
+-- un solo artículo si coinciden en nombre, unidad, imagen, talla y color. Si
+-- difieren no se mezclan: cada versión queda como un artículo aparte y, salvo
+-- la de la fila más antigua, sin código en el catálogo (el código del catálogo
+-- es único). Las tiendas conservan su código en products, que es el que
+-- imprimen en etiquetas y escanean en caja; unir esas versiones queda a cargo
+-- del usuario. Para revisarlas:
+--   SELECT p.code, p.store_id, p.name, p.catalog_id FROM products p
+--   JOIN catalog_products c ON c.id = p.catalog_id
+--   WHERE c.code IS NULL AND p.code IS NOT NULL ORDER BY p.code, p.store_id;
+
+/* =========================
+   CATALOG PRODUCTS
+========================= */
+CREATE TABLE IF NOT EXISTS catalog_products (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    code TEXT,
+    name TEXT NOT NULL,
+    unit TEXT DEFAULT 'Unidades',
+    image_url TEXT,
+    barcode TEXT,
+    size TEXT,
+    color TEXT,
+    price INTEGER NOT NULL DEFAULT 0, -- céntimos; precio base
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_catalog_products_code
+    ON catalog_products(code)
+    WHERE code IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_catalog_products_barcode ON catalog_products(barcode);
+
+-- Artículo de cada fila: el de la fila más antigua con el mismo código y los
+-- mismos datos; sin código, uno por fila
+CREATE TEMP TABLE catalog_backfill AS
+SELECT p.id AS product_id,
+       CASE WHEN p.code IS NULL THEN p.id ELSE (
+           SELECT MIN(q.id) FROM products q
+           WHERE q.code = p.code AND q.name IS p.name AND q.unit IS p.unit
+             AND q.image_url IS p.image_url AND q.size IS p.size AND q.color IS p.color
+       ) END AS catalog_id
+FROM products p;
+
+INSERT INTO catalog_products (id, code, name, unit, image_url, barcode, size, color, price, created_at)
+SELECT
+    p.id,
+    CASE
+        WHEN p.id = (SELECT MIN(q.id) FROM products q WHERE q.code = p.code) THEN p.code
+    END,
+    p.name, p.unit, p.image_url,
+    COALESCE(p.barcode, (
+        SELECT q.barcode FROM products q
+        INNER JOIN catalog_backfill b ON b.product_id = q.id
+        WHERE b.catalog_id = p.id AND q.barcode IS NOT NULL
+        ORDER BY q.id LIMIT 1
+    )),
+    p.size, p.color, CAST(p.price AS INTEGER), p.created_at
+FROM products p
+WHERE p.id IN (SELECT catalog_id FROM catalog_backfill)
+ORDER BY p.id;
+
+/* =========================
+   PRODUCTS (inventario por tienda)
+   catalog_id: artículo del catálogo
+   local_price: precio propio de la tienda (céntimos); NULL = precio base
+========================= */
+ALTER TABLE products ADD COLUMN catalog_id INTEGER REFERENCES catalog_products(id);
+ALTER TABLE products ADD COLUMN local_price INTEGER;
+
+UPDATE products SET catalog_id = (
+    SELECT b.catalog_id FROM catalog_backfill b WHERE b.product_id = products.id
+);
+
+DROP TABLE catalog_backfill;
+
+-- El código de barras del artículo solo si está libre en la tienda
+UPDATE products SET barcode = (SELECT c.barcode FROM catalog_products c WHERE c.id = products.catalog_id)
+WHERE barcode IS NULL
+  AND NOT EXISTS (
+      SELECT 1 FROM products d
+      WHERE d.store_id = products.store_id
+        AND d.barcode = (SELECT c.barcode FROM catalog_products c WHERE c.id = products.catalog_id)
+  );
+
+UPDATE products SET local_price = CAST(price AS INTEGER)
+WHERE style_id IS NULL
+  AND CAST(price AS INTEGER) <> (SELECT c.price FROM catalog_products c WHERE c.id = products.catalog_id);
+
+CREATE INDEX IF NOT EXISTS idx_products_catalog ON products(catalog_id);
+CREATE UNIQUE INDEX IF NOT EXISTS idx_products_catalog_store ON products(catalog_id, store_id);
+
+/* =========================
+   SYNC TRIGGERS
+========================= */
+-- El alta de un producto ahora puede reescribir su nombre dentro del mismo
+-- INSERT (trigger de abajo) y el trigger de búsqueda de UPDATE lo indexa
+-- antes que el de INSERT: este último debe tolerar que la fila ya exista.
+DROP TRIGGER IF EXISTS trg_products_fts_insert;
+CREATE TRIGGER IF NOT EXISTS trg_products_fts_insert
+AFTER INSERT ON products
+BEGIN
+    DELETE FROM products_fts WHERE rowid = NEW.id;
+    INSERT INTO products_fts (rowid, name, code, category, attributes)
+    SELECT id, name, code, category, attributes FROM product_search_source WHERE id = NEW.id;
+END;
+
+-- Producto nuevo sin artículo: se enlaza al del mismo código o se crea uno.
+-- Si el artículo ya existía, sus datos mandan sobre los del alta.
+CREATE TRIGGER IF NOT EXISTS trg_products_catalog_insert
+AFTER INSERT ON products
+WHEN NEW.catalog_id IS NULL
+BEGIN
+    INSERT INTO catalog_products (code, name, unit, image_url, barcode, size, color, price)
+    SELECT NEW.code, NEW.name, NEW.unit, NEW.image_url, NEW.barcode, NEW.size, NEW.color, CAST(NEW.price AS INTEGER)
+    WHERE NEW.code IS NULL
+       OR NOT EXISTS (SELECT 1 FROM catalog_products WHERE code = NEW.code);
+
+    UPDATE products SET catalog_id = COALESCE(
+        (SELECT id FROM catalog_products WHERE code = NEW.code),
+        last_insert_rowid()
+    )
+    WHERE id = NEW.id;
+
+    UPDATE products SET
+        name = c.name,
+        unit = c.unit,
+        image_url = COALESCE(c.image_url, products.image_url),
+        size = c.size,
+        color = c.color,
+        barcode = CASE
+            WHEN c.barcode IS NULL THEN products.barcode
+            WHEN EXISTS (SELECT 1 FROM products d WHERE d.store_id = products.store_id AND d.barcode = c.barcode AND d.id <> products.id)
+                THEN products.barcode
+            ELSE c.barcode
+        END,
+        local_price = CASE
+            WHEN products.style_id IS NULL AND CAST(products.price AS INTEGER) <> c.price
+                THEN CAST(products.price AS INTEGER)
+        END
+    FROM catalog_products c
+    WHERE c.id = products.catalog_id AND products.id = NEW.id;
+END;
+
+-- Editar la identidad en una tienda la cambia en el catálogo. El código de
+-- barras solo sube si cambió en esta fila: una tienda que conserva el suyo
+-- (ver abajo) no debe pisar el del catálogo al editar otro dato. Un artículo
+-- sin código en el catálogo (versión separada en la migración) lo toma solo
+-- si ningún otro artículo lo usa...
+CREATE TRIGGER IF NOT EXISTS trg_products_catalog_update
+AFTER UPDATE OF code, name, unit, image_url, barcode, size, color ON products
+WHEN NEW.catalog_id IS NOT NULL
+BEGIN
+    UPDATE catalog_products SET
+        code = CASE
+            WHEN code IS NULL AND EXISTS (SELECT 1 FROM catalog_products d WHERE d.code = NEW.code AND d.id <> NEW.catalog_id)
+                THEN NULL
+            ELSE NEW.code
+        END,
+        name = NEW.name,
+        unit = NEW.unit,
+        image_url = NEW.image_url,
+        barcode = CASE WHEN NEW.barcode IS NOT OLD.barcode THEN NEW.barcode ELSE barcode END,
+        size = NEW.size,
+        color = NEW.color
+    WHERE id = NEW.catalog_id
+      AND (code IS NOT NEW.code OR name IS NOT NEW.name OR unit IS NOT NEW.unit
+           OR image_url IS NOT NEW.image_url
+           OR (NEW.barcode IS NOT OLD.barcode AND barcode IS NOT NEW.barcode)
+           OR size IS NOT NEW.size OR color IS NOT NEW.color);
+END;
+
+-- ...y el catálogo la reparte a todas las tiendas, con el precio base donde
+-- no hay precio local. El código solo baja si cambió en el catálogo, así las
+-- tiendas de un artículo sin código conservan el suyo. El código de barras es
+-- único por tienda: donde otro producto ya lo usa, la tienda conserva el suyo
+CREATE TRIGGER IF NOT EXISTS trg_catalog_products_update
+AFTER UPDATE ON catalog_products
+BEGIN
+    UPDATE products SET
+        code = CASE WHEN NEW.code IS NOT OLD.code THEN NEW.code ELSE products.code END,
+        name = NEW.name,
+        unit = NEW.unit,
+        image_url = NEW.image_url,
+        barcode = CASE
+            WHEN EXISTS (SELECT 1 FROM products d WHERE d.store_id = products.store_id AND d.barcode = NEW.barcode AND d.id <> products.id)
+                THEN products.barcode
+            ELSE NEW.barcode
+        END,
+        size = NEW.size,
+        color = NEW.color
+    WHERE catalog_id = NEW.id
+      AND ((NEW.code IS NOT OLD.code AND code IS NOT NEW.code) OR name IS NOT NEW.name OR unit IS NOT NEW.unit
+           OR image_url IS NOT NEW.image_url OR barcode IS NOT NEW.barcode
+           OR size IS NOT NEW.size OR color IS NOT NEW.color);
+
+    UPDATE products SET price = NEW.price
+    WHERE catalog_id = NEW.id AND style_id IS NULL AND local_price IS NULL
+      AND CAST(price AS INTEGER) <> NEW.price;
+END;
+
+-- Un precio fijado en la tienda queda como precio local si difiere del base
+CREATE TRIGGER IF NOT EXISTS trg_products_local_price
+AFTER UPDATE OF price ON products
+WHEN NEW.style_id IS NULL AND NEW.catalog_id IS NOT NULL
+BEGIN
+    UPDATE products SET local_price = (
+        SELECT CASE WHEN c.price = CAST(NEW.price AS INTEGER) THEN NULL ELSE CAST(NEW.price AS INTEGER) END
+        FROM catalog_products c WHERE c.id = NEW.catalog_id
+    )
+    WHERE id = NEW.id;
+END;
@@ -0,0 +1,76 @@
+-- 032_product_kits.sql
+-- Packs (kits): un producto de la tienda formado por otros productos de la
+-- misma tienda en cantidades fijas y vendido a un precio de pack.
+-- El pack no tiene stock propio: su disponibilidad es la del componente más
+-- escaso. Al venderlo se descuenta el stock de cada componente y la línea de
+-- venta guarda sus componentes con su costo y su parte del precio del pack
+-- (repartido según el precio de lista de cada componente), para reportes y
+-- análisis de margen.
+
+/* =========================
+   PRODUCTS
+   is_kit: 1 = pack; su stock se calcula desde los componentes
+========================= */
+ALTER TABLE products ADD COLUMN is_kit INTEGER NOT NULL DEFAULT 0;
+
+/* =========================
+   PRODUCT KIT COMPONENTS
+   quantity: unidades del componente en un pack
+========================= */
+CREATE TABLE IF NOT EXISTS product_kit_components (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    kit_id INTEGER NOT NULL,
+    component_id INTEGER NOT NULL,
+    quantity INTEGER NOT NULL CHECK (quantity > 0),
+    UNIQUE (kit_id, component_id),
+    FOREIGN KEY (kit_id) REFERENCES products(id),
+    FOREIGN KEY (component_id) REFERENCES products(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_product_kit_components_component ON product_kit_components(component_id);
+
+-- El stock de un pack no se mueve: los ingresos, ajustes y traslados van a
+-- sus componentes
+CREATE TRIGGER IF NOT EXISTS trg_products_kit_stock
+BEFORE UPDATE OF stock ON products
+WHEN NEW.is_kit = 1 AND NEW.stock <> OLD.stock
+BEGIN
+    SELECT RAISE(ABORT, 'Los packs no tienen stock propio: mueva el stock de sus componentes');
+END;
+
+/* =========================
+   ORDER ITEM COMPONENTS
+   Componentes vendidos en una línea de pack
+   quantity: unidades del componente (packs vendidos × unidades por pack)
+   unit_cost: costo unitario del componente al vender (céntimos)
+   subtotal: parte del subtotal de la línea que le corresponde (céntimos)
+========================= */
+CREATE TABLE IF NOT EXISTS order_item_components (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    order_item_id INTEGER NOT NULL,
+    order_id INTEGER NOT NULL,
+    product_id INTEGER NOT NULL,
+    product_name TEXT NOT NULL,
+    quantity INTEGER NOT NULL,
+    unit_cost INTEGER,
+    subtotal INTEGER NOT NULL,
+    FOREIGN KEY (order_item_id) REFERENCES order_items(id),
+    FOREIGN KEY (order_id) REFERENCES orders(id),
+    FOREIGN KEY (product_id) REFERENCES products(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_order_item_components_item ON order_item_components(order_item_id);
+CREATE INDEX IF NOT EXISTS idx_order_item_components_product ON order_item_components(product_id);
+
+/* =========================
+   ORDER PRODUCT LINES
+   Unidades vendidas por producto: las líneas normales y, en lugar de las
+   líneas de pack, sus componentes
+========================= */
+CREATE VIEW IF NOT EXISTS order_product_lines AS
+SELECT oi.order_id, oi.product_id, oi.quantity, oi.subtotal, oi.unit_cost
+FROM order_items oi
+WHERE NOT EXISTS (SELECT 1 FROM order_item_components oc WHERE oc.order_item_id = oi.id)
+UNION ALL
+SELECT oc.order_id, oc.product_id, oc.quantity, oc.subtotal, oc.unit_cost
+FROM order_item_components oc;
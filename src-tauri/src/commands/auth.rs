@@ -15,6 +15,24 @@ pub struct AppState {
     pub cash_service: crate::services::cash_service::CashService,
     pub purchase_order_service: crate::services::purchase_order_service::PurchaseOrderService,
     pub config_service: crate::services::config_service::ConfigService,
+    pub pdf_service: crate::services::pdf_service::PdfService,
+    pub quotation_service: crate::services::quotation_service::QuotationService,
+    pub layaway_service: crate::services::layaway_service::LayawayService,
+    pub stored_value_service: crate::services::stored_value_service::StoredValueService,
+    pub loyalty_service: crate::services::loyalty_service::LoyaltyService,
+    pub price_list_service: crate::services::price_list_service::PriceListService,
+    pub commission_service: crate::services::commission_service::CommissionService,
+    pub variant_service: crate::services::variant_service::VariantService,
+    pub stock_adjustment_service: crate::services::stock_adjustment_service::StockAdjustmentService,
+    pub stock_transfer_service: crate::services::stock_transfer_service::StockTransferService,
+    pub inventory_count_service: crate::services::inventory_count_service::InventoryCountService,
+    pub reorder_service: crate::services::reorder_service::ReorderService,
+    pub barcode_service: crate::services::barcode_service::BarcodeService,
+    pub product_import_service: crate::services::product_import_service::ProductImportService,
+    pub product_image_service: crate::services::product_image_service::ProductImageService,
+    pub price_change_service: crate::services::price_change_service::PriceChangeService,
+    pub catalog_service: crate::services::catalog_service::CatalogService,
+    pub kit_service: crate::services::kit_service::KitService,
 }
 
 #[tauri::command]
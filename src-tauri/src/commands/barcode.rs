@@ -0,0 +1,58 @@
+use crate::commands::auth::AppState;
+use crate::models::barcode::{AssignBarcodesPayload, AssignedBarcode, LabelRequest};
+use chrono::Local;
+use tauri::{AppHandle, Manager, State};
+
+#[tauri::command]
+pub async fn assign_barcodes(
+    state: State<'_, AppState>,
+    payload: AssignBarcodesPayload,
+) -> Result<Vec<AssignedBarcode>, String> {
+    state.barcode_service.assign_barcodes(payload).await
+}
+
+#[tauri::command]
+pub async fn set_barcode_symbology(
+    state: State<'_, AppState>,
+    symbology: String,
+) -> Result<(), String> {
+    state.barcode_service.set_symbology(&symbology).await
+}
+
+#[tauri::command]
+pub async fn generate_labels(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    store_id: i64,
+    items: Vec<LabelRequest>,
+    format: String, // "zpl" | "pdf"
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    let file_stem = format!("etiquetas_{}", Local::now().format("%Y%m%d_%H%M%S"));
+    state
+        .barcode_service
+        .generate_labels(store_id, &items, &format, &output_dir, &file_stem)
+        .await
+}
+
+#[tauri::command]
+pub async fn generate_purchase_order_labels(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    purchase_order_id: i64,
+    format: String, // "zpl" | "pdf"
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    state
+        .barcode_service
+        .generate_purchase_order_labels(purchase_order_id, &format, &output_dir)
+        .await
+}
@@ -1,5 +1,6 @@
 use crate::commands::auth::AppState;
 use crate::models::cash::{CashSession, CloseCashPayload, OpenCashPayload};
+use crate::models::money::Money;
 use tauri::State;
 
 #[tauri::command]
@@ -40,7 +41,7 @@ pub async fn add_cash_expense(
     state: State<'_, AppState>,
     session_id: i64,
     description: String,
-    amount: f64,
+    amount: Money,
     payment_method: String,
 ) -> Result<i64, String> {
     state
@@ -54,7 +55,7 @@ pub async fn add_cash_other_income(
     state: State<'_, AppState>,
     session_id: i64,
     description: String,
-    amount: f64,
+    amount: Money,
     payment_method: String,
 ) -> Result<i64, String> {
     state
@@ -102,7 +103,7 @@ pub async fn delete_expense(
 pub async fn add_expense_standalone(
     state: State<'_, AppState>,
     description: String,
-    amount: f64,
+    amount: Money,
     payment_method: String,
     category: Option<String>,
     supplier: Option<String>,
@@ -0,0 +1,48 @@
+use crate::commands::auth::AppState;
+use crate::models::catalog::{
+    AddCatalogProductToStorePayload, CatalogProduct, CatalogProductDetail, UpdateCatalogProductPayload,
+};
+use crate::models::money::Money;
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_catalog_products(
+    state: State<'_, AppState>,
+    search: Option<String>,
+) -> Result<Vec<CatalogProduct>, String> {
+    state.catalog_service.get_catalog_products(search).await
+}
+
+#[tauri::command]
+pub async fn get_catalog_product(state: State<'_, AppState>, id: i64) -> Result<CatalogProductDetail, String> {
+    state.catalog_service.get_catalog_product(id).await
+}
+
+#[tauri::command]
+pub async fn update_catalog_product(
+    state: State<'_, AppState>,
+    payload: UpdateCatalogProductPayload,
+) -> Result<CatalogProductDetail, String> {
+    state.catalog_service.update_catalog_product(payload).await
+}
+
+#[tauri::command]
+pub async fn add_catalog_product_to_store(
+    state: State<'_, AppState>,
+    payload: AddCatalogProductToStorePayload,
+) -> Result<i64, String> {
+    state.catalog_service.add_catalog_product_to_store(payload).await
+}
+
+#[tauri::command]
+pub async fn set_local_price(
+    state: State<'_, AppState>,
+    product_id: i64,
+    local_price: Option<Money>,
+    user_id: Option<i64>,
+) -> Result<(), String> {
+    state
+        .catalog_service
+        .set_local_price(product_id, local_price, user_id)
+        .await
+}
@@ -0,0 +1,57 @@
+use crate::commands::auth::AppState;
+use crate::models::commission::{CommissionReport, CommissionRule, SaveCommissionRulePayload};
+use tauri::{AppHandle, Manager, State};
+
+#[tauri::command]
+pub async fn get_commission_rules(
+    state: State<'_, AppState>,
+    store_id: i64,
+) -> Result<Vec<CommissionRule>, String> {
+    state.commission_service.get_rules(store_id).await
+}
+
+#[tauri::command]
+pub async fn save_commission_rule(
+    state: State<'_, AppState>,
+    payload: SaveCommissionRulePayload,
+) -> Result<i64, String> {
+    state.commission_service.save_rule(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_commission_rule(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.commission_service.delete_rule(id).await
+}
+
+#[tauri::command]
+pub async fn get_commission_report(
+    state: State<'_, AppState>,
+    store_id: i64,
+    date_from: String,
+    date_to: String,
+) -> Result<CommissionReport, String> {
+    state
+        .commission_service
+        .get_commission_report(store_id, date_from, date_to)
+        .await
+}
+
+#[tauri::command]
+pub async fn export_commission_report(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    store_id: i64,
+    date_from: String,
+    date_to: String,
+    format: String, // "csv" | "xlsx"
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    state
+        .commission_service
+        .export_commission_report(store_id, date_from, date_to, &format, &output_dir)
+        .await
+}
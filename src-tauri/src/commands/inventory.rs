@@ -1,17 +1,34 @@
 use crate::commands::auth::AppState;
-use crate::models::inventory::{Category, ProductWithCategory};
+use crate::models::inventory::{
+    Category, CategoryNode, ProductLookup, ProductSearchPage, ProductWithCategory,
+};
+use crate::models::money::Money;
+use crate::models::product_cost::ProductCostChange;
+use crate::models::stock_movement::Kardex;
 use tauri::State;
 
 // Categories CRUD
 
 #[tauri::command]
-pub async fn get_categories(state: State<'_, AppState>) -> Result<Vec<Category>, String> {
-    state.inventory_service.get_categories().await
+pub async fn get_categories(
+    state: State<'_, AppState>,
+    store_id: Option<i64>,
+) -> Result<Vec<CategoryNode>, String> {
+    state.inventory_service.get_categories(store_id).await
 }
 
+/// `store_id` makes the category private to that store; `None` shares it.
 #[tauri::command]
-pub async fn create_category(state: State<'_, AppState>, name: String) -> Result<Category, String> {
-    state.inventory_service.create_category(&name).await
+pub async fn create_category(
+    state: State<'_, AppState>,
+    name: String,
+    parent_id: Option<i64>,
+    store_id: Option<i64>,
+) -> Result<Category, String> {
+    state
+        .inventory_service
+        .create_category(&name, parent_id, store_id)
+        .await
 }
 
 #[tauri::command]
@@ -24,8 +41,42 @@ pub async fn update_category(
 }
 
 #[tauri::command]
-pub async fn delete_category(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.inventory_service.delete_category(id).await
+pub async fn move_category(
+    state: State<'_, AppState>,
+    id: i64,
+    parent_id: Option<i64>,
+    sort_order: Option<i64>,
+) -> Result<(), String> {
+    state
+        .inventory_service
+        .move_category(id, parent_id, sort_order)
+        .await
+}
+
+#[tauri::command]
+pub async fn reorder_categories(state: State<'_, AppState>, ids: Vec<i64>) -> Result<(), String> {
+    state.inventory_service.reorder_categories(&ids).await
+}
+
+#[tauri::command]
+pub async fn delete_category(
+    state: State<'_, AppState>,
+    id: i64,
+    reassign_to: Option<i64>,
+) -> Result<(), String> {
+    state.inventory_service.delete_category(id, reassign_to).await
+}
+
+#[tauri::command]
+pub async fn get_category_products(
+    state: State<'_, AppState>,
+    store_id: i64,
+    category_id: i64,
+) -> Result<Vec<ProductWithCategory>, String> {
+    state
+        .inventory_service
+        .get_category_products(store_id, category_id)
+        .await
 }
 
 // Products CRUD
@@ -35,18 +86,44 @@ pub async fn get_products(state: State<'_, AppState>, store_id: i64) -> Result<V
     state.inventory_service.get_products(store_id).await
 }
 
+#[tauri::command]
+pub async fn search_products(
+    state: State<'_, AppState>,
+    store_id: i64,
+    query: String,
+    page: Option<i64>,
+    page_size: Option<i64>,
+) -> Result<ProductSearchPage, String> {
+    state
+        .inventory_service
+        .search_products(store_id, &query, page, page_size)
+        .await
+}
+
+/// Scanner-driven lookup by barcode, code or scale/price label.
+#[tauri::command]
+pub async fn lookup_product(
+    state: State<'_, AppState>,
+    store_id: i64,
+    code: String,
+) -> Result<Option<ProductLookup>, String> {
+    state.inventory_service.lookup_product(store_id, &code).await
+}
+
 #[tauri::command]
 pub async fn create_product(
     state: State<'_, AppState>,
     code: Option<String>,
     name: String,
     category_id: Option<i64>,
-    price: f64,
-    cost: f64,
+    price: Money,
+    cost: Money,
     stock: i64,
+    min_stock: Option<i64>,
     unit: Option<String>,
     image_url: Option<String>,
     store_id: i64,
+    user_id: Option<i64>,
 ) -> Result<i64, String> {
     state
         .inventory_service
@@ -57,9 +134,11 @@ pub async fn create_product(
             price,
             cost,
             stock,
+            min_stock,
             unit.as_deref(),
             image_url.as_deref(),
             store_id,
+            user_id,
         )
         .await
 }
@@ -71,12 +150,13 @@ pub async fn update_product(
     code: Option<String>,
     name: String,
     category_id: Option<i64>,
-    price: f64,
-    cost: f64,
-    stock: i64,
+    price: Money,
+    cost: Money,
+    min_stock: Option<i64>,
     unit: Option<String>,
     image_url: Option<String>,
     store_id: i64,
+    user_id: Option<i64>,
 ) -> Result<(), String> {
     state
         .inventory_service
@@ -87,15 +167,49 @@ pub async fn update_product(
             category_id,
             price,
             cost,
-            stock,
+            min_stock,
             unit.as_deref(),
             image_url.as_deref(),
             store_id,
+            user_id,
         )
-        .await
+        .await?;
+    // A replaced picture may be left unused
+    cleanup_product_images(&state).await;
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn delete_product(state: State<'_, AppState>, id: i64) -> Result<(), String> {
-    state.inventory_service.delete_product(id).await
+    state.inventory_service.delete_product(id).await?;
+    cleanup_product_images(&state).await;
+    Ok(())
+}
+
+/// The product change already succeeded; a failed cleanup is retried next time.
+async fn cleanup_product_images(state: &AppState) {
+    if let Err(e) = state.product_image_service.cleanup_orphans().await {
+        log::warn!("No se pudieron limpiar las imágenes huérfanas: {}", e);
+    }
+}
+
+#[tauri::command]
+pub async fn get_kardex(
+    state: State<'_, AppState>,
+    product_id: i64,
+    date_from: String,
+    date_to: String,
+) -> Result<Kardex, String> {
+    state
+        .inventory_service
+        .get_kardex(product_id, date_from, date_to)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_cost_history(
+    state: State<'_, AppState>,
+    product_id: i64,
+) -> Result<Vec<ProductCostChange>, String> {
+    state.inventory_service.get_cost_history(product_id).await
 }
@@ -0,0 +1,71 @@
+use crate::commands::auth::AppState;
+use crate::models::inventory_count::{
+    InventoryCount, InventoryCountReport, ScanInventoryCountPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn start_inventory_count(
+    state: State<'_, AppState>,
+    store_id: i64,
+    user_id: i64,
+    notes: Option<String>,
+) -> Result<InventoryCount, String> {
+    state
+        .inventory_count_service
+        .start_count(store_id, user_id, notes)
+        .await
+}
+
+#[tauri::command]
+pub async fn scan_inventory_count(
+    state: State<'_, AppState>,
+    payload: ScanInventoryCountPayload,
+) -> Result<i64, String> {
+    state.inventory_count_service.scan(payload).await
+}
+
+#[tauri::command]
+pub async fn start_inventory_count_pass(
+    state: State<'_, AppState>,
+    count_id: i64,
+) -> Result<i64, String> {
+    state.inventory_count_service.start_pass(count_id).await
+}
+
+#[tauri::command]
+pub async fn get_inventory_count_report(
+    state: State<'_, AppState>,
+    count_id: i64,
+) -> Result<InventoryCountReport, String> {
+    state.inventory_count_service.get_report(count_id).await
+}
+
+#[tauri::command]
+pub async fn approve_inventory_count(
+    state: State<'_, AppState>,
+    count_id: i64,
+    manager_id: i64,
+    zero_uncounted: bool,
+) -> Result<i64, String> {
+    state
+        .inventory_count_service
+        .approve_count(count_id, manager_id, zero_uncounted)
+        .await
+}
+
+#[tauri::command]
+pub async fn cancel_inventory_count(
+    state: State<'_, AppState>,
+    count_id: i64,
+) -> Result<(), String> {
+    state.inventory_count_service.cancel_count(count_id).await
+}
+
+#[tauri::command]
+pub async fn get_inventory_counts(
+    state: State<'_, AppState>,
+    store_id: i64,
+) -> Result<Vec<InventoryCount>, String> {
+    state.inventory_count_service.get_counts(store_id).await
+}
@@ -0,0 +1,34 @@
+use crate::commands::auth::AppState;
+use crate::models::kit::{KitComponentSales, ProductKit, ProductKitDetail, SaveKitComponentsPayload};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_product_kits(state: State<'_, AppState>, store_id: i64) -> Result<Vec<ProductKit>, String> {
+    state.kit_service.get_product_kits(store_id).await
+}
+
+#[tauri::command]
+pub async fn get_product_kit(state: State<'_, AppState>, kit_id: i64) -> Result<ProductKitDetail, String> {
+    state.kit_service.get_product_kit(kit_id).await
+}
+
+#[tauri::command]
+pub async fn save_kit_components(
+    state: State<'_, AppState>,
+    payload: SaveKitComponentsPayload,
+) -> Result<Option<ProductKitDetail>, String> {
+    state.kit_service.save_kit_components(payload).await
+}
+
+#[tauri::command]
+pub async fn get_kit_component_sales(
+    state: State<'_, AppState>,
+    store_id: i64,
+    date_from: String,
+    date_to: String,
+) -> Result<Vec<KitComponentSales>, String> {
+    state
+        .kit_service
+        .get_kit_component_sales(store_id, date_from, date_to)
+        .await
+}
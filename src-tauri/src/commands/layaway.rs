@@ -0,0 +1,57 @@
+use crate::commands::auth::AppState;
+use crate::models::layaway::{
+    AddLayawayPaymentPayload, CreateLayawayPayload, Layaway, LayawayDetail,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_layaway(
+    state: State<'_, AppState>,
+    payload: CreateLayawayPayload,
+) -> Result<LayawayDetail, String> {
+    state.layaway_service.create_layaway(payload).await
+}
+
+#[tauri::command]
+pub async fn add_layaway_payment(
+    state: State<'_, AppState>,
+    payload: AddLayawayPaymentPayload,
+) -> Result<LayawayDetail, String> {
+    state.layaway_service.add_payment(payload).await
+}
+
+#[tauri::command]
+pub async fn cancel_layaway(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.layaway_service.cancel_layaway(id).await
+}
+
+#[tauri::command]
+pub async fn get_layaways(
+    state: State<'_, AppState>,
+    store_id: i64,
+    status: Option<String>,
+) -> Result<Vec<Layaway>, String> {
+    state
+        .layaway_service
+        .get_layaways(store_id, status.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_layaway_detail(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<Option<LayawayDetail>, String> {
+    state.layaway_service.get_layaway_detail(id).await
+}
+
+#[tauri::command]
+pub async fn set_layaway_expiry_days(state: State<'_, AppState>, days: i64) -> Result<(), String> {
+    if days <= 0 {
+        return Err("La vigencia debe ser de al menos un día".to_string());
+    }
+    state
+        .config_service
+        .set_config("layaway_expiry_days", &days.to_string())
+        .await
+}
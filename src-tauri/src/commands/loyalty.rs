@@ -0,0 +1,44 @@
+use crate::commands::auth::AppState;
+use crate::models::loyalty::{CategoryMultiplier, CustomerPoints, LoyaltySettings};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_customer_points(
+    state: State<'_, AppState>,
+    document: Option<String>,
+    phone: Option<String>,
+) -> Result<CustomerPoints, String> {
+    state.loyalty_service.get_customer_points(document, phone).await
+}
+
+#[tauri::command]
+pub async fn get_loyalty_settings(state: State<'_, AppState>) -> Result<LoyaltySettings, String> {
+    state.loyalty_service.get_settings().await
+}
+
+#[tauri::command]
+pub async fn update_loyalty_settings(
+    state: State<'_, AppState>,
+    settings: LoyaltySettings,
+) -> Result<(), String> {
+    state.loyalty_service.update_settings(settings).await
+}
+
+#[tauri::command]
+pub async fn get_loyalty_category_multipliers(
+    state: State<'_, AppState>,
+) -> Result<Vec<CategoryMultiplier>, String> {
+    state.loyalty_service.get_category_multipliers().await
+}
+
+#[tauri::command]
+pub async fn set_loyalty_category_multiplier(
+    state: State<'_, AppState>,
+    category_id: i64,
+    multiplier: f64,
+) -> Result<(), String> {
+    state
+        .loyalty_service
+        .set_category_multiplier(category_id, multiplier)
+        .await
+}
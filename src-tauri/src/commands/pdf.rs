@@ -0,0 +1,37 @@
+use crate::commands::auth::AppState;
+use tauri::{AppHandle, Manager, State};
+
+#[tauri::command]
+pub async fn generate_sale_pdf(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    sale_id: i64,
+    layout: String, // "ticket" | "a4"
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    state
+        .pdf_service
+        .generate_sale_pdf(sale_id, &layout, &output_dir)
+        .await
+}
+
+#[tauri::command]
+pub async fn generate_cash_session_report_pdf(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    session_id: i64,
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    state
+        .pdf_service
+        .generate_cash_session_report(session_id, &output_dir)
+        .await
+}
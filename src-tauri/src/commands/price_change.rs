@@ -0,0 +1,70 @@
+use crate::commands::auth::AppState;
+use crate::models::price_change::{
+    ConfirmPurchasePricesPayload, PendingPurchasePrice, ProductPriceChange,
+    SchedulePriceChangesPayload, ScheduledPriceChange,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_price_history(
+    state: State<'_, AppState>,
+    product_id: i64,
+) -> Result<Vec<ProductPriceChange>, String> {
+    state.price_change_service.get_price_history(product_id).await
+}
+
+#[tauri::command]
+pub async fn schedule_price_changes(
+    state: State<'_, AppState>,
+    payload: SchedulePriceChangesPayload,
+) -> Result<Vec<ScheduledPriceChange>, String> {
+    state.price_change_service.schedule_price_changes(payload).await
+}
+
+#[tauri::command]
+pub async fn get_scheduled_price_changes(
+    state: State<'_, AppState>,
+    store_id: i64,
+    status: Option<String>,
+) -> Result<Vec<ScheduledPriceChange>, String> {
+    state
+        .price_change_service
+        .get_scheduled_price_changes(store_id, status)
+        .await
+}
+
+#[tauri::command]
+pub async fn cancel_scheduled_price_change(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.price_change_service.cancel_scheduled_price_change(id).await
+}
+
+#[tauri::command]
+pub async fn get_pending_purchase_prices(
+    state: State<'_, AppState>,
+    store_id: i64,
+) -> Result<Vec<PendingPurchasePrice>, String> {
+    state
+        .price_change_service
+        .get_pending_purchase_prices(store_id)
+        .await
+}
+
+#[tauri::command]
+pub async fn confirm_purchase_prices(
+    state: State<'_, AppState>,
+    payload: ConfirmPurchasePricesPayload,
+) -> Result<usize, String> {
+    state.price_change_service.confirm_purchase_prices(payload).await
+}
+
+#[tauri::command]
+pub async fn discard_purchase_prices(
+    state: State<'_, AppState>,
+    store_id: i64,
+    item_ids: Vec<i64>,
+) -> Result<usize, String> {
+    state
+        .price_change_service
+        .discard_purchase_prices(store_id, item_ids)
+        .await
+}
@@ -0,0 +1,77 @@
+use crate::commands::auth::AppState;
+use crate::models::money::Money;
+use crate::models::price_list::{PriceList, PriceListItem};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_price_lists(state: State<'_, AppState>) -> Result<Vec<PriceList>, String> {
+    state.price_list_service.get_price_lists().await
+}
+
+#[tauri::command]
+pub async fn create_price_list(
+    state: State<'_, AppState>,
+    name: String,
+    description: Option<String>,
+) -> Result<i64, String> {
+    state.price_list_service.create_price_list(name, description).await
+}
+
+#[tauri::command]
+pub async fn update_price_list(
+    state: State<'_, AppState>,
+    id: i64,
+    name: String,
+    description: Option<String>,
+    is_active: bool,
+) -> Result<(), String> {
+    state
+        .price_list_service
+        .update_price_list(id, name, description, is_active)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_price_list_items(
+    state: State<'_, AppState>,
+    price_list_id: i64,
+    store_id: Option<i64>,
+) -> Result<Vec<PriceListItem>, String> {
+    state
+        .price_list_service
+        .get_price_list_items(price_list_id, store_id)
+        .await
+}
+
+#[tauri::command]
+pub async fn set_price_list_item(
+    state: State<'_, AppState>,
+    price_list_id: i64,
+    product_id: i64,
+    min_quantity: i64,
+    price: Money,
+) -> Result<(), String> {
+    state
+        .price_list_service
+        .set_price_list_item(price_list_id, product_id, min_quantity, price)
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_price_list_item(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.price_list_service.delete_price_list_item(id).await
+}
+
+#[tauri::command]
+pub async fn set_customer_price_list(
+    state: State<'_, AppState>,
+    document: Option<String>,
+    phone: Option<String>,
+    name: Option<String>,
+    price_list_id: Option<i64>,
+) -> Result<(), String> {
+    state
+        .price_list_service
+        .set_customer_price_list(document, phone, name, price_list_id)
+        .await
+}
@@ -0,0 +1,50 @@
+use crate::commands::auth::AppState;
+use crate::models::product_image::ProductImage;
+use tauri::State;
+
+#[tauri::command]
+pub async fn import_product_image(
+    state: State<'_, AppState>,
+    file_path: String,
+) -> Result<ProductImage, String> {
+    state.product_image_service.import_image(&file_path).await
+}
+
+/// Local files of an `image_url`; `None` for remote URLs or images not synced yet.
+#[tauri::command]
+pub async fn get_product_image(
+    state: State<'_, AppState>,
+    reference: String,
+) -> Result<Option<ProductImage>, String> {
+    state.product_image_service.get_image(&reference).await
+}
+
+// Master-data sync: the replica lists what it lacks, the Primary serves the
+// bytes and the replica stores them.
+
+#[tauri::command]
+pub async fn get_missing_product_images(state: State<'_, AppState>) -> Result<Vec<String>, String> {
+    state.product_image_service.get_missing_images().await
+}
+
+#[tauri::command]
+pub async fn get_product_image_data(
+    state: State<'_, AppState>,
+    hash: String,
+) -> Result<Vec<u8>, String> {
+    state.product_image_service.read_image(&hash).await
+}
+
+#[tauri::command]
+pub async fn save_synced_product_image(
+    state: State<'_, AppState>,
+    hash: String,
+    data: Vec<u8>,
+) -> Result<ProductImage, String> {
+    state.product_image_service.save_synced_image(&hash, &data).await
+}
+
+#[tauri::command]
+pub async fn cleanup_product_images(state: State<'_, AppState>) -> Result<usize, String> {
+    state.product_image_service.cleanup_orphans().await
+}
@@ -0,0 +1,40 @@
+use crate::commands::auth::AppState;
+use crate::models::product_import::{ImportFilePreview, ImportProductsPayload, ProductImportReport};
+use tauri::{AppHandle, Manager, State};
+
+#[tauri::command]
+pub async fn preview_import_file(
+    state: State<'_, AppState>,
+    file_path: String,
+    sheet: Option<String>,
+) -> Result<ImportFilePreview, String> {
+    state
+        .product_import_service
+        .preview_import_file(&file_path, sheet.as_deref())
+}
+
+#[tauri::command]
+pub async fn import_products(
+    state: State<'_, AppState>,
+    payload: ImportProductsPayload,
+) -> Result<ProductImportReport, String> {
+    state.product_import_service.import_products(payload).await
+}
+
+#[tauri::command]
+pub async fn export_products(
+    app: AppHandle,
+    state: State<'_, AppState>,
+    store_id: i64,
+    format: String, // "csv" | "xlsx"
+) -> Result<String, String> {
+    let output_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("documents");
+    state
+        .product_import_service
+        .export_products(store_id, &format, &output_dir)
+        .await
+}
@@ -1,5 +1,7 @@
 use crate::commands::auth::AppState;
-use crate::models::purchase_order::{CreatePurchaseOrderPayload, PurchaseOrder, PurchaseOrderWithItems};
+use crate::models::purchase_order::{
+    CreatePurchaseOrderPayload, PurchaseOrder, PurchaseOrderWithItems, ReceivePurchaseOrderPayload,
+};
 use tauri::State;
 
 #[tauri::command]
@@ -34,3 +36,25 @@ pub async fn get_purchase_order_detail(
         .get_purchase_order_detail(id)
         .await
 }
+
+#[tauri::command]
+pub async fn receive_purchase_order(
+    state: State<'_, AppState>,
+    payload: ReceivePurchaseOrderPayload,
+) -> Result<PurchaseOrderWithItems, String> {
+    state
+        .purchase_order_service
+        .receive_purchase_order(payload)
+        .await
+}
+
+#[tauri::command]
+pub async fn delete_draft_purchase_order(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<(), String> {
+    state
+        .purchase_order_service
+        .delete_draft_purchase_order(id)
+        .await
+}
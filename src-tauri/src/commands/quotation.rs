@@ -0,0 +1,54 @@
+use crate::commands::auth::AppState;
+use crate::models::quotation::{
+    ConvertQuotationPayload, CreateQuotationPayload, Quotation, QuotationWithItems,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_quotation(
+    state: State<'_, AppState>,
+    payload: CreateQuotationPayload,
+) -> Result<QuotationWithItems, String> {
+    state.quotation_service.create_quotation(payload).await
+}
+
+#[tauri::command]
+pub async fn get_quotations(
+    state: State<'_, AppState>,
+    store_id: i64,
+    status: Option<String>,
+    client: Option<String>,
+) -> Result<Vec<Quotation>, String> {
+    state
+        .quotation_service
+        .get_quotations(store_id, status.as_deref(), client.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_quotation_detail(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<Option<QuotationWithItems>, String> {
+    state.quotation_service.get_quotation_detail(id).await
+}
+
+#[tauri::command]
+pub async fn update_quotation_status(
+    state: State<'_, AppState>,
+    id: i64,
+    status: String,
+) -> Result<(), String> {
+    state
+        .quotation_service
+        .update_quotation_status(id, &status)
+        .await
+}
+
+#[tauri::command]
+pub async fn convert_quotation_to_sale(
+    state: State<'_, AppState>,
+    payload: ConvertQuotationPayload,
+) -> Result<i64, String> {
+    state.quotation_service.convert_to_sale(payload).await
+}
@@ -0,0 +1,38 @@
+use crate::commands::auth::AppState;
+use crate::models::purchase_order::PurchaseOrderWithItems;
+use crate::models::reorder::{
+    CreateReorderPurchaseOrderPayload, LowStockProduct, ReorderSuggestion,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_low_stock_products(
+    state: State<'_, AppState>,
+    store_id: i64,
+) -> Result<Vec<LowStockProduct>, String> {
+    state.reorder_service.get_low_stock(store_id).await
+}
+
+#[tauri::command]
+pub async fn get_reorder_suggestions(
+    state: State<'_, AppState>,
+    store_id: i64,
+    days: Option<i64>,
+    cover_days: Option<i64>,
+) -> Result<Vec<ReorderSuggestion>, String> {
+    state
+        .reorder_service
+        .get_reorder_suggestions(store_id, days, cover_days)
+        .await
+}
+
+#[tauri::command]
+pub async fn create_reorder_purchase_order(
+    state: State<'_, AppState>,
+    payload: CreateReorderPurchaseOrderPayload,
+) -> Result<PurchaseOrderWithItems, String> {
+    state
+        .reorder_service
+        .create_reorder_purchase_order(payload)
+        .await
+}
@@ -1,4 +1,5 @@
 use crate::commands::auth::AppState;
+use crate::models::money::Money;
 use crate::models::sales::{
     CreateOrderItemPayload, CreateOrderPayload, OrderItemExport, Sale, SaleDetail,
 };
@@ -13,11 +14,16 @@ pub async fn create_sale(
     client_name: Option<String>,
     payment_method: String,
     items: Vec<CreateOrderItemPayload>,
-    subtotal: f64,
-    igv: f64,
-    total: f64,
+    subtotal: Money,
+    igv: Money,
+    total: Money,
     cash_session_id: i64,
     store_id: i64,
+    document_type: Option<String>,
+    stored_value_code: Option<String>,
+    stored_value_amount: Option<Money>,
+    loyalty_points: Option<i64>,
+    price_list_id: Option<i64>,
 ) -> Result<i64, String> {
     let payload = CreateOrderPayload {
         user_id,
@@ -31,8 +37,27 @@ pub async fn create_sale(
         total,
         cash_session_id,
         store_id,
+        document_type,
+        stored_value_code,
+        stored_value_amount,
+        loyalty_points,
+        price_list_id,
     };
-    state.sales_service.create_order(payload).await
+    state
+        .sales_service
+        .create_order(payload)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn void_sale(
+    state: State<'_, AppState>,
+    sale_id: i64,
+    user_id: i64,
+    reason: Option<String>,
+) -> Result<(), String> {
+    state.sales_service.void_sale(sale_id, user_id, reason).await
 }
 
 #[tauri::command]
@@ -0,0 +1,60 @@
+use crate::commands::auth::AppState;
+use crate::models::stock_adjustment::{CreateStockAdjustmentPayload, StockAdjustment};
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_stock_adjustment(
+    state: State<'_, AppState>,
+    payload: CreateStockAdjustmentPayload,
+) -> Result<StockAdjustment, String> {
+    state.stock_adjustment_service.create_adjustment(payload).await
+}
+
+#[tauri::command]
+pub async fn approve_stock_adjustment(
+    state: State<'_, AppState>,
+    id: i64,
+    manager_id: i64,
+    manager_password: String,
+) -> Result<(), String> {
+    state
+        .stock_adjustment_service
+        .approve_adjustment(id, manager_id, &manager_password)
+        .await
+}
+
+#[tauri::command]
+pub async fn reject_stock_adjustment(
+    state: State<'_, AppState>,
+    id: i64,
+    manager_id: i64,
+    manager_password: String,
+) -> Result<(), String> {
+    state
+        .stock_adjustment_service
+        .reject_adjustment(id, manager_id, &manager_password)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_stock_adjustments(
+    state: State<'_, AppState>,
+    store_id: i64,
+    status: Option<String>,
+) -> Result<Vec<StockAdjustment>, String> {
+    state
+        .stock_adjustment_service
+        .get_adjustments(store_id, status.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn set_stock_adjustment_approval(
+    state: State<'_, AppState>,
+    required: bool,
+) -> Result<(), String> {
+    state
+        .config_service
+        .set_config("stock_adjustment_approval", if required { "1" } else { "0" })
+        .await
+}
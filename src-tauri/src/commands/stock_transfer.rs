@@ -0,0 +1,55 @@
+use crate::commands::auth::AppState;
+use crate::models::stock_transfer::{
+    CreateStockTransferPayload, ReceiveStockTransferPayload, StockTransfer, StockTransferDetail,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn create_stock_transfer(
+    state: State<'_, AppState>,
+    payload: CreateStockTransferPayload,
+) -> Result<StockTransferDetail, String> {
+    state.stock_transfer_service.create_transfer(payload).await
+}
+
+#[tauri::command]
+pub async fn dispatch_stock_transfer(
+    state: State<'_, AppState>,
+    id: i64,
+    user_id: i64,
+) -> Result<StockTransferDetail, String> {
+    state.stock_transfer_service.dispatch_transfer(id, user_id).await
+}
+
+#[tauri::command]
+pub async fn receive_stock_transfer(
+    state: State<'_, AppState>,
+    payload: ReceiveStockTransferPayload,
+) -> Result<StockTransferDetail, String> {
+    state.stock_transfer_service.receive_transfer(payload).await
+}
+
+#[tauri::command]
+pub async fn cancel_stock_transfer(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.stock_transfer_service.cancel_transfer(id).await
+}
+
+#[tauri::command]
+pub async fn get_stock_transfers(
+    state: State<'_, AppState>,
+    store_id: i64,
+    status: Option<String>,
+) -> Result<Vec<StockTransfer>, String> {
+    state
+        .stock_transfer_service
+        .get_transfers(store_id, status.as_deref())
+        .await
+}
+
+#[tauri::command]
+pub async fn get_stock_transfer_detail(
+    state: State<'_, AppState>,
+    id: i64,
+) -> Result<Option<StockTransferDetail>, String> {
+    state.stock_transfer_service.get_transfer_detail(id).await
+}
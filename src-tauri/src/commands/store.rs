@@ -50,3 +50,18 @@ pub async fn delete_store(state: State<'_, AppState>, id: i64) -> Result<(), Str
         .await
         .map_err(|e| e.to_string())
 }
+
+#[tauri::command]
+pub async fn update_store_fiscal_info(
+    state: State<'_, AppState>,
+    id: i64,
+    ruc: Option<String>,
+    logo_path: Option<String>,
+) -> Result<(), String> {
+    state
+        .auth_service
+        .store_repo
+        .update_fiscal_info(id, ruc.as_deref(), logo_path.as_deref())
+        .await
+        .map_err(|e| e.to_string())
+}
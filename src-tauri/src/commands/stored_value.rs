@@ -0,0 +1,52 @@
+use crate::commands::auth::AppState;
+use crate::models::stored_value::{
+    IssueGiftCardPayload, IssueStoreCreditPayload, StoredValueAccount, StoredValueStatement,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn issue_gift_card(
+    state: State<'_, AppState>,
+    payload: IssueGiftCardPayload,
+) -> Result<StoredValueStatement, String> {
+    state.stored_value_service.issue_gift_card(payload).await
+}
+
+#[tauri::command]
+pub async fn issue_store_credit(
+    state: State<'_, AppState>,
+    payload: IssueStoreCreditPayload,
+) -> Result<StoredValueStatement, String> {
+    state.stored_value_service.issue_store_credit(payload).await
+}
+
+#[tauri::command]
+pub async fn get_stored_value_balance(
+    state: State<'_, AppState>,
+    code: String,
+) -> Result<StoredValueStatement, String> {
+    state.stored_value_service.get_balance(&code).await
+}
+
+#[tauri::command]
+pub async fn get_customer_store_credit(
+    state: State<'_, AppState>,
+    customer_document: String,
+) -> Result<Option<StoredValueStatement>, String> {
+    state
+        .stored_value_service
+        .get_store_credit(&customer_document)
+        .await
+}
+
+#[tauri::command]
+pub async fn get_stored_value_accounts(
+    state: State<'_, AppState>,
+    store_id: i64,
+    kind: Option<String>,
+) -> Result<Vec<StoredValueAccount>, String> {
+    state
+        .stored_value_service
+        .get_accounts(store_id, kind.as_deref())
+        .await
+}
@@ -0,0 +1,64 @@
+use crate::commands::auth::AppState;
+use crate::models::variant::{
+    GenerateVariantsPayload, ProductStyle, ProductVariant, SaveProductStylePayload, StyleReportRow,
+    UpdateVariantPayload,
+};
+use tauri::State;
+
+#[tauri::command]
+pub async fn get_product_styles(
+    state: State<'_, AppState>,
+    store_id: i64,
+) -> Result<Vec<ProductStyle>, String> {
+    state.variant_service.get_product_styles(store_id).await
+}
+
+#[tauri::command]
+pub async fn save_product_style(
+    state: State<'_, AppState>,
+    payload: SaveProductStylePayload,
+) -> Result<i64, String> {
+    state.variant_service.save_product_style(payload).await
+}
+
+#[tauri::command]
+pub async fn delete_product_style(state: State<'_, AppState>, id: i64) -> Result<(), String> {
+    state.variant_service.delete_product_style(id).await
+}
+
+#[tauri::command]
+pub async fn get_style_variants(
+    state: State<'_, AppState>,
+    style_id: i64,
+) -> Result<Vec<ProductVariant>, String> {
+    state.variant_service.get_style_variants(style_id).await
+}
+
+#[tauri::command]
+pub async fn generate_variant_matrix(
+    state: State<'_, AppState>,
+    payload: GenerateVariantsPayload,
+) -> Result<Vec<ProductVariant>, String> {
+    state.variant_service.generate_variant_matrix(payload).await
+}
+
+#[tauri::command]
+pub async fn update_variant(
+    state: State<'_, AppState>,
+    payload: UpdateVariantPayload,
+) -> Result<(), String> {
+    state.variant_service.update_variant(payload).await
+}
+
+#[tauri::command]
+pub async fn get_style_stock_report(
+    state: State<'_, AppState>,
+    store_id: i64,
+    date_from: String,
+    date_to: String,
+) -> Result<Vec<StyleReportRow>, String> {
+    state
+        .variant_service
+        .get_style_stock_report(store_id, date_from, date_to)
+        .await
+}
@@ -1,4 +1,6 @@
-use sqlx::sqlite::SqlitePool;
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool};
+use sqlx::{ConnectOptions, Connection};
+use std::str::FromStr;
 use std::fs;
 use tauri::AppHandle;
 use tauri::Manager;
@@ -15,10 +17,17 @@ pub async fn init_db(app_handle: &AppHandle) -> Result<SqlitePool, Box<dyn std::
         std::fs::File::create(&db_path)?;
     }
 
-    let pool = SqlitePool::connect(&db_url).await?;
+    // Run migrations on their own connection with foreign keys off: SQLite can
+    // only change a column constraint by rebuilding the table, and dropping the
+    // old table would otherwise trip every reference to it.
+    let mut conn = SqliteConnectOptions::from_str(&db_url)?
+        .foreign_keys(false)
+        .connect()
+        .await?;
+    sqlx::migrate!("./migrations").run(&mut conn).await?;
+    conn.close().await?;
 
-    // Run migrations
-    sqlx::migrate!("./migrations").run(&pool).await?;
+    let pool = SqlitePool::connect(&db_url).await?;
 
     Ok(pool)
 }
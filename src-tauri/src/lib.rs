@@ -7,6 +7,7 @@ pub mod services;
 use commands::auth::AppState;
 use services::auth_service::AuthService;
 use services::config_service::ConfigService;
+use std::time::Duration;
 use tauri::Manager;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -28,6 +29,41 @@ pub fn run() {
             let cash_service = services::cash_service::CashService::new(pool.clone());
             let purchase_order_service =
                 services::purchase_order_service::PurchaseOrderService::new(pool.clone());
+            let pdf_service = services::pdf_service::PdfService::new(pool.clone());
+            let quotation_service =
+                services::quotation_service::QuotationService::new(pool.clone());
+            let layaway_service = services::layaway_service::LayawayService::new(pool.clone());
+            let stored_value_service =
+                services::stored_value_service::StoredValueService::new(pool.clone());
+            let loyalty_service = services::loyalty_service::LoyaltyService::new(pool.clone());
+            let price_list_service =
+                services::price_list_service::PriceListService::new(pool.clone());
+            let commission_service =
+                services::commission_service::CommissionService::new(pool.clone());
+            let variant_service = services::variant_service::VariantService::new(pool.clone());
+            let stock_adjustment_service =
+                services::stock_adjustment_service::StockAdjustmentService::new(pool.clone());
+            let stock_transfer_service =
+                services::stock_transfer_service::StockTransferService::new(pool.clone());
+            let inventory_count_service =
+                services::inventory_count_service::InventoryCountService::new(pool.clone());
+            let reorder_service = services::reorder_service::ReorderService::new(pool.clone());
+            let barcode_service = services::barcode_service::BarcodeService::new(pool.clone());
+            let product_import_service =
+                services::product_import_service::ProductImportService::new(pool.clone());
+            let images_dir = app_handle
+                .path()
+                .app_data_dir()
+                .expect("Failed to resolve app data dir")
+                .join("images");
+            let product_image_service =
+                services::product_image_service::ProductImageService::new(pool.clone(), images_dir);
+            let price_change_service =
+                services::price_change_service::PriceChangeService::new(pool.clone());
+            let price_scheduler =
+                services::price_change_service::PriceChangeService::new(pool.clone());
+            let catalog_service = services::catalog_service::CatalogService::new(pool.clone());
+            let kit_service = services::kit_service::KitService::new(pool.clone());
             let config_service = ConfigService::new(pool);
 
                 // Initialize Admin if needed
@@ -44,6 +80,35 @@ pub fn run() {
                     cash_service,
                     purchase_order_service,
                     config_service,
+                    pdf_service,
+                    quotation_service,
+                    layaway_service,
+                    stored_value_service,
+                    loyalty_service,
+                    price_list_service,
+                    commission_service,
+                    variant_service,
+                    stock_adjustment_service,
+                    stock_transfer_service,
+                    inventory_count_service,
+                    reorder_service,
+                    barcode_service,
+                    product_import_service,
+                    product_image_service,
+                    price_change_service,
+                    catalog_service,
+                    kit_service,
+                });
+
+                // Scheduled price changes take effect on their own
+                tauri::async_runtime::spawn(async move {
+                    let mut interval = tokio::time::interval(Duration::from_secs(60));
+                    loop {
+                        interval.tick().await;
+                        if let Err(e) = price_scheduler.apply_due_price_changes().await {
+                            log::warn!("No se pudieron aplicar los cambios de precio programados: {}", e);
+                        }
+                    }
                 });
             });
 
@@ -66,6 +131,7 @@ pub fn run() {
             commands::store::create_store,
             commands::store::update_store,
             commands::store::delete_store,
+            commands::store::update_store_fiscal_info,
             commands::user::get_all_users,
             commands::user::create_staff_user,
             commands::user::update_user,
@@ -75,16 +141,92 @@ pub fn run() {
             commands::inventory::get_categories,
             commands::inventory::create_category,
             commands::inventory::update_category,
+            commands::inventory::move_category,
+            commands::inventory::reorder_categories,
             commands::inventory::delete_category,
+            commands::inventory::get_category_products,
             commands::inventory::get_products,
+            commands::inventory::search_products,
+            commands::inventory::lookup_product,
             commands::inventory::create_product,
             commands::inventory::update_product,
             commands::inventory::delete_product,
+            commands::inventory::get_kardex,
+            commands::inventory::get_cost_history,
+            // Stock adjustments
+            commands::stock_adjustment::create_stock_adjustment,
+            commands::stock_adjustment::approve_stock_adjustment,
+            commands::stock_adjustment::reject_stock_adjustment,
+            commands::stock_adjustment::get_stock_adjustments,
+            commands::stock_adjustment::set_stock_adjustment_approval,
+            // Stock transfers
+            commands::stock_transfer::create_stock_transfer,
+            commands::stock_transfer::dispatch_stock_transfer,
+            commands::stock_transfer::receive_stock_transfer,
+            commands::stock_transfer::cancel_stock_transfer,
+            commands::stock_transfer::get_stock_transfers,
+            commands::stock_transfer::get_stock_transfer_detail,
+            // Physical inventory counts
+            commands::inventory_count::start_inventory_count,
+            commands::inventory_count::scan_inventory_count,
+            commands::inventory_count::start_inventory_count_pass,
+            commands::inventory_count::get_inventory_count_report,
+            commands::inventory_count::approve_inventory_count,
+            commands::inventory_count::cancel_inventory_count,
+            commands::inventory_count::get_inventory_counts,
+            // Low stock & reorder
+            commands::reorder::get_low_stock_products,
+            commands::reorder::get_reorder_suggestions,
+            commands::reorder::create_reorder_purchase_order,
+            // Barcodes & labels
+            commands::barcode::assign_barcodes,
+            commands::barcode::set_barcode_symbology,
+            commands::barcode::generate_labels,
+            commands::barcode::generate_purchase_order_labels,
+            // Product import & export
+            commands::product_import::preview_import_file,
+            commands::product_import::import_products,
+            commands::product_import::export_products,
+            // Product images
+            commands::product_image::import_product_image,
+            commands::product_image::get_product_image,
+            commands::product_image::get_missing_product_images,
+            commands::product_image::get_product_image_data,
+            commands::product_image::save_synced_product_image,
+            commands::product_image::cleanup_product_images,
+            // Price history & scheduled prices
+            commands::price_change::get_price_history,
+            commands::price_change::schedule_price_changes,
+            commands::price_change::get_scheduled_price_changes,
+            commands::price_change::cancel_scheduled_price_change,
+            commands::price_change::get_pending_purchase_prices,
+            commands::price_change::confirm_purchase_prices,
+            commands::price_change::discard_purchase_prices,
+            // Global catalog & store inventory
+            commands::catalog::get_catalog_products,
+            commands::catalog::get_catalog_product,
+            commands::catalog::update_catalog_product,
+            commands::catalog::add_catalog_product_to_store,
+            commands::catalog::set_local_price,
+            // Packs / kits
+            commands::kit::get_product_kits,
+            commands::kit::get_product_kit,
+            commands::kit::save_kit_components,
+            commands::kit::get_kit_component_sales,
+            // Styles & variants
+            commands::variant::get_product_styles,
+            commands::variant::save_product_style,
+            commands::variant::delete_product_style,
+            commands::variant::get_style_variants,
+            commands::variant::generate_variant_matrix,
+            commands::variant::update_variant,
+            commands::variant::get_style_stock_report,
             // Sales
             commands::sales::create_sale,
             commands::sales::get_sales,
             commands::sales::get_sale_detail,
             commands::sales::get_all_order_items,
+            commands::sales::void_sale,
             // Cash
             commands::cash::get_active_cash_session,
             commands::cash::get_last_closed_cash_session,
@@ -102,12 +244,56 @@ pub fn run() {
             commands::purchase_order::create_purchase_order,
             commands::purchase_order::get_purchase_orders,
             commands::purchase_order::get_purchase_order_detail,
+            commands::purchase_order::receive_purchase_order,
+            commands::purchase_order::delete_draft_purchase_order,
+            // Quotations
+            commands::quotation::create_quotation,
+            commands::quotation::get_quotations,
+            commands::quotation::get_quotation_detail,
+            commands::quotation::update_quotation_status,
+            commands::quotation::convert_quotation_to_sale,
+            // Layaways
+            commands::layaway::create_layaway,
+            commands::layaway::add_layaway_payment,
+            commands::layaway::cancel_layaway,
+            commands::layaway::get_layaways,
+            commands::layaway::get_layaway_detail,
+            commands::layaway::set_layaway_expiry_days,
+            // Gift cards & store credit
+            commands::stored_value::issue_gift_card,
+            commands::stored_value::issue_store_credit,
+            commands::stored_value::get_stored_value_balance,
+            commands::stored_value::get_customer_store_credit,
+            commands::stored_value::get_stored_value_accounts,
+            // Loyalty
+            commands::loyalty::get_customer_points,
+            commands::loyalty::get_loyalty_settings,
+            commands::loyalty::update_loyalty_settings,
+            commands::loyalty::get_loyalty_category_multipliers,
+            commands::loyalty::set_loyalty_category_multiplier,
+            // Price lists
+            commands::price_list::get_price_lists,
+            commands::price_list::create_price_list,
+            commands::price_list::update_price_list,
+            commands::price_list::get_price_list_items,
+            commands::price_list::set_price_list_item,
+            commands::price_list::delete_price_list_item,
+            commands::price_list::set_customer_price_list,
+            // Commissions
+            commands::commission::get_commission_rules,
+            commands::commission::save_commission_rule,
+            commands::commission::delete_commission_rule,
+            commands::commission::get_commission_report,
+            commands::commission::export_commission_report,
             // Config
             commands::config::get_operating_mode,
             commands::config::has_app_config,
             commands::config::set_operating_mode,
             commands::config::get_app_config,
             commands::config::set_app_config,
+            // Documents
+            commands::pdf::generate_sale_pdf,
+            commands::pdf::generate_cash_session_report_pdf,
         ])
         .plugin(tauri_plugin_process::init())
         .run(tauri::generate_context!())
@@ -0,0 +1,38 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Data printed on a product label.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LabelProduct {
+    pub product_id: i64,
+    pub name: String,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub price: Money,
+    pub barcode: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AssignedBarcode {
+    pub product_id: i64,
+    pub barcode: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignBarcodesPayload {
+    pub store_id: i64,
+    /// Products to tag; every product of the store without a barcode when empty.
+    #[serde(default)]
+    pub product_ids: Vec<i64>,
+    /// "ean13" or "code128"; defaults to the configured symbology.
+    pub symbology: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelRequest {
+    pub product_id: i64,
+    pub copies: i64,
+}
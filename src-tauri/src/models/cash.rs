@@ -1,3 +1,4 @@
+use crate::models::money::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -8,13 +9,13 @@ pub struct CashSession {
     pub opened_at: String,
     pub closed_by: Option<i64>,
     pub closed_at: Option<String>,
-    pub opening_cash: f64,
-    pub opening_virtual: f64,
-    pub expected_closing_cash: f64,
-    pub expected_closing_virtual: f64,
-    pub real_closing_cash: Option<f64>,
-    pub real_closing_virtual: Option<f64>,
-    pub difference: Option<f64>,
+    pub opening_cash: Money,
+    pub opening_virtual: Money,
+    pub expected_closing_cash: Money,
+    pub expected_closing_virtual: Money,
+    pub real_closing_cash: Option<Money>,
+    pub real_closing_virtual: Option<Money>,
+    pub difference: Option<Money>,
     pub justification: Option<String>,
     pub status: String,
     pub store_id: i64,
@@ -32,16 +33,16 @@ pub struct UserSession {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct OpenCashPayload {
     pub opened_by: i64,
-    pub opening_cash: f64,
-    pub opening_virtual: f64,
+    pub opening_cash: Money,
+    pub opening_virtual: Money,
     pub store_id: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CloseCashPayload {
     pub closed_by: i64,
-    pub real_closing_cash: f64,
-    pub real_closing_virtual: f64,
+    pub real_closing_cash: Money,
+    pub real_closing_virtual: Money,
     pub justification: Option<String>,
 }
 
@@ -51,7 +52,7 @@ pub struct Expense {
     pub uuid: String,
     pub cash_session_id: Option<i64>,
     pub description: String,
-    pub amount: f64,
+    pub amount: Money,
     pub payment_method: String,
     pub category: Option<String>,
     pub supplier: Option<String>,
@@ -65,7 +66,7 @@ pub struct Expense {
 pub struct UpdateExpensePayload {
     pub id: i64,
     pub description: String,
-    pub amount: f64,
+    pub amount: Money,
     pub payment_method: String,
     pub category: Option<String>,
     pub supplier: Option<String>,
@@ -76,7 +77,7 @@ pub struct OtherIncome {
     pub id: i64,
     pub cash_session_id: i64,
     pub description: String,
-    pub amount: f64,
+    pub amount: Money,
     pub payment_method: String,
     pub store_id: i64,
     pub created_at: String,
@@ -0,0 +1,76 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// An item of the catalog shared by every store, with its stock summed over
+/// the stores that carry it.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CatalogProduct {
+    pub id: i64,
+    pub code: Option<String>,
+    pub name: String,
+    pub unit: Option<String>,
+    pub image_url: Option<String>,
+    pub barcode: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    /// Base price, used by the stores without a local one.
+    pub price: Money,
+    pub created_at: Option<String>,
+    /// Stores where the item is active.
+    pub store_count: i64,
+    pub total_stock: i64,
+}
+
+/// The inventory row of a catalog item in one store.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StoreInventory {
+    pub product_id: i64,
+    pub store_id: i64,
+    pub store_name: String,
+    pub category_id: Option<i64>,
+    pub category_name: Option<String>,
+    pub stock: i64,
+    pub min_stock: Option<i64>,
+    pub cost: Money,
+    /// Price the store sells at.
+    pub price: Money,
+    pub local_price: Option<Money>,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CatalogProductDetail {
+    #[serde(flatten)]
+    pub product: CatalogProduct,
+    pub stores: Vec<StoreInventory>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateCatalogProductPayload {
+    pub id: i64,
+    pub code: Option<String>,
+    pub name: String,
+    pub unit: Option<String>,
+    pub image_url: Option<String>,
+    pub barcode: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub price: Money,
+    #[serde(default)]
+    pub user_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddCatalogProductToStorePayload {
+    pub catalog_id: i64,
+    pub store_id: i64,
+    pub category_id: Option<i64>,
+    pub min_stock: Option<i64>,
+    /// `None` sells at the base price.
+    pub local_price: Option<Money>,
+    #[serde(default)]
+    pub user_id: Option<i64>,
+}
@@ -0,0 +1,97 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Columns of the exported commission report, in order.
+pub const COMMISSION_EXPORT_HEADERS: [&str; 9] = [
+    "vendedor_id",
+    "vendedor",
+    "ventas",
+    "ventas_brutas",
+    "devoluciones",
+    "anuladas",
+    "monto_anulado",
+    "ventas_netas",
+    "comision",
+];
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CommissionRule {
+    pub id: i64,
+    pub store_id: Option<i64>,
+    pub scope: String, // "global" | "category" | "product"
+    pub category_id: Option<i64>,
+    pub category_name: Option<String>,
+    pub product_id: Option<i64>,
+    pub product_name: Option<String>,
+    /// Percent of the sale amount before IGV.
+    pub rate: f64,
+    /// The rule only applies once the seller's net sales in the month reach this amount.
+    pub min_monthly_volume: Money,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveCommissionRulePayload {
+    pub id: Option<i64>,
+    pub store_id: Option<i64>,
+    pub scope: String,
+    pub category_id: Option<i64>,
+    pub product_id: Option<i64>,
+    pub rate: f64,
+    #[serde(default)]
+    pub min_monthly_volume: Money,
+    #[serde(default = "default_true")]
+    pub is_active: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// One sold line with what is needed to price its commission.
+#[derive(Debug, FromRow)]
+pub struct CommissionLine {
+    pub order_id: i64,
+    pub user_id: i64,
+    pub user_name: Option<String>,
+    pub month: String,
+    pub order_total: Money,
+    pub returned_amount: Money,
+    pub product_id: i64,
+    pub category_id: Option<i64>,
+    pub subtotal: Money,
+}
+
+/// Voided sales of a seller in the period, shown for reference.
+#[derive(Debug, FromRow)]
+pub struct VoidedSummary {
+    pub user_id: i64,
+    pub voided_count: i64,
+    pub voided_amount: Money,
+}
+
+/// Flat per-seller row, ready to be exported as CSV.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommissionReportRow {
+    pub user_id: i64,
+    pub user_name: Option<String>,
+    pub sales_count: i64,
+    pub gross_sales: Money,
+    pub returns: Money,
+    pub voided_count: i64,
+    pub voided_amount: Money,
+    pub net_sales: Money,
+    pub commission: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CommissionReport {
+    pub store_id: i64,
+    pub date_from: String,
+    pub date_to: String,
+    pub rows: Vec<CommissionReportRow>,
+    pub total_commission: Money,
+}
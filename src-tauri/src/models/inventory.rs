@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use crate::models::money::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -6,6 +7,25 @@ use sqlx::FromRow;
 pub struct Category {
     pub id: i64,
     pub name: String,
+    pub parent_id: Option<i64>,
+    /// `None` for categories shared by every store.
+    pub store_id: Option<i64>,
+    pub sort_order: i64,
+}
+
+/// A category in tree order, with its position in the hierarchy.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CategoryNode {
+    pub id: i64,
+    pub name: String,
+    pub parent_id: Option<i64>,
+    pub store_id: Option<i64>,
+    pub sort_order: i64,
+    pub depth: i64,
+    /// "Pantalones > Jeans > Slim"
+    pub path: String,
+    /// Active products directly in this category.
+    pub product_count: i64,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -14,8 +34,8 @@ pub struct Product {
     pub code: Option<String>,
     pub name: String,
     pub category_id: Option<i64>,
-    pub price: f64,
-    pub cost: f64,
+    pub price: Money,
+    pub cost: Money,
     pub stock: i64,
     pub min_stock: Option<i64>,
     pub unit: Option<String>,
@@ -23,6 +43,16 @@ pub struct Product {
     pub is_active: bool,
     pub store_id: Option<i64>,
     pub created_at: Option<NaiveDateTime>,
+    pub style_id: Option<i64>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub barcode: Option<String>,
+    /// Shared catalog item this store row stocks.
+    pub catalog_id: Option<i64>,
+    /// Store's own price; `None` sells at the catalog base price.
+    pub local_price: Option<Money>,
+    /// A pack of other products; its stock comes from its components.
+    pub is_kit: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -32,13 +62,41 @@ pub struct ProductWithCategory {
     pub name: String,
     pub category_id: Option<i64>,
     pub category_name: Option<String>,
-    pub price: f64,
-    pub cost: f64,
+    pub price: Money,
+    pub cost: Money,
     pub stock: i64,
+    pub reserved_stock: i64,
     pub min_stock: Option<i64>,
     pub unit: Option<String>,
     pub image_url: Option<String>,
     pub is_active: bool,
     pub store_id: Option<i64>,
     pub created_at: Option<NaiveDateTime>,
+    pub style_id: Option<i64>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub barcode: Option<String>,
+    pub is_kit: bool,
+}
+
+/// A scanned or typed code resolved to the product to sell.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductLookup {
+    #[serde(flatten)]
+    pub product: ProductWithCategory,
+    /// "barcode", "code" or "embedded" (scale / price label)
+    pub matched_by: String,
+    /// Price of one scanned unit: the product price, or the amount printed
+    /// in a price- or weight-embedded label.
+    pub unit_price: Money,
+    pub embedded_weight_grams: Option<i64>,
+}
+
+/// One page of full-text search results, best matches first.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductSearchPage {
+    pub items: Vec<ProductWithCategory>,
+    pub total: i64,
+    pub page: i64,
+    pub page_size: i64,
 }
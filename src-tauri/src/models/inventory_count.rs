@@ -0,0 +1,64 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct InventoryCount {
+    pub id: i64,
+    pub store_id: i64,
+    pub status: String, // "open" | "approved" | "cancelled"
+    pub notes: Option<String>,
+    pub current_pass: i64,
+    pub created_by: i64,
+    pub created_by_name: Option<String>,
+    pub approved_by: Option<i64>,
+    pub approved_by_name: Option<String>,
+    pub approved_at: Option<String>,
+    pub created_at: String,
+}
+
+/// One product of the count with its expected and counted units.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct InventoryCountLine {
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub snapshot_stock: i64,
+    /// Snapshot plus the movements up to the moment the product was counted.
+    pub expected: i64,
+    /// `None` while the product hasn't been scanned.
+    pub counted: Option<i64>,
+    pub counted_pass: Option<i64>,
+    pub unit_cost: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InventoryCountVariance {
+    #[serde(flatten)]
+    pub line: InventoryCountLine,
+    pub variance: i64,
+    pub variance_cost: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InventoryCountReport {
+    pub count: InventoryCount,
+    pub lines: Vec<InventoryCountVariance>,
+    pub counted_products: i64,
+    pub uncounted_products: i64,
+    pub units_over: i64,
+    pub units_short: i64,
+    pub cost_over: Money,
+    pub cost_short: Money,
+    pub net_cost: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScanInventoryCountPayload {
+    pub count_id: i64,
+    pub code: String,
+    /// Units read in this scan; negative to undo a wrong read. Defaults to 1.
+    pub quantity: Option<i64>,
+    pub user_id: i64,
+}
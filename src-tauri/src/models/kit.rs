@@ -0,0 +1,78 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A pack with what its components are worth on their own.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductKit {
+    pub kit_id: i64,
+    pub code: Option<String>,
+    pub name: String,
+    pub store_id: i64,
+    /// Pack price.
+    pub price: Money,
+    /// Sum of the components' list prices for one pack.
+    pub components_price: Money,
+    /// Sum of the components' costs for one pack.
+    pub components_cost: Money,
+    /// Packs that can be sold with the components' stock.
+    pub available: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct KitComponent {
+    pub component_id: i64,
+    pub code: Option<String>,
+    pub name: String,
+    /// Units in one pack.
+    pub quantity: i64,
+    pub price: Money,
+    pub cost: Money,
+    /// Sellable stock of the component.
+    pub stock: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductKitDetail {
+    #[serde(flatten)]
+    pub kit: ProductKit,
+    pub components: Vec<KitComponent>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveKitComponentsPayload {
+    pub kit_id: i64,
+    /// An empty list turns the pack back into a regular product.
+    pub components: Vec<KitComponentPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KitComponentPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+}
+
+/// A component of a pack line being sold.
+#[derive(Debug, FromRow)]
+pub struct KitSaleComponent {
+    pub product_id: i64,
+    pub name: String,
+    pub quantity: i64,
+    pub price: Money,
+    pub cost: Money,
+}
+
+/// Units of a product sold inside packs in a period, with its share of the
+/// pack revenue and its cost.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct KitComponentSales {
+    pub product_id: i64,
+    pub code: Option<String>,
+    pub name: String,
+    pub units_sold: i64,
+    pub revenue: Money,
+    pub cost: Money,
+    pub margin: Money,
+}
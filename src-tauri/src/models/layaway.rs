@@ -0,0 +1,85 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Layaway {
+    pub id: i64,
+    pub uuid: String,
+    pub store_id: i64,
+    pub user_id: i64,
+    pub client_document: Option<String>,
+    pub client_phone: Option<String>,
+    pub client_name: String,
+    pub total: Money,
+    pub paid_amount: Money,
+    pub status: String,
+    pub expires_at: String,
+    pub order_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LayawayItem {
+    pub id: i64,
+    pub layaway_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub unit_price: Money,
+    pub quantity: i64,
+    pub subtotal: Money,
+    pub price_list_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LayawayPayment {
+    pub id: i64,
+    pub layaway_id: i64,
+    pub cash_session_id: i64,
+    pub amount: Money,
+    pub payment_method: String,
+    pub user_id: i64,
+    pub store_id: i64,
+    pub created_at: String,
+}
+
+/// Full layaway detail including its reserved items and deposits.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LayawayDetail {
+    #[serde(flatten)]
+    pub layaway: Layaway,
+    pub items: Vec<LayawayItem>,
+    pub payments: Vec<LayawayPayment>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLayawayPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub client_document: Option<String>,
+    pub client_phone: Option<String>,
+    pub client_name: String,
+    pub items: Vec<CreateLayawayItemPayload>,
+    pub deposit: Money,
+    pub payment_method: String,
+    /// Defaults to the customer's price list.
+    #[serde(default)]
+    pub price_list_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLayawayItemPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AddLayawayPaymentPayload {
+    pub layaway_id: i64,
+    pub user_id: i64,
+    pub amount: Money,
+    pub payment_method: String,
+}
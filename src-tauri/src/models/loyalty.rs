@@ -0,0 +1,59 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A customer identified by DNI/RUC or phone.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Customer {
+    pub id: i64,
+    pub document: Option<String>,
+    pub phone: Option<String>,
+    pub name: Option<String>,
+    pub created_at: String,
+    pub price_list_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LoyaltyEntry {
+    pub id: i64,
+    pub customer_id: i64,
+    pub order_id: Option<i64>,
+    pub entry_type: String,
+    pub points: i64,
+    pub remaining: i64,
+    pub expires_at: Option<String>,
+    pub created_at: String,
+}
+
+/// Customer with the current (unexpired) points balance and ledger.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CustomerPoints {
+    pub customer: Customer,
+    pub balance: i64,
+    /// Value of the whole balance in soles when redeemed at checkout.
+    pub balance_value: Money,
+    pub entries: Vec<LoyaltyEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LoyaltySettings {
+    pub enabled: bool,
+    pub points_per_sol: f64,
+    pub point_value: f64,
+    pub expiry_days: i64,
+}
+
+impl LoyaltySettings {
+    /// What `points` are worth at checkout, rounded to the céntimo.
+    pub fn value_of(&self, points: i64) -> Money {
+        Money::from_soles(points as f64 * self.point_value)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct CategoryMultiplier {
+    pub category_id: i64,
+    pub category_name: Option<String>,
+    pub multiplier: f64,
+}
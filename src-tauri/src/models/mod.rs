@@ -1,6 +1,26 @@
+pub mod barcode;
 pub mod cash;
+pub mod catalog;
+pub mod commission;
 pub mod inventory;
+pub mod inventory_count;
+pub mod kit;
+pub mod layaway;
+pub mod loyalty;
+pub mod money;
+pub mod price_change;
+pub mod price_list;
+pub mod product_cost;
+pub mod product_image;
+pub mod product_import;
 pub mod purchase_order;
+pub mod quotation;
+pub mod reorder;
 pub mod sales;
+pub mod stock_adjustment;
+pub mod stock_movement;
+pub mod stock_transfer;
 pub mod store;
+pub mod stored_value;
 pub mod user;
+pub mod variant;
@@ -0,0 +1,227 @@
+use serde::de::{self, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::sqlite::{SqliteArgumentValue, SqliteTypeInfo, SqliteValueRef};
+use sqlx::{Decode, Encode, Sqlite, Type, TypeInfo, ValueRef};
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Neg, Sub, SubAssign};
+
+/// An amount in soles, held as an exact number of céntimos.
+///
+/// Stored in SQLite as an INTEGER count of céntimos and sent to the frontend as
+/// a plain number of soles (`12.5`), so the JSON shape is unchanged. Floats only
+/// appear at those boundaries and are rounded to the céntimo once, half away
+/// from zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Money(i64);
+
+impl Money {
+    pub const ZERO: Money = Money(0);
+
+    pub const fn from_cents(cents: i64) -> Self {
+        Money(cents)
+    }
+
+    pub const fn cents(self) -> i64 {
+        self.0
+    }
+
+    /// Converts a decimal amount in soles, rounding to the nearest céntimo.
+    /// The product is first snapped to 6 decimals so that inputs like 1.005,
+    /// which are slightly below their decimal value as f64, still round up.
+    pub fn from_soles(soles: f64) -> Self {
+        let cents = soles * 100.0;
+        Money(((cents * 1e6).round() / 1e6).round() as i64)
+    }
+
+    pub fn to_soles(self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn is_positive(self) -> bool {
+        self.0 > 0
+    }
+
+    pub fn is_negative(self) -> bool {
+        self.0 < 0
+    }
+
+    pub fn abs(self) -> Self {
+        Money(self.0.abs())
+    }
+
+    pub fn min(self, other: Money) -> Money {
+        Money(self.0.min(other.0))
+    }
+
+    pub fn max(self, other: Money) -> Money {
+        Money(self.0.max(other.0))
+    }
+
+    /// Unit price times a quantity; exact.
+    pub fn times(self, quantity: i64) -> Money {
+        Money(self.0 * quantity)
+    }
+
+    /// `self * numerator / denominator`, rounded half away from zero.
+    pub fn mul_div(self, numerator: i64, denominator: i64) -> Money {
+        let n = self.0 as i128 * numerator as i128;
+        let d = denominator as i128;
+        let q = n / d;
+        let r = n % d;
+        let rounded = if 2 * r.abs() >= d.abs() {
+            q + if (n < 0) != (d < 0) { -1 } else { 1 }
+        } else {
+            q
+        };
+        Money(rounded as i64)
+    }
+
+    /// Multiplies by a non-monetary factor (a rate, a ratio, a multiplier),
+    /// rounding the result half away from zero.
+    pub fn scale(self, factor: f64) -> Money {
+        Money((self.0 as f64 * factor).round() as i64)
+    }
+
+    /// `self / other` as a plain ratio, e.g. the share of a total that was returned.
+    pub fn ratio(self, other: Money) -> f64 {
+        if other.0 == 0 {
+            0.0
+        } else {
+            self.0 as f64 / other.0 as f64
+        }
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let sign = if self.0 < 0 { "-" } else { "" };
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}{}.{:02}", sign, abs / 100, abs % 100)
+    }
+}
+
+impl Add for Money {
+    type Output = Money;
+    fn add(self, rhs: Money) -> Money {
+        Money(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Money {
+    type Output = Money;
+    fn sub(self, rhs: Money) -> Money {
+        Money(self.0 - rhs.0)
+    }
+}
+
+impl Neg for Money {
+    type Output = Money;
+    fn neg(self) -> Money {
+        Money(-self.0)
+    }
+}
+
+impl AddAssign for Money {
+    fn add_assign(&mut self, rhs: Money) {
+        self.0 += rhs.0;
+    }
+}
+
+impl SubAssign for Money {
+    fn sub_assign(&mut self, rhs: Money) {
+        self.0 -= rhs.0;
+    }
+}
+
+impl Sum for Money {
+    fn sum<I: Iterator<Item = Money>>(iter: I) -> Money {
+        Money(iter.map(|m| m.0).sum())
+    }
+}
+
+impl<'a> Sum<&'a Money> for Money {
+    fn sum<I: Iterator<Item = &'a Money>>(iter: I) -> Money {
+        Money(iter.map(|m| m.0).sum())
+    }
+}
+
+impl Serialize for Money {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_soles())
+    }
+}
+
+impl<'de> Deserialize<'de> for Money {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct MoneyVisitor;
+
+        impl Visitor<'_> for MoneyVisitor {
+            type Value = Money;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an amount in soles")
+            }
+
+            fn visit_f64<E: de::Error>(self, v: f64) -> Result<Money, E> {
+                if v.is_finite() {
+                    Ok(Money::from_soles(v))
+                } else {
+                    Err(E::custom("monto inválido"))
+                }
+            }
+
+            fn visit_i64<E: de::Error>(self, v: i64) -> Result<Money, E> {
+                Ok(Money(v * 100))
+            }
+
+            fn visit_u64<E: de::Error>(self, v: u64) -> Result<Money, E> {
+                Ok(Money(v as i64 * 100))
+            }
+
+            fn visit_str<E: de::Error>(self, v: &str) -> Result<Money, E> {
+                v.trim()
+                    .parse::<f64>()
+                    .map_err(|_| E::custom(format!("monto inválido: {}", v)))
+                    .and_then(|f| self.visit_f64(f))
+            }
+        }
+
+        deserializer.deserialize_any(MoneyVisitor)
+    }
+}
+
+impl Type<Sqlite> for Money {
+    fn type_info() -> SqliteTypeInfo {
+        <i64 as Type<Sqlite>>::type_info()
+    }
+
+    // Aggregates and rows written before the migration to céntimos may come back as REAL
+    fn compatible(ty: &SqliteTypeInfo) -> bool {
+        <i64 as Type<Sqlite>>::compatible(ty) || <f64 as Type<Sqlite>>::compatible(ty)
+    }
+}
+
+impl<'q> Encode<'q, Sqlite> for Money {
+    fn encode_by_ref(&self, args: &mut Vec<SqliteArgumentValue<'q>>) -> Result<IsNull, BoxDynError> {
+        args.push(SqliteArgumentValue::Int64(self.0));
+        Ok(IsNull::No)
+    }
+}
+
+impl<'r> Decode<'r, Sqlite> for Money {
+    fn decode(value: SqliteValueRef<'r>) -> Result<Self, BoxDynError> {
+        if value.type_info().name() == "REAL" {
+            let cents = <f64 as Decode<Sqlite>>::decode(value)?;
+            Ok(Money(cents.round() as i64))
+        } else {
+            Ok(Money(<i64 as Decode<Sqlite>>::decode(value)?))
+        }
+    }
+}
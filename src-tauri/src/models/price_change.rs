@@ -0,0 +1,84 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One change of a product's selling price.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductPriceChange {
+    pub id: i64,
+    pub product_id: i64,
+    pub store_id: i64,
+    pub previous_price: Money,
+    pub new_price: Money,
+    pub reason: String, // "manual" | "purchase_order" | "scheduled" | "import" | "style" | "catalog"
+    pub document_type: Option<String>,
+    pub document_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ScheduledPriceChange {
+    pub id: i64,
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub store_id: i64,
+    pub current_price: Money,
+    pub new_price: Money,
+    pub effective_at: String,
+    pub status: String, // "pending" | "applied" | "cancelled"
+    pub purchase_order_item_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_by_name: Option<String>,
+    pub created_at: String,
+    pub applied_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulePriceChangesPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    /// Local date ("YYYY-MM-DD", from midnight) or date and time.
+    pub effective_at: String,
+    pub note: Option<String>,
+    pub items: Vec<ScheduledPriceItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledPriceItemPayload {
+    pub product_id: i64,
+    pub new_price: Money,
+}
+
+/// A purchase order line whose batch price differs from the product's price
+/// and waits for someone to confirm or discard it.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PendingPurchasePrice {
+    pub item_id: i64,
+    pub purchase_order_id: i64,
+    pub order_alias: Option<String>,
+    pub supplier_name: Option<String>,
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub current_price: Money,
+    pub proposed_price: Money,
+    pub unit_cost: Money,
+    pub created_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfirmPurchasePricesPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub item_ids: Vec<i64>,
+    /// Schedule the new prices instead of applying them now.
+    pub effective_at: Option<String>,
+}
@@ -0,0 +1,26 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PriceList {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_active: bool,
+    pub created_at: String,
+}
+
+/// Price of a product in a list from `min_quantity` units upwards.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct PriceListItem {
+    pub id: i64,
+    pub price_list_id: i64,
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: Option<String>,
+    /// Shelf price, for comparison.
+    pub base_price: Option<Money>,
+    pub min_quantity: i64,
+    pub price: Money,
+}
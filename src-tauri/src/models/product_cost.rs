@@ -0,0 +1,25 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One change of a product's weighted-average cost.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductCostChange {
+    pub id: i64,
+    pub product_id: i64,
+    pub store_id: i64,
+    pub previous_cost: Money,
+    pub new_cost: Money,
+    /// Units received and their unit cost; `None` for manual changes.
+    pub quantity: Option<i64>,
+    pub unit_cost: Option<Money>,
+    /// Physical stock right before the receipt.
+    pub stock_before: Option<i64>,
+    pub reason: String, // "purchase" | "transfer" | "manual" | "import"
+    pub document_type: Option<String>,
+    pub document_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Prefix of `image_url` values that point to an image stored locally.
+pub const LOCAL_IMAGE_PREFIX: &str = "local-image:";
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductImageRecord {
+    pub hash: String,
+    pub extension: String,
+    pub width: i64,
+    pub height: i64,
+    pub size_bytes: i64,
+    pub created_at: Option<String>,
+}
+
+/// A stored image with the files to display it.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductImage {
+    /// Value to save in `image_url` ("local-image:<hash>").
+    pub reference: String,
+    pub hash: String,
+    pub path: String,
+    pub thumbnail_path: String,
+    pub width: i64,
+    pub height: i64,
+    pub size_bytes: i64,
+}
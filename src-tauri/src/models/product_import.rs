@@ -0,0 +1,119 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+
+/// Column headers used by the export; also the default import mapping, so an
+/// exported file can be edited and imported back as is.
+pub const EXPORT_HEADERS: [&str; 12] = [
+    "codigo",
+    "nombre",
+    "categoria",
+    "precio",
+    "costo",
+    "stock",
+    "separado",
+    "stock_minimo",
+    "unidad",
+    "codigo_barras",
+    "talla",
+    "color",
+];
+
+/// Source column (header text) for each product field. `code` is the upsert
+/// key; unmapped fields keep their current value on existing products.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportColumnMapping {
+    pub code: String,
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub price: Option<String>,
+    pub cost: Option<String>,
+    pub stock: Option<String>,
+    pub min_stock: Option<String>,
+    pub unit: Option<String>,
+    pub barcode: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+}
+
+impl Default for ImportColumnMapping {
+    fn default() -> Self {
+        let column = |name: &str| Some(name.to_string());
+        Self {
+            code: "codigo".to_string(),
+            name: column("nombre"),
+            category: column("categoria"),
+            price: column("precio"),
+            cost: column("costo"),
+            stock: column("stock"),
+            min_stock: column("stock_minimo"),
+            unit: column("unidad"),
+            barcode: column("codigo_barras"),
+            size: column("talla"),
+            color: column("color"),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImportProductsPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    /// .csv (comma or semicolon separated), .xlsx or .xls
+    pub file_path: String,
+    /// Worksheet to read; the first one by default.
+    pub sheet: Option<String>,
+    pub mapping: Option<ImportColumnMapping>,
+    /// Create categories missing from the catalog instead of rejecting the rows.
+    #[serde(default)]
+    pub create_categories: bool,
+    /// Only validate and report; nothing is written.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+/// A validated spreadsheet row. `None` fields are left untouched on update
+/// and take the usual defaults on create.
+#[derive(Debug, Clone)]
+pub struct ProductImportRow {
+    pub row: usize,
+    pub code: String,
+    pub name: Option<String>,
+    pub category: Option<String>,
+    pub price: Option<Money>,
+    pub cost: Option<Money>,
+    pub stock: Option<i64>,
+    pub min_stock: Option<i64>,
+    pub unit: Option<String>,
+    pub barcode: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportRowError {
+    /// Spreadsheet row number (the header is row 1).
+    pub row: usize,
+    pub code: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProductImportReport {
+    pub total_rows: usize,
+    pub to_create: usize,
+    pub to_update: usize,
+    pub new_categories: Vec<String>,
+    pub errors: Vec<ImportRowError>,
+    /// False on dry runs and when there are errors: the import is all or nothing.
+    pub applied: bool,
+}
+
+/// First rows of a file, to build the column mapping.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImportFilePreview {
+    pub sheets: Vec<String>,
+    pub headers: Vec<String>,
+    pub sample_rows: Vec<Vec<String>>,
+}
@@ -1,4 +1,5 @@
 use chrono::NaiveDateTime;
+use crate::models::money::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -10,9 +11,10 @@ pub struct PurchaseOrder {
     pub supplier_name: Option<String>,
     pub batch_date: String,
     pub alias: Option<String>,
-    pub total_cost: f64,
+    pub total_cost: Money,
     pub created_by: Option<i64>,
     pub created_at: Option<NaiveDateTime>,
+    pub status: String, // "draft" | "received"
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow)]
@@ -24,9 +26,12 @@ pub struct PurchaseOrderItem {
     pub sku: Option<String>,
     pub category_id: Option<i64>,
     pub quantity: i64,
-    pub unit_cost: f64,
-    pub unit_price: f64,
+    pub unit_cost: Money,
+    pub unit_price: Money,
     pub created_at: Option<NaiveDateTime>,
+    /// Whether `unit_price` still has to be confirmed as the product's price:
+    /// `None` (same price) | "pending" | "applied" | "scheduled" | "discarded".
+    pub price_status: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -44,12 +49,15 @@ pub struct CreatePurchaseOrderPayload {
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CreatePurchaseOrderItemPayload {
+    /// Existing product or variant to restock; otherwise it is matched by `sku`.
+    #[serde(default)]
+    pub product_id: Option<i64>,
     pub product_name: String,
     pub sku: Option<String>,
     pub category_id: Option<i64>,
     pub quantity: i64,
-    pub unit_cost: f64,
-    pub unit_price: f64,
+    pub unit_cost: Money,
+    pub unit_price: Money,
     pub image_url: Option<String>,
 }
 
@@ -61,8 +69,38 @@ pub struct PurchaseOrderWithItems {
     pub supplier_name: Option<String>,
     pub batch_date: String,
     pub alias: Option<String>,
-    pub total_cost: f64,
+    pub total_cost: Money,
     pub created_by: Option<i64>,
     pub created_at: Option<NaiveDateTime>,
+    pub status: String,
     pub items: Vec<PurchaseOrderItem>,
 }
+
+/// A draft line as it is received.
+#[derive(Debug)]
+pub struct ReceivedItem {
+    pub item_id: i64,
+    pub product_id: i64,
+    pub quantity: i64,
+    pub unit_cost: Money,
+}
+
+/// Receives a draft order: stock is added and the expense recorded.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivePurchaseOrderPayload {
+    pub id: i64,
+    pub user_id: i64,
+    pub payment_method: String,
+    /// Quantities or costs that changed against the draft.
+    #[serde(default)]
+    pub items: Vec<ReceivePurchaseOrderItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivePurchaseOrderItemPayload {
+    pub item_id: i64,
+    pub quantity: i64,
+    pub unit_cost: Option<Money>,
+}
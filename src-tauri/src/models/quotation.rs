@@ -0,0 +1,72 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct Quotation {
+    pub id: i64,
+    pub uuid: String,
+    pub store_id: i64,
+    pub user_id: i64,
+    pub client_document: Option<String>,
+    pub client_phone: Option<String>,
+    pub client_name: Option<String>,
+    pub valid_until: String,
+    pub status: String,
+    pub notes: Option<String>,
+    pub subtotal: Money,
+    pub igv: Money,
+    pub total: Money,
+    pub order_id: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct QuotationItem {
+    pub id: i64,
+    pub quotation_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub unit_price: Money,
+    pub quantity: i64,
+    pub subtotal: Money,
+}
+
+/// Full quotation detail including its items.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct QuotationWithItems {
+    #[serde(flatten)]
+    pub quotation: Quotation,
+    pub items: Vec<QuotationItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateQuotationPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub client_document: Option<String>,
+    pub client_phone: Option<String>,
+    pub client_name: Option<String>,
+    pub valid_until: String, // YYYY-MM-DD
+    pub notes: Option<String>,
+    pub items: Vec<CreateQuotationItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateQuotationItemPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+    /// Negotiated price; defaults to the product's current price.
+    pub unit_price: Option<Money>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConvertQuotationPayload {
+    pub quotation_id: i64,
+    pub user_id: i64,
+    pub cash_session_id: i64,
+    pub payment_method: String,
+}
@@ -0,0 +1,65 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A product whose sellable stock is at or below its `min_stock`.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct LowStockProduct {
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub category_name: Option<String>,
+    pub stock: i64,
+    pub reserved_stock: i64,
+    pub min_stock: i64,
+    /// Units missing to get back to `min_stock`.
+    pub shortage: i64,
+}
+
+/// Stock and recent sales of a product, input for the reorder suggestions.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductSalesVelocity {
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub category_id: Option<i64>,
+    pub stock: i64,
+    pub min_stock: i64,
+    pub unit_cost: Money,
+    pub unit_price: Money,
+    pub units_sold: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ReorderSuggestion {
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub category_id: Option<i64>,
+    pub stock: i64,
+    pub min_stock: i64,
+    pub units_sold: i64,
+    /// Average units sold per day over the analysed period.
+    pub daily_velocity: f64,
+    /// Days the current stock lasts at that pace (`None` without sales).
+    pub days_of_cover: Option<f64>,
+    pub suggested_quantity: i64,
+    pub unit_cost: Money,
+    pub unit_price: Money,
+    pub estimated_cost: Money,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateReorderPurchaseOrderPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub supplier_name: Option<String>,
+    /// Sales history used for the velocity; defaults to 30 days.
+    pub days: Option<i64>,
+    /// Days of sales the order should cover; defaults to 30 days.
+    pub cover_days: Option<i64>,
+    /// Only these products; all suggestions when empty.
+    #[serde(default)]
+    pub product_ids: Vec<i64>,
+}
@@ -1,3 +1,4 @@
+use crate::models::money::Money;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
@@ -6,9 +7,12 @@ use sqlx::FromRow;
 pub struct CreateOrderItemPayload {
     pub product_id: i64,
     pub product_name: String,
-    pub unit_price: f64,
+    pub unit_price: Money,
     pub quantity: i64,
-    pub subtotal: f64,
+    pub subtotal: Money,
+    /// Price list the unit price came from; set by the server when a list applies.
+    #[serde(default)]
+    pub price_list_id: Option<i64>,
 }
 
 /// Full payload received from the frontend to create a sale.
@@ -18,13 +22,27 @@ pub struct CreateOrderPayload {
     pub client_document: Option<String>,
     pub client_phone: Option<String>,
     pub client_name: Option<String>,
-    pub payment_method: String, // "cash" | "card" | "yape"
+    pub payment_method: String, // "cash" | "card" | "yape" | "gift_card" | "store_credit"
     pub items: Vec<CreateOrderItemPayload>,
-    pub subtotal: f64,
-    pub igv: f64,
-    pub total: f64,
+    pub subtotal: Money,
+    pub igv: Money,
+    pub total: Money,
     pub cash_session_id: i64,
     pub store_id: i64,
+    #[serde(default)]
+    pub document_type: Option<String>, // "boleta" | "factura"
+    /// Gift card / store credit code used to pay part or all of the total.
+    #[serde(default)]
+    pub stored_value_code: Option<String>,
+    /// Amount charged to `stored_value_code`; the rest goes to `payment_method`.
+    #[serde(default)]
+    pub stored_value_amount: Option<Money>,
+    /// Loyalty points redeemed as payment; needs `client_document` or `client_phone`.
+    #[serde(default)]
+    pub loyalty_points: Option<i64>,
+    /// Price list chosen for this sale; defaults to the customer's list.
+    #[serde(default)]
+    pub price_list_id: Option<i64>,
 }
 
 /// Represents a created order returned to the frontend.
@@ -43,10 +61,17 @@ pub struct Sale {
     pub client_phone: Option<String>,
     pub client_name: Option<String>,
     pub payment_method: String,
-    pub subtotal: f64,
-    pub igv: f64,
-    pub total: f64,
+    pub subtotal: Money,
+    pub igv: Money,
+    pub total: Money,
     pub store_id: i64,
+    pub document_type: Option<String>,
+    pub series: Option<String>,
+    pub number: Option<i64>,
+    pub stored_value_amount: Money,
+    pub customer_id: Option<i64>,
+    pub loyalty_amount: Money,
+    pub status: String, // "completed" | "voided"
     pub created_at: String,
 }
 
@@ -56,9 +81,22 @@ pub struct SaleItem {
     pub id: i64,
     pub product_id: i64,
     pub product_name: String,
-    pub unit_price: f64,
+    pub unit_price: Money,
     pub quantity: i64,
-    pub subtotal: f64,
+    pub subtotal: Money,
+    pub price_list_id: Option<i64>,
+    pub price_list_name: Option<String>,
+}
+
+/// A product sold inside a pack line, with its share of the line subtotal.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct SaleItemComponent {
+    pub order_item_id: i64,
+    pub product_id: i64,
+    pub product_name: String,
+    pub quantity: i64,
+    pub unit_cost: Money,
+    pub subtotal: Money,
 }
 
 /// Full sale detail including its items.
@@ -67,6 +105,8 @@ pub struct SaleDetail {
     #[serde(flatten)]
     pub sale: Sale,
     pub items: Vec<SaleItem>,
+    /// Components of the pack lines in `items`.
+    pub components: Vec<SaleItemComponent>,
 }
 
 /// Flat row used for the "export all items" CSV.
@@ -78,8 +118,10 @@ pub struct OrderItemExport {
     pub client_document: Option<String>,
     pub payment_method: String,
     pub product_name: String,
-    pub unit_price: f64,
+    pub unit_price: Money,
     pub quantity: i64,
-    pub subtotal: f64,
+    pub subtotal: Money,
+    /// Product cost per unit when it was sold.
+    pub unit_cost: Money,
     pub store_id: Option<i64>,
 }
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Reason codes accepted for a manual adjustment.
+pub const ADJUSTMENT_REASONS: [&str; 4] = ["damage", "theft", "found", "correction"];
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockAdjustment {
+    pub id: i64,
+    pub store_id: i64,
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: Option<String>,
+    pub reason_code: String, // "damage" | "theft" | "found" | "correction"
+    pub delta: i64,
+    pub note: Option<String>,
+    pub status: String, // "pending" | "applied" | "rejected"
+    pub requested_by: i64,
+    pub requested_by_name: Option<String>,
+    pub reviewed_by: Option<i64>,
+    pub reviewed_by_name: Option<String>,
+    pub reviewed_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStockAdjustmentPayload {
+    pub store_id: i64,
+    pub product_id: i64,
+    pub reason_code: String,
+    /// Units to add (positive) or remove (negative).
+    pub delta: i64,
+    pub note: Option<String>,
+    pub user_id: i64,
+    /// Manager who authorised the adjustment at the counter, if any.
+    pub approved_by: Option<i64>,
+    /// That manager's password; required with `approved_by`.
+    #[serde(default)]
+    pub manager_password: Option<String>,
+}
@@ -0,0 +1,73 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// One line of the kardex.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockMovement {
+    pub id: i64,
+    pub product_id: i64,
+    pub store_id: i64,
+    pub delta: i64,
+    /// Physical stock right after the movement.
+    pub balance: i64,
+    pub reason: String, // "sale" | "purchase" | "adjustment" | "return" | "transfer"
+    pub document_type: Option<String>,
+    pub document_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub user_name: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Why stock changed and who changed it; saved with the movement.
+#[derive(Debug, Clone)]
+pub struct MovementSource {
+    pub reason: &'static str,
+    pub document_type: Option<&'static str>,
+    pub document_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub note: Option<String>,
+}
+
+impl MovementSource {
+    /// A movement caused by a document (sale, purchase order, transfer, ...).
+    pub fn document(
+        reason: &'static str,
+        document_type: &'static str,
+        document_id: i64,
+        user_id: Option<i64>,
+    ) -> Self {
+        Self {
+            reason,
+            document_type: Some(document_type),
+            document_id: Some(document_id),
+            user_id,
+            note: None,
+        }
+    }
+
+    /// A movement entered by hand, without a source document.
+    pub fn manual(reason: &'static str, user_id: Option<i64>, note: &str) -> Self {
+        Self {
+            reason,
+            document_type: None,
+            document_id: None,
+            user_id,
+            note: Some(note.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Kardex {
+    pub product_id: i64,
+    pub product_code: Option<String>,
+    pub product_name: String,
+    pub date_from: String,
+    pub date_to: String,
+    pub opening_balance: i64,
+    pub units_in: i64,
+    pub units_out: i64,
+    pub closing_balance: i64,
+    pub movements: Vec<StockMovement>,
+}
@@ -0,0 +1,76 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockTransfer {
+    pub id: i64,
+    pub origin_store_id: i64,
+    pub origin_store_name: Option<String>,
+    pub destination_store_id: i64,
+    pub destination_store_name: Option<String>,
+    pub status: String, // "draft" | "dispatched" | "received" | "discrepancy" | "cancelled"
+    pub notes: Option<String>,
+    pub created_by: i64,
+    pub dispatched_by: Option<i64>,
+    pub dispatched_at: Option<String>,
+    pub received_by: Option<i64>,
+    pub received_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StockTransferItem {
+    pub id: i64,
+    pub transfer_id: i64,
+    pub product_id: i64,
+    pub product_code: String,
+    pub product_name: String,
+    pub quantity: i64,
+    pub received_quantity: Option<i64>,
+    pub destination_product_id: Option<i64>,
+    /// Origin cost per unit, set when dispatched.
+    pub unit_cost: Option<Money>,
+}
+
+/// Full transfer detail including its items.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StockTransferDetail {
+    #[serde(flatten)]
+    pub transfer: StockTransfer,
+    pub items: Vec<StockTransferItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStockTransferPayload {
+    pub origin_store_id: i64,
+    pub destination_store_id: i64,
+    pub user_id: i64,
+    pub notes: Option<String>,
+    pub items: Vec<CreateStockTransferItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateStockTransferItemPayload {
+    pub product_id: i64,
+    pub quantity: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiveStockTransferPayload {
+    pub transfer_id: i64,
+    pub user_id: i64,
+    /// Units counted on arrival per item; items left out are taken as complete.
+    #[serde(default)]
+    pub items: Vec<ReceivedItemPayload>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceivedItemPayload {
+    pub item_id: i64,
+    pub received_quantity: i64,
+}
@@ -10,4 +10,6 @@ pub struct Store {
     pub address: Option<String>,
     pub is_active: bool,
     pub created_at: Option<NaiveDateTime>,
+    pub ruc: Option<String>,
+    pub logo_path: Option<String>,
 }
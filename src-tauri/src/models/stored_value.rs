@@ -0,0 +1,69 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// A gift card or a customer's store credit.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StoredValueAccount {
+    pub id: i64,
+    pub uuid: String,
+    pub code: String,
+    pub kind: String, // "gift_card" | "store_credit"
+    pub store_id: i64,
+    pub customer_document: Option<String>,
+    pub customer_name: Option<String>,
+    pub balance: Money,
+    pub status: String,
+    pub expires_at: Option<String>,
+    pub created_by: Option<i64>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct StoredValueEntry {
+    pub id: i64,
+    pub account_id: i64,
+    pub entry_type: String,
+    pub amount: Money,
+    pub balance_after: Money,
+    pub order_id: Option<i64>,
+    pub cash_session_id: Option<i64>,
+    pub user_id: Option<i64>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+/// Balance inquiry result: the account and its movements.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StoredValueStatement {
+    #[serde(flatten)]
+    pub account: StoredValueAccount,
+    pub entries: Vec<StoredValueEntry>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueGiftCardPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub amount: Money,
+    pub payment_method: String,
+    /// Pre-printed card code; generated when empty.
+    pub code: Option<String>,
+    pub customer_document: Option<String>,
+    pub customer_name: Option<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IssueStoreCreditPayload {
+    pub store_id: i64,
+    pub user_id: i64,
+    pub customer_document: String,
+    pub customer_name: Option<String>,
+    pub amount: Money,
+    pub note: Option<String>,
+    /// Sale being returned, if the credit comes from a return.
+    pub order_id: Option<i64>,
+}
@@ -0,0 +1,114 @@
+use crate::models::money::Money;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// Parent style (e.g. "Short Playero") whose size/colour variants are products.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductStyle {
+    pub id: i64,
+    pub store_id: i64,
+    pub code: String,
+    pub name: String,
+    pub category_id: Option<i64>,
+    pub category_name: Option<String>,
+    /// Price of the variants that have no price of their own.
+    pub price: Money,
+    pub cost: Money,
+    pub unit: Option<String>,
+    pub image_url: Option<String>,
+    pub is_active: bool,
+    pub created_at: String,
+    pub variant_count: i64,
+    /// Sellable stock of all variants.
+    pub stock: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct ProductVariant {
+    pub id: i64,
+    pub style_id: i64,
+    /// SKU
+    pub code: Option<String>,
+    pub barcode: Option<String>,
+    pub name: String,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    /// Effective price: `price_override` or the style price.
+    pub price: Money,
+    pub price_override: Option<Money>,
+    pub cost: Money,
+    pub stock: i64,
+    pub reserved_stock: i64,
+    pub is_active: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SaveProductStylePayload {
+    pub id: Option<i64>,
+    pub store_id: i64,
+    pub code: String,
+    pub name: String,
+    pub category_id: Option<i64>,
+    pub price: Money,
+    #[serde(default)]
+    pub cost: Money,
+    pub unit: Option<String>,
+    pub image_url: Option<String>,
+    /// Who saves it, for the price history.
+    #[serde(default)]
+    pub user_id: Option<i64>,
+}
+
+/// Creates one variant per size × colour that the style does not have yet.
+/// Either list may be empty (e.g. one-size items in several colours).
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenerateVariantsPayload {
+    pub style_id: i64,
+    #[serde(default)]
+    pub sizes: Vec<String>,
+    #[serde(default)]
+    pub colors: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateVariantPayload {
+    pub id: i64,
+    pub code: Option<String>,
+    pub barcode: Option<String>,
+    /// `None` goes back to the style price.
+    pub price_override: Option<Money>,
+    #[serde(default)]
+    pub user_id: Option<i64>,
+}
+
+/// Stock, sales and purchases of one variant in a period.
+#[derive(Debug, Serialize, Deserialize, FromRow)]
+pub struct VariantReportRow {
+    pub product_id: i64,
+    pub style_id: i64,
+    pub code: Option<String>,
+    pub size: Option<String>,
+    pub color: Option<String>,
+    pub stock: i64,
+    pub reserved_stock: i64,
+    pub units_sold: i64,
+    pub sales_amount: Money,
+    pub units_purchased: i64,
+}
+
+/// The same figures rolled up to the style, with its variants.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StyleReportRow {
+    pub style_id: i64,
+    pub code: String,
+    pub name: String,
+    pub stock: i64,
+    pub reserved_stock: i64,
+    pub units_sold: i64,
+    pub sales_amount: Money,
+    pub units_purchased: i64,
+    pub variants: Vec<VariantReportRow>,
+}
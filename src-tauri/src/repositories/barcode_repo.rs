@@ -0,0 +1,111 @@
+use crate::models::barcode::{AssignedBarcode, LabelProduct};
+use sqlx::SqlitePool;
+
+pub struct BarcodeRepository {
+    pool: SqlitePool,
+}
+
+impl BarcodeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Numbers the store's products that have no barcode (only `product_ids`
+    /// when given) with the next values of the store's `symbology` sequence,
+    /// turned into a barcode by `format`. Values already used as a code or
+    /// barcode in the store are skipped.
+    pub async fn assign(
+        &self,
+        store_id: i64,
+        product_ids: &[i64],
+        symbology: &str,
+        format: &(dyn Fn(i64) -> String + Sync),
+    ) -> Result<Vec<AssignedBarcode>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("INSERT OR IGNORE INTO barcode_sequences (store_id, symbology) VALUES (?, ?)")
+            .bind(store_id)
+            .bind(symbology)
+            .execute(&mut *tx)
+            .await?;
+
+        let pending: Vec<i64> = sqlx::query_scalar(
+            r#"
+            SELECT id FROM products
+            WHERE store_id = ? AND is_active = 1 AND (barcode IS NULL OR TRIM(barcode) = '')
+            ORDER BY id ASC
+            "#,
+        )
+        .bind(store_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let mut assigned = Vec::new();
+        for product_id in pending
+            .into_iter()
+            .filter(|id| product_ids.is_empty() || product_ids.contains(id))
+        {
+            let barcode = loop {
+                let seq: i64 = sqlx::query_scalar(
+                    r#"
+                    UPDATE barcode_sequences SET last_value = last_value + 1
+                    WHERE store_id = ? AND symbology = ?
+                    RETURNING last_value
+                    "#,
+                )
+                .bind(store_id)
+                .bind(symbology)
+                .fetch_one(&mut *tx)
+                .await?;
+                let candidate = format(seq);
+
+                let taken: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM products WHERE store_id = ?1 AND (barcode = ?2 OR code = ?2))",
+                )
+                .bind(store_id)
+                .bind(&candidate)
+                .fetch_one(&mut *tx)
+                .await?;
+                if !taken {
+                    break candidate;
+                }
+            };
+
+            sqlx::query(
+                "UPDATE products SET barcode = ?, code = COALESCE(NULLIF(TRIM(code), ''), ?) WHERE id = ?",
+            )
+            .bind(&barcode)
+            .bind(&barcode)
+            .bind(product_id)
+            .execute(&mut *tx)
+            .await?;
+            assigned.push(AssignedBarcode { product_id, barcode });
+        }
+
+        tx.commit().await?;
+        Ok(assigned)
+    }
+
+    pub async fn find_label_products(
+        &self,
+        store_id: i64,
+        product_ids: &[i64],
+    ) -> Result<Vec<LabelProduct>, sqlx::Error> {
+        let mut products = Vec::with_capacity(product_ids.len());
+        for product_id in product_ids {
+            let product = sqlx::query_as::<_, LabelProduct>(
+                r#"
+                SELECT id AS product_id, name, size, color, CAST(price AS INTEGER) AS price, barcode
+                FROM products
+                WHERE id = ? AND store_id = ? AND is_active = 1
+                "#,
+            )
+            .bind(product_id)
+            .bind(store_id)
+            .fetch_optional(&self.pool)
+            .await?;
+            products.extend(product);
+        }
+        Ok(products)
+    }
+}
@@ -1,5 +1,6 @@
 use crate::models::cash::{CashSession, CloseCashPayload, OpenCashPayload, UpdateExpensePayload};
-use sqlx::{Row, SqlitePool};
+use crate::models::money::Money;
+use sqlx::{Row, Sqlite, SqlitePool, Transaction};
 
 pub struct CashRepository {
     pool: SqlitePool,
@@ -28,6 +29,13 @@ impl CashRepository {
         .await
     }
 
+    pub async fn get_session_by_id(&self, session_id: i64) -> Result<Option<CashSession>, sqlx::Error> {
+        sqlx::query_as::<_, CashSession>("SELECT * FROM cash_sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
     pub async fn open_session(&self, payload: OpenCashPayload) -> Result<i64, sqlx::Error> {
         let id = sqlx::query(
             r#"
@@ -53,7 +61,7 @@ impl CashRepository {
         session_id: i64,
         payload: CloseCashPayload,
     ) -> Result<(), sqlx::Error> {
-        let difference = (payload.real_closing_cash + payload.real_closing_virtual) - (sqlx::query_scalar::<_, f64>("SELECT expected_closing_cash + expected_closing_virtual FROM cash_sessions WHERE id = ?")
+        let difference = (payload.real_closing_cash + payload.real_closing_virtual) - (sqlx::query_scalar::<_, Money>("SELECT expected_closing_cash + expected_closing_virtual FROM cash_sessions WHERE id = ?")
                             .bind(session_id)
                             .fetch_one(&self.pool)
                             .await?);
@@ -87,7 +95,7 @@ impl CashRepository {
         &self,
         session_id: i64,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
     ) -> Result<i64, sqlx::Error> {
         let mut tx = self.pool.begin().await?;
@@ -233,12 +241,40 @@ impl CashRepository {
     pub async fn add_expense_standalone(
         &self,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
         category: Option<String>,
         supplier: Option<String>,
         store_id: i64,
         uuid: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let id = Self::insert_expense_standalone(
+            &mut tx,
+            &description,
+            amount,
+            &payment_method,
+            category.as_deref(),
+            supplier.as_deref(),
+            store_id,
+            uuid,
+        )
+        .await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Same as `add_expense_standalone` but inside the caller's transaction.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn insert_expense_standalone(
+        tx: &mut Transaction<'_, Sqlite>,
+        description: &str,
+        amount: Money,
+        payment_method: &str,
+        category: Option<&str>,
+        supplier: Option<&str>,
+        store_id: i64,
+        uuid: &str,
     ) -> Result<i64, sqlx::Error> {
         let id = sqlx::query(
             "INSERT INTO expenses (uuid, cash_session_id, description, amount, payment_method, category, supplier, store_id) VALUES (?, NULL, ?, ?, ?, ?, ?, ?)"
@@ -246,11 +282,11 @@ impl CashRepository {
         .bind(uuid)
         .bind(description)
         .bind(amount)
-        .bind(&payment_method)
-        .bind(&category)
-        .bind(&supplier)
+        .bind(payment_method)
+        .bind(category)
+        .bind(supplier)
         .bind(store_id)
-        .execute(&self.pool)
+        .execute(&mut **tx)
         .await?
         .last_insert_rowid();
 
@@ -261,10 +297,27 @@ impl CashRepository {
         &self,
         session_id: i64,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
     ) -> Result<i64, sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        let id = Self::insert_other_income(&mut tx, session_id, &description, amount, &payment_method).await?;
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Same as `add_other_income` but inside the caller's transaction.
+    pub async fn insert_other_income(
+        tx: &mut Transaction<'_, Sqlite>,
+        session_id: i64,
+        description: &str,
+        amount: Money,
+        payment_method: &str,
+    ) -> Result<i64, sqlx::Error> {
+        let store_id = sqlx::query_scalar::<_, i64>("SELECT store_id FROM cash_sessions WHERE id = ?")
+            .bind(session_id)
+            .fetch_one(&mut **tx)
+            .await?;
 
         let id = sqlx::query(
             "INSERT INTO other_income (cash_session_id, description, amount, payment_method, store_id) VALUES (?, ?, ?, ?, ?)"
@@ -272,9 +325,9 @@ impl CashRepository {
         .bind(session_id)
         .bind(description)
         .bind(amount)
-        .bind(&payment_method)
-        .bind(sqlx::query_scalar::<_, i64>("SELECT store_id FROM cash_sessions WHERE id = ?").bind(session_id).fetch_one(&mut *tx).await?)
-        .execute(&mut *tx)
+        .bind(payment_method)
+        .bind(store_id)
+        .execute(&mut **tx)
         .await?
         .last_insert_rowid();
 
@@ -282,17 +335,16 @@ impl CashRepository {
             sqlx::query("UPDATE cash_sessions SET expected_closing_cash = expected_closing_cash + ? WHERE id = ?")
                 .bind(amount)
                 .bind(session_id)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await?;
         } else {
             sqlx::query("UPDATE cash_sessions SET expected_closing_virtual = expected_closing_virtual + ? WHERE id = ?")
                 .bind(amount)
                 .bind(session_id)
-                .execute(&mut *tx)
+                .execute(&mut **tx)
                 .await?;
         }
 
-        tx.commit().await?;
         Ok(id)
     }
 
@@ -306,7 +358,7 @@ impl CashRepository {
     pub async fn update_expected_balances(
         &self,
         session_id: i64,
-        amount: f64,
+        amount: Money,
         payment_method: String,
     ) -> Result<(), sqlx::Error> {
         if payment_method == "cash" {
@@ -331,8 +383,8 @@ impl CashRepository {
     ) -> Result<Vec<serde_json::Value>, sqlx::Error> {
         // Fetch Orders
         let orders = sqlx::query(
-            "SELECT id, total as amount, payment_method, created_at, 'Venta #' || id as description, 'income' as type 
-             FROM orders WHERE cash_session_id = ?"
+            "SELECT id, total - stored_value_amount - loyalty_amount as amount, payment_method, created_at, 'Venta #' || id as description, 'income' as type 
+             FROM orders WHERE cash_session_id = ? AND payment_method != 'layaway' AND status = 'completed' AND total - stored_value_amount - loyalty_amount > 0"
         )
         .bind(session_id)
         .fetch_all(&self.pool)
@@ -356,12 +408,21 @@ impl CashRepository {
         .fetch_all(&self.pool)
         .await?;
 
+        // Fetch Layaway deposits
+        let layaway_payments = sqlx::query(
+            "SELECT id, amount, payment_method, created_at, 'Abono separación #' || layaway_id as description, 'income' as type 
+             FROM layaway_payments WHERE cash_session_id = ?",
+        )
+        .bind(session_id)
+        .fetch_all(&self.pool)
+        .await?;
+
         let mut all: Vec<serde_json::Value> = Vec::new();
 
         for o in orders {
             all.push(serde_json::json!({
                 "id": format!("order_{}", o.get::<i64, _>("id")),
-                "amount": o.get::<f64, _>("amount"),
+                "amount": o.get::<Money, _>("amount"),
                 "payment_method": o.get::<String, _>("payment_method"),
                 "created_at": o.get::<String, _>("created_at"),
                 "description": o.get::<String, _>("description"),
@@ -373,7 +434,7 @@ impl CashRepository {
         for e in expenses {
             all.push(serde_json::json!({
                 "id": format!("expense_{}", e.get::<i64, _>("id")),
-                "amount": e.get::<f64, _>("amount"),
+                "amount": e.get::<Money, _>("amount"),
                 "payment_method": e.get::<String, _>("payment_method"),
                 "created_at": e.get::<String, _>("created_at"),
                 "description": e.get::<String, _>("description"),
@@ -385,7 +446,7 @@ impl CashRepository {
         for i in other_income {
             all.push(serde_json::json!({
                 "id": format!("income_{}", i.get::<i64, _>("id")),
-                "amount": i.get::<f64, _>("amount"),
+                "amount": i.get::<Money, _>("amount"),
                 "payment_method": i.get::<String, _>("payment_method"),
                 "created_at": i.get::<String, _>("created_at"),
                 "description": i.get::<String, _>("description"),
@@ -394,6 +455,18 @@ impl CashRepository {
             }));
         }
 
+        for p in layaway_payments {
+            all.push(serde_json::json!({
+                "id": format!("layaway_payment_{}", p.get::<i64, _>("id")),
+                "amount": p.get::<Money, _>("amount"),
+                "payment_method": p.get::<String, _>("payment_method"),
+                "created_at": p.get::<String, _>("created_at"),
+                "description": p.get::<String, _>("description"),
+                "type": p.get::<String, _>("type"),
+                "category": "Separación"
+            }));
+        }
+
         all.sort_by(|a, b| b["created_at"].as_str().cmp(&a["created_at"].as_str()));
 
         Ok(all)
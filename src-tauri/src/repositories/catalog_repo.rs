@@ -0,0 +1,236 @@
+use crate::models::catalog::{
+    AddCatalogProductToStorePayload, CatalogProduct, StoreInventory, UpdateCatalogProductPayload,
+};
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::price_change_repo::PriceChangeRepository;
+use sqlx::SqlitePool;
+
+const CATALOG_SELECT: &str = r#"
+    SELECT c.id, c.code, c.name, c.unit, c.image_url, c.barcode, c.size, c.color, c.price,
+           CAST(c.created_at AS TEXT) AS created_at,
+           COUNT(p.id) AS store_count,
+           COALESCE(SUM(p.stock), 0) AS total_stock
+    FROM catalog_products c
+    LEFT JOIN products p ON p.catalog_id = c.id AND p.is_active = 1
+"#;
+
+pub struct CatalogRepository {
+    pool: SqlitePool,
+}
+
+impl CatalogRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Catalog items by name; `search` matches name or code, or the exact barcode.
+    pub async fn find_all(&self, search: Option<&str>) -> Result<Vec<CatalogProduct>, sqlx::Error> {
+        let pattern = search.map(|s| format!("%{}%", s));
+        sqlx::query_as::<_, CatalogProduct>(&format!(
+            r#"
+            {}
+            WHERE ?1 IS NULL OR c.name LIKE ?1 OR c.code LIKE ?1 OR c.barcode = ?2
+            GROUP BY c.id
+            ORDER BY c.name
+            "#,
+            CATALOG_SELECT
+        ))
+        .bind(pattern)
+        .bind(search)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<CatalogProduct>, sqlx::Error> {
+        sqlx::query_as::<_, CatalogProduct>(&format!(
+            "{} WHERE c.id = ? GROUP BY c.id",
+            CATALOG_SELECT
+        ))
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<CatalogProduct>, sqlx::Error> {
+        sqlx::query_as::<_, CatalogProduct>(&format!(
+            "{} WHERE c.code = ? GROUP BY c.id",
+            CATALOG_SELECT
+        ))
+        .bind(code)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// The item's row in every store that has (or had) it.
+    pub async fn find_inventory(&self, catalog_id: i64) -> Result<Vec<StoreInventory>, sqlx::Error> {
+        sqlx::query_as::<_, StoreInventory>(
+            r#"
+            SELECT p.id AS product_id, p.store_id, s.name AS store_name,
+                   p.category_id, cat.name AS category_name, p.stock, p.min_stock,
+                   CAST(p.cost AS INTEGER) AS cost, CAST(p.price AS INTEGER) AS price,
+                   p.local_price, p.is_active
+            FROM products p
+            INNER JOIN stores s ON s.id = p.store_id
+            LEFT JOIN categories cat ON cat.id = p.category_id
+            WHERE p.catalog_id = ?
+            ORDER BY s.name
+            "#,
+        )
+        .bind(catalog_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// The item's row in a store, active or not.
+    pub async fn find_store_row(&self, catalog_id: i64, store_id: i64) -> Result<Option<(i64, bool)>, sqlx::Error> {
+        sqlx::query_as("SELECT id, is_active FROM products WHERE catalog_id = ? AND store_id = ?")
+            .bind(catalog_id)
+            .bind(store_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Whether the item is a variant of a style in some store.
+    pub async fn is_variant(&self, catalog_id: i64) -> Result<bool, sqlx::Error> {
+        let found: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM products WHERE catalog_id = ? AND style_id IS NOT NULL LIMIT 1")
+                .bind(catalog_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(found.is_some())
+    }
+
+    /// Updates the shared data; the triggers copy it to every store, and the
+    /// base price to the stores without a local one. Those price changes are
+    /// recorded with `source`.
+    pub async fn update(
+        &self,
+        payload: &UpdateCatalogProductPayload,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Vec<(i64, Money)> =
+            sqlx::query_as("SELECT id, CAST(price AS INTEGER) FROM products WHERE catalog_id = ?")
+                .bind(payload.id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE catalog_products
+            SET code = ?, name = ?, unit = ?, image_url = ?, barcode = ?, size = ?, color = ?, price = ?
+            WHERE id = ?
+            "#,
+        )
+        .bind(&payload.code)
+        .bind(&payload.name)
+        .bind(&payload.unit)
+        .bind(&payload.image_url)
+        .bind(&payload.barcode)
+        .bind(&payload.size)
+        .bind(&payload.color)
+        .bind(payload.price)
+        .bind(payload.id)
+        .execute(&mut *tx)
+        .await?;
+
+        PriceChangeRepository::record_changes(&mut tx, &previous, source).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Starts stocking the item in a store with no units; the barcode is only
+    /// copied if no other product of the store uses it. An item left without a
+    /// catalog code by the migration takes the code its stores use. Returns
+    /// the product id.
+    pub async fn add_to_store(&self, payload: &AddCatalogProductToStorePayload) -> Result<i64, sqlx::Error> {
+        let id = sqlx::query(
+            r#"
+            INSERT INTO products (code, name, category_id, price, cost, stock, min_stock, unit, image_url, store_id, size, color, barcode, catalog_id, local_price)
+            SELECT COALESCE(c.code, (SELECT q.code FROM products q WHERE q.catalog_id = c.id ORDER BY q.id LIMIT 1)),
+                   c.name, ?1, COALESCE(?2, c.price), 0, 0, COALESCE(?3, 5), c.unit, c.image_url, ?4, c.size, c.color,
+                   CASE WHEN EXISTS (SELECT 1 FROM products d WHERE d.store_id = ?4 AND d.barcode = c.barcode)
+                        THEN NULL ELSE c.barcode END,
+                   c.id,
+                   CASE WHEN ?2 <> c.price THEN ?2 END
+            FROM catalog_products c
+            WHERE c.id = ?5
+            "#,
+        )
+        .bind(payload.category_id)
+        .bind(payload.local_price)
+        .bind(payload.min_stock)
+        .bind(payload.store_id)
+        .bind(payload.catalog_id)
+        .execute(&self.pool)
+        .await?
+        .last_insert_rowid();
+        Ok(id)
+    }
+
+    /// Brings back a deleted store row with the new settings.
+    pub async fn reactivate(
+        &self,
+        product_id: i64,
+        payload: &AddCatalogProductToStorePayload,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let previous = PriceChangeRepository::current_price(&mut tx, product_id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE products SET
+                is_active = 1,
+                category_id = ?1,
+                min_stock = COALESCE(?2, min_stock),
+                price = COALESCE(?3, (SELECT c.price FROM catalog_products c WHERE c.id = products.catalog_id))
+            WHERE id = ?4
+            "#,
+        )
+        .bind(payload.category_id)
+        .bind(payload.min_stock)
+        .bind(payload.local_price)
+        .bind(product_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let price = PriceChangeRepository::current_price(&mut tx, product_id).await?;
+        PriceChangeRepository::record_change(&mut tx, product_id, previous, price, source).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Sets the store's own price, or goes back to the base price with `None`.
+    pub async fn set_local_price(
+        &self,
+        product_id: i64,
+        local_price: Option<Money>,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let previous = PriceChangeRepository::current_price(&mut tx, product_id).await?;
+
+        sqlx::query(
+            r#"
+            UPDATE products
+            SET price = COALESCE(?, (SELECT c.price FROM catalog_products c WHERE c.id = products.catalog_id))
+            WHERE id = ?
+            "#,
+        )
+        .bind(local_price)
+        .bind(product_id)
+        .execute(&mut *tx)
+        .await?;
+
+        let price = PriceChangeRepository::current_price(&mut tx, product_id).await?;
+        PriceChangeRepository::record_change(&mut tx, product_id, previous, price, source).await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+}
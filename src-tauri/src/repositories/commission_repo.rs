@@ -0,0 +1,181 @@
+use crate::models::commission::{
+    CommissionLine, CommissionRule, SaveCommissionRulePayload, VoidedSummary,
+};
+use crate::models::money::Money;
+use sqlx::SqlitePool;
+
+/// Amount refunded as store credit against an order (aliased `o`).
+const RETURNED_SQL: &str = "(SELECT COALESCE(SUM(l.amount), 0) FROM stored_value_ledger l WHERE l.order_id = o.id AND l.entry_type IN ('issue', 'topup'))";
+
+pub struct CommissionRepository {
+    pool: SqlitePool,
+}
+
+impl CommissionRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Rules that apply to a store: its own plus the ones for all stores.
+    pub async fn get_rules(&self, store_id: i64) -> Result<Vec<CommissionRule>, sqlx::Error> {
+        sqlx::query_as::<_, CommissionRule>(
+            r#"
+            SELECT
+                r.id,
+                r.store_id,
+                r.scope,
+                r.category_id,
+                c.name AS category_name,
+                r.product_id,
+                p.name AS product_name,
+                CAST(r.rate AS REAL) AS rate,
+                CAST(r.min_monthly_volume AS INTEGER) AS min_monthly_volume,
+                r.is_active,
+                r.created_at
+            FROM commission_rules r
+            LEFT JOIN categories c ON c.id = r.category_id
+            LEFT JOIN products p ON p.id = r.product_id
+            WHERE r.store_id IS NULL OR r.store_id = ?
+            ORDER BY r.scope ASC, r.min_monthly_volume ASC, r.id ASC
+            "#,
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn save_rule(&self, payload: &SaveCommissionRulePayload) -> Result<i64, sqlx::Error> {
+        match payload.id {
+            Some(id) => {
+                sqlx::query(
+                    r#"
+                    UPDATE commission_rules
+                    SET store_id = ?, scope = ?, category_id = ?, product_id = ?, rate = ?, min_monthly_volume = ?, is_active = ?
+                    WHERE id = ?
+                    "#,
+                )
+                .bind(payload.store_id)
+                .bind(&payload.scope)
+                .bind(payload.category_id)
+                .bind(payload.product_id)
+                .bind(payload.rate)
+                .bind(payload.min_monthly_volume)
+                .bind(payload.is_active)
+                .bind(id)
+                .execute(&self.pool)
+                .await?;
+                Ok(id)
+            }
+            None => {
+                let result = sqlx::query(
+                    r#"
+                    INSERT INTO commission_rules (store_id, scope, category_id, product_id, rate, min_monthly_volume, is_active)
+                    VALUES (?, ?, ?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(payload.store_id)
+                .bind(&payload.scope)
+                .bind(payload.category_id)
+                .bind(payload.product_id)
+                .bind(payload.rate)
+                .bind(payload.min_monthly_volume)
+                .bind(payload.is_active)
+                .execute(&self.pool)
+                .await?;
+                Ok(result.last_insert_rowid())
+            }
+        }
+    }
+
+    pub async fn delete_rule(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM commission_rules WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Lines of the completed (not voided) sales of a store between two dates
+    /// (inclusive, `YYYY-MM-DD`), with the seller name as in `get_sales`.
+    pub async fn get_lines(
+        &self,
+        store_id: i64,
+        date_from: &str,
+        date_to: &str,
+    ) -> Result<Vec<CommissionLine>, sqlx::Error> {
+        sqlx::query_as::<_, CommissionLine>(&format!(
+            r#"
+            SELECT
+                o.id AS order_id,
+                o.user_id,
+                u.username AS user_name,
+                substr(o.created_at, 1, 7) AS month,
+                CAST(o.total AS INTEGER) AS order_total,
+                CAST({} AS INTEGER) AS returned_amount,
+                oi.product_id,
+                p.category_id,
+                CAST(oi.subtotal AS INTEGER) AS subtotal
+            FROM order_items oi
+            INNER JOIN orders o ON o.id = oi.order_id
+            LEFT JOIN users u ON u.id = o.user_id
+            LEFT JOIN products p ON p.id = oi.product_id
+            WHERE o.store_id = ? AND o.status = 'completed'
+              AND date(o.created_at) BETWEEN ? AND ?
+            ORDER BY o.user_id ASC, o.id ASC
+            "#,
+            RETURNED_SQL
+        ))
+        .bind(store_id)
+        .bind(date_from)
+        .bind(date_to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Net sales (returns subtracted, voids excluded) per seller and month,
+    /// over whole calendar months so tiers don't depend on the report range.
+    pub async fn get_monthly_volumes(
+        &self,
+        store_id: i64,
+        month_from: &str,
+        month_to: &str,
+    ) -> Result<Vec<(i64, String, Money)>, sqlx::Error> {
+        sqlx::query_as(&format!(
+            r#"
+            SELECT o.user_id, substr(o.created_at, 1, 7) AS month, CAST(SUM(o.total - {}) AS INTEGER)
+            FROM orders o
+            WHERE o.store_id = ? AND o.status = 'completed'
+              AND substr(o.created_at, 1, 7) BETWEEN ? AND ?
+            GROUP BY o.user_id, month
+            "#,
+            RETURNED_SQL
+        ))
+        .bind(store_id)
+        .bind(month_from)
+        .bind(month_to)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_voided(
+        &self,
+        store_id: i64,
+        date_from: &str,
+        date_to: &str,
+    ) -> Result<Vec<VoidedSummary>, sqlx::Error> {
+        sqlx::query_as::<_, VoidedSummary>(
+            r#"
+            SELECT o.user_id, COUNT(*) AS voided_count, CAST(COALESCE(SUM(o.total), 0) AS INTEGER) AS voided_amount
+            FROM orders o
+            WHERE o.store_id = ? AND o.status = 'voided'
+              AND date(o.created_at) BETWEEN ? AND ?
+            GROUP BY o.user_id
+            "#,
+        )
+        .bind(store_id)
+        .bind(date_from)
+        .bind(date_to)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
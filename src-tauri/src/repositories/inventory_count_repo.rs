@@ -0,0 +1,285 @@
+use crate::models::inventory_count::{InventoryCount, InventoryCountLine};
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const COUNT_SELECT: &str = r#"
+    SELECT c.id, c.store_id, c.status, c.notes, c.current_pass,
+           c.created_by, cu.username AS created_by_name,
+           c.approved_by, au.username AS approved_by_name, c.approved_at, c.created_at
+    FROM inventory_counts c
+    LEFT JOIN users cu ON cu.id = c.created_by
+    LEFT JOIN users au ON au.id = c.approved_by
+"#;
+
+/// Expected units: the snapshot plus every kardex movement after the count
+/// was opened and up to the product's first scan in its counting pass (up to
+/// now if it hasn't been scanned). Frozen in `final_expected` on approval.
+const LINE_SELECT: &str = r#"
+    SELECT i.product_id, p.code AS product_code, p.name AS product_name, i.snapshot_stock,
+           COALESCE(i.final_expected, i.snapshot_stock + COALESCE((
+               SELECT SUM(m.delta) FROM stock_movements m
+               WHERE m.product_id = i.product_id
+                 AND m.id > c.movement_watermark
+                 AND (i.scan_watermark IS NULL OR m.id <= i.scan_watermark)
+           ), 0)) AS expected,
+           i.counted, i.counted_pass, CAST(i.unit_cost AS INTEGER) AS unit_cost
+    FROM inventory_count_items i
+    INNER JOIN inventory_counts c ON c.id = i.count_id
+    INNER JOIN products p ON p.id = i.product_id
+    WHERE i.count_id = ?
+    ORDER BY p.name ASC, p.id ASC
+"#;
+
+pub struct InventoryCountRepository {
+    pool: SqlitePool,
+}
+
+impl InventoryCountRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Opens the count and snapshots the stock of every active product of the
+    /// store together with the kardex position, in one transaction.
+    pub async fn create(
+        &self,
+        store_id: i64,
+        user_id: i64,
+        notes: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query("INSERT INTO inventory_counts (store_id, notes, created_by) VALUES (?, ?, ?)")
+            .bind(store_id)
+            .bind(notes)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+
+        sqlx::query(
+            "UPDATE inventory_counts SET movement_watermark = (SELECT COALESCE(MAX(id), 0) FROM stock_movements) WHERE id = ?",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO inventory_count_items (count_id, product_id, snapshot_stock, unit_cost)
+            SELECT ?, id, stock, CAST(cost AS INTEGER)
+            FROM products
+            WHERE store_id = ? AND is_active = 1
+            "#,
+        )
+        .bind(id)
+        .bind(store_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Adds `quantity` to the product's count in the current pass. The first
+    /// scan of a product in a new pass replaces what earlier passes counted.
+    /// Returns the product's counted units, or `None` if the count isn't open;
+    /// fails with `RowNotFound` if the count would go negative.
+    pub async fn scan(
+        &self,
+        count_id: i64,
+        product_id: i64,
+        unit_cost: Money,
+        code: &str,
+        quantity: i64,
+        user_id: i64,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // A write first: it takes the lock, so the kardex position read below
+        // can't miss a sale committed in between
+        let pass: Option<i64> = sqlx::query_scalar(
+            "UPDATE inventory_counts SET current_pass = current_pass WHERE id = ? AND status = 'open' RETURNING current_pass",
+        )
+        .bind(count_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let pass = match pass {
+            Some(pass) => pass,
+            None => return Ok(None),
+        };
+
+        sqlx::query(
+            "INSERT INTO inventory_count_scans (count_id, product_id, code, quantity, pass, user_id) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(count_id)
+        .bind(product_id)
+        .bind(code)
+        .bind(quantity)
+        .bind(pass)
+        .bind(user_id)
+        .execute(&mut *tx)
+        .await?;
+
+        // Products created after the snapshot start from zero
+        sqlx::query(
+            "INSERT OR IGNORE INTO inventory_count_items (count_id, product_id, snapshot_stock, unit_cost) VALUES (?, ?, 0, ?)",
+        )
+        .bind(count_id)
+        .bind(product_id)
+        .bind(unit_cost)
+        .execute(&mut *tx)
+        .await?;
+
+        let counted: i64 = sqlx::query_scalar(
+            r#"
+            UPDATE inventory_count_items
+            SET counted = CASE WHEN counted_pass = ?1 THEN counted + ?2 ELSE ?2 END,
+                scan_watermark = CASE WHEN counted_pass = ?1 THEN scan_watermark
+                                      ELSE (SELECT COALESCE(MAX(id), 0) FROM stock_movements) END,
+                counted_pass = ?1
+            WHERE count_id = ?3 AND product_id = ?4
+            RETURNING counted
+            "#,
+        )
+        .bind(pass)
+        .bind(quantity)
+        .bind(count_id)
+        .bind(product_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        if counted < 0 {
+            return Err(sqlx::Error::RowNotFound);
+        }
+
+        tx.commit().await?;
+        Ok(Some(counted))
+    }
+
+    /// Starts a new counting pass. Returns its number, or `None` if the count isn't open.
+    pub async fn next_pass(&self, count_id: i64) -> Result<Option<i64>, sqlx::Error> {
+        sqlx::query_scalar(
+            "UPDATE inventory_counts SET current_pass = current_pass + 1 WHERE id = ? AND status = 'open' RETURNING current_pass",
+        )
+        .bind(count_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    /// Freezes the expected units and posts the variance of every counted
+    /// product as an adjustment movement. With `zero_uncounted`, products
+    /// nobody scanned are taken as missing. Returns the number of adjusted
+    /// products, or `None` if the count wasn't open.
+    pub async fn approve(
+        &self,
+        count_id: i64,
+        manager_id: i64,
+        zero_uncounted: bool,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let claimed = sqlx::query(
+            r#"
+            UPDATE inventory_counts
+            SET status = 'approved', approved_by = ?, approved_at = datetime('now', 'localtime')
+            WHERE id = ? AND status = 'open'
+            "#,
+        )
+        .bind(manager_id)
+        .bind(count_id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(None);
+        }
+
+        let source = MovementSource {
+            note: Some(format!("Conteo físico #{}", count_id)),
+            ..MovementSource::document("adjustment", "inventory_count", count_id, Some(manager_id))
+        };
+        let mut adjusted = 0;
+        for line in Self::lines(&mut tx, count_id).await? {
+            let counted = match line.counted {
+                Some(counted) => Some(counted),
+                None if zero_uncounted => Some(0),
+                None => None,
+            };
+
+            sqlx::query(
+                "UPDATE inventory_count_items SET final_expected = ?, counted = ? WHERE count_id = ? AND product_id = ?",
+            )
+            .bind(line.expected)
+            .bind(counted)
+            .bind(count_id)
+            .bind(line.product_id)
+            .execute(&mut *tx)
+            .await?;
+
+            if let Some(counted) = counted {
+                let variance = counted - line.expected;
+                if variance != 0 {
+                    StockMovementRepository::apply(&mut tx, line.product_id, variance, &source).await?;
+                    adjusted += 1;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok(Some(adjusted))
+    }
+
+    pub async fn cancel(&self, count_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE inventory_counts SET status = 'cancelled' WHERE id = ? AND status = 'open'",
+        )
+        .bind(count_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<InventoryCount>, sqlx::Error> {
+        sqlx::query_as::<_, InventoryCount>(&format!("{} WHERE c.id = ?", COUNT_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_open(&self, store_id: i64) -> Result<Option<InventoryCount>, sqlx::Error> {
+        sqlx::query_as::<_, InventoryCount>(&format!(
+            "{} WHERE c.store_id = ? AND c.status = 'open'",
+            COUNT_SELECT
+        ))
+        .bind(store_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_store(&self, store_id: i64) -> Result<Vec<InventoryCount>, sqlx::Error> {
+        sqlx::query_as::<_, InventoryCount>(&format!(
+            "{} WHERE c.store_id = ? ORDER BY c.created_at DESC, c.id DESC",
+            COUNT_SELECT
+        ))
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_lines(&self, count_id: i64) -> Result<Vec<InventoryCountLine>, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::lines(&mut conn, count_id).await
+    }
+
+    async fn lines(
+        conn: &mut SqliteConnection,
+        count_id: i64,
+    ) -> Result<Vec<InventoryCountLine>, sqlx::Error> {
+        sqlx::query_as::<_, InventoryCountLine>(LINE_SELECT)
+            .bind(count_id)
+            .fetch_all(&mut *conn)
+            .await
+    }
+}
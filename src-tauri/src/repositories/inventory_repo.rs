@@ -1,7 +1,37 @@
-use crate::models::inventory::{Category, ProductWithCategory};
+use crate::models::inventory::{Category, CategoryNode, ProductWithCategory};
 use crate::models::inventory::Product;
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::price_change_repo::PriceChangeRepository;
+use crate::repositories::product_cost_repo::ProductCostRepository;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
 use sqlx::SqlitePool;
 
+/// Units of product `p` held by active, unexpired layaways.
+/// Must be embedded in a query that aliases `products` as `p`.
+pub const RESERVED_STOCK_SQL: &str = "COALESCE((
+    SELECT SUM(li.quantity * COALESCE(k.quantity, 1)) FROM layaway_items li
+    INNER JOIN layaways l ON l.id = li.layaway_id
+    LEFT JOIN product_kit_components k ON k.kit_id = li.product_id AND k.component_id = p.id
+    WHERE (li.product_id = p.id OR k.component_id = p.id)
+      AND l.status = 'active' AND l.expires_at >= datetime('now', 'localtime')
+), 0)";
+
+/// Sellable packs of the kit `p`: the scarcest component's sellable stock
+/// (as in `RESERVED_STOCK_SQL`) over its units per pack.
+pub const KIT_STOCK_SQL: &str = "COALESCE((
+    SELECT MIN(MAX((c.stock - COALESCE((
+        SELECT SUM(li.quantity * COALESCE(ck.quantity, 1)) FROM layaway_items li
+        INNER JOIN layaways l ON l.id = li.layaway_id
+        LEFT JOIN product_kit_components ck ON ck.kit_id = li.product_id AND ck.component_id = c.id
+        WHERE (li.product_id = c.id OR ck.component_id = c.id)
+          AND l.status = 'active' AND l.expires_at >= datetime('now', 'localtime')
+    ), 0)) / k.quantity, 0) * c.is_active)
+    FROM product_kit_components k
+    INNER JOIN products c ON c.id = k.component_id
+    WHERE k.kit_id = p.id
+), 0)";
+
 pub struct InventoryRepository {
     pool: SqlitePool,
 }
@@ -12,22 +42,68 @@ impl InventoryRepository {
     }
 
     // Categories
-    pub async fn get_categories(&self) -> Result<Vec<Category>, sqlx::Error> {
-        sqlx::query_as::<_, Category>("SELECT * FROM categories ORDER BY name ASC")
-            .fetch_all(&self.pool)
-            .await
+    /// Categories in tree order (siblings by `sort_order`, then name). With a
+    /// store, only the shared categories and that store's own ones.
+    pub async fn get_categories(&self, store_id: Option<i64>) -> Result<Vec<CategoryNode>, sqlx::Error> {
+        // char(1) sorts before any printable character, so a category's
+        // children come right after it and before its next sibling
+        sqlx::query_as::<_, CategoryNode>(
+            r#"
+            WITH RECURSIVE tree(id, name, parent_id, store_id, sort_order, depth, path, sort_key) AS (
+                SELECT id, name, parent_id, store_id, sort_order, 0, name,
+                       printf('%06d', sort_order) || lower(name)
+                FROM categories
+                WHERE parent_id IS NULL AND (?1 IS NULL OR store_id IS NULL OR store_id = ?1)
+                UNION ALL
+                SELECT c.id, c.name, c.parent_id, c.store_id, c.sort_order, t.depth + 1,
+                       t.path || ' > ' || c.name,
+                       t.sort_key || char(1) || printf('%06d', c.sort_order) || lower(c.name)
+                FROM categories c
+                INNER JOIN tree t ON c.parent_id = t.id
+                WHERE ?1 IS NULL OR c.store_id IS NULL OR c.store_id = ?1
+            )
+            SELECT
+                t.id, t.name, t.parent_id, t.store_id, t.sort_order, t.depth, t.path,
+                (SELECT COUNT(*) FROM products p
+                 WHERE p.category_id = t.id AND p.is_active = 1
+                   AND (?1 IS NULL OR p.store_id = ?1)) AS product_count
+            FROM tree t
+            ORDER BY t.sort_key
+            "#,
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
     }
 
-    pub async fn create_category(&self, name: &str) -> Result<Category, sqlx::Error> {
-        let result = sqlx::query("INSERT INTO categories (name) VALUES (?)")
-            .bind(name)
-            .execute(&self.pool)
-            .await?;
+    pub async fn find_category(&self, id: i64) -> Result<Option<Category>, sqlx::Error> {
+        sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
 
-        Ok(Category {
-            id: result.last_insert_rowid(),
-            name: name.to_string(),
-        })
+    /// Adds the category after its last sibling.
+    pub async fn create_category(
+        &self,
+        name: &str,
+        parent_id: Option<i64>,
+        store_id: Option<i64>,
+    ) -> Result<Category, sqlx::Error> {
+        sqlx::query_as::<_, Category>(
+            r#"
+            INSERT INTO categories (name, parent_id, store_id, sort_order)
+            VALUES (?1, ?2, ?3, (
+                SELECT COALESCE(MAX(sort_order), -1) + 1 FROM categories WHERE parent_id IS ?2
+            ))
+            RETURNING *
+            "#,
+        )
+        .bind(name)
+        .bind(parent_id)
+        .bind(store_id)
+        .fetch_one(&self.pool)
+        .await
     }
 
     pub async fn update_category(&self, id: i64, name: &str) -> Result<(), sqlx::Error> {
@@ -39,92 +115,324 @@ impl InventoryRepository {
         Ok(())
     }
 
-    pub async fn delete_category(&self, id: i64) -> Result<(), sqlx::Error> {
-        sqlx::query("DELETE FROM categories WHERE id = ?")
+    pub async fn move_category(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+        sort_order: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE categories SET parent_id = ?, sort_order = ? WHERE id = ?")
+            .bind(parent_id)
+            .bind(sort_order)
             .bind(id)
             .execute(&self.pool)
             .await?;
         Ok(())
     }
 
+    /// Numbers the given categories 0, 1, 2... in that order.
+    pub async fn reorder_categories(&self, ids: &[i64]) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        for (position, id) in ids.iter().enumerate() {
+            sqlx::query("UPDATE categories SET sort_order = ? WHERE id = ?")
+                .bind(position as i64)
+                .bind(id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Whether `id` is `ancestor_id` or lies below it.
+    pub async fn is_in_subtree(&self, ancestor_id: i64, id: i64) -> Result<bool, sqlx::Error> {
+        let found: Option<i64> = sqlx::query_scalar(
+            "SELECT 1 FROM category_closure WHERE ancestor_id = ? AND id = ? LIMIT 1",
+        )
+        .bind(ancestor_id)
+        .bind(id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(found.is_some())
+    }
+
+    /// Products (deleted ones too) and styles filed directly under the category.
+    pub async fn count_category_usage(&self, id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT (SELECT COUNT(*) FROM products WHERE category_id = ?1)
+                 + (SELECT COUNT(*) FROM product_styles WHERE category_id = ?1)
+            "#,
+        )
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Products and styles of the category that belong to a store other than `store_id`.
+    pub async fn count_category_usage_outside_store(&self, id: i64, store_id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT (SELECT COUNT(*) FROM products WHERE category_id = ?1 AND store_id IS NOT ?2)
+                 + (SELECT COUNT(*) FROM product_styles WHERE category_id = ?1 AND store_id IS NOT ?2)
+            "#,
+        )
+        .bind(id)
+        .bind(store_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Moves the products and styles to `reassign_to`, lifts the subcategories
+    /// to the deleted category's parent and deletes it, in one transaction.
+    pub async fn delete_category(&self, id: i64, reassign_to: Option<i64>) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("UPDATE products SET category_id = ? WHERE category_id = ?")
+            .bind(reassign_to)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE product_styles SET category_id = ? WHERE category_id = ?")
+            .bind(reassign_to)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(
+            "UPDATE categories SET parent_id = (SELECT parent_id FROM categories WHERE id = ?1) WHERE parent_id = ?1",
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        sqlx::query("DELETE FROM categories WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
     // Products
+    /// `stock` is the sellable quantity: units reserved by layaways are subtracted
+    /// and reported separately in `reserved_stock`.
     pub async fn get_products(&self, store_id: i64) -> Result<Vec<ProductWithCategory>, sqlx::Error> {
-        let sql = r#"
+        let sql = format!(
+            r#"
             SELECT 
                 p.id, p.code, p.name, p.category_id, c.name as category_name,
-                p.price, p.cost, p.stock, p.min_stock, p.unit, p.image_url, p.is_active, p.store_id, p.created_at
+                p.price, p.cost, CASE WHEN p.is_kit = 1 THEN {kit} ELSE p.stock - {reserved} END AS stock, {reserved} AS reserved_stock,
+                p.min_stock, p.unit, p.image_url, p.is_active, p.store_id, p.created_at,
+                p.style_id, p.size, p.color, p.barcode, p.is_kit
             FROM products p
             LEFT JOIN categories c ON p.category_id = c.id
             WHERE p.is_active = 1 AND p.store_id = ?
             ORDER BY p.name ASC
-        "#;
-        sqlx::query_as::<_, ProductWithCategory>(sql)
+            "#,
+            reserved = RESERVED_STOCK_SQL,
+            kit = KIT_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductWithCategory>(&sql)
+            .bind(store_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Products of the store filed under the category or any of its subcategories.
+    pub async fn get_products_in_category(
+        &self,
+        store_id: i64,
+        category_id: i64,
+    ) -> Result<Vec<ProductWithCategory>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT
+                p.id, p.code, p.name, p.category_id, c.name as category_name,
+                p.price, p.cost, CASE WHEN p.is_kit = 1 THEN {kit} ELSE p.stock - {reserved} END AS stock, {reserved} AS reserved_stock,
+                p.min_stock, p.unit, p.image_url, p.is_active, p.store_id, p.created_at,
+                p.style_id, p.size, p.color, p.barcode, p.is_kit
+            FROM products p
+            INNER JOIN category_closure cc ON cc.id = p.category_id AND cc.ancestor_id = ?2
+            LEFT JOIN categories c ON p.category_id = c.id
+            WHERE p.is_active = 1 AND p.store_id = ?1
+            ORDER BY p.name ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL,
+            kit = KIT_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductWithCategory>(&sql)
             .bind(store_id)
+            .bind(category_id)
             .fetch_all(&self.pool)
             .await
     }
 
+    /// Whether a product of `store_id` may use the category (shared or own).
+    pub async fn category_visible(&self, category_id: i64, store_id: i64) -> Result<bool, sqlx::Error> {
+        let found: Option<i64> = sqlx::query_scalar(
+            "SELECT 1 FROM categories WHERE id = ? AND (store_id IS NULL OR store_id = ?)",
+        )
+        .bind(category_id)
+        .bind(store_id)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(found.is_some())
+    }
+
+    /// Creates the product and books its initial stock as a movement.
     pub async fn create_product(
         &self,
         code: Option<&str>,
         name: &str,
         category_id: Option<i64>,
-        price: f64,
-        cost: f64,
+        price: Money,
+        cost: Money,
         stock: i64,
+        min_stock: Option<i64>,
         unit: Option<&str>,
         image_url: Option<&str>,
         store_id: i64,
+        source: &MovementSource,
     ) -> Result<i64, sqlx::Error> {
-        let result = sqlx::query(
-            "INSERT INTO products (code, name, category_id, price, cost, stock, unit, image_url, store_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)"
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query(
+            "INSERT INTO products (code, name, category_id, price, cost, stock, min_stock, unit, image_url, store_id) VALUES (?, ?, ?, ?, ?, 0, COALESCE(?, 5), ?, ?, ?)"
         )
         .bind(code)
         .bind(name)
         .bind(category_id)
         .bind(price)
         .bind(cost)
-        .bind(stock)
+        .bind(min_stock)
         .bind(unit)
         .bind(image_url)
         .bind(store_id)
-        .execute(&self.pool)
-        .await?;
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        if stock != 0 {
+            StockMovementRepository::apply(&mut tx, id, stock, source).await?;
+        }
 
-        Ok(result.last_insert_rowid())
+        tx.commit().await?;
+        Ok(id)
     }
 
+    /// Updates everything but the stock, which only changes through movements.
+    /// A `None` min_stock keeps the current one; price and cost changes are
+    /// recorded in their history with `source`.
     pub async fn update_product(
         &self,
         id: i64,
         code: Option<&str>,
         name: &str,
         category_id: Option<i64>,
-        price: f64,
-        cost: f64,
-        stock: i64,
+        price: Money,
+        cost: Money,
+        min_stock: Option<i64>,
         unit: Option<&str>,
         image_url: Option<&str>,
         store_id: i64,
+        source: &MovementSource,
     ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let previous: Option<(Money, Money)> = sqlx::query_as(
+            "SELECT CAST(price AS INTEGER), CAST(cost AS INTEGER) FROM products WHERE id = ?",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+
         sqlx::query(
-            "UPDATE products SET code=?, name=?, category_id=?, price=?, cost=?, stock=?, unit=?, image_url=?, store_id=? WHERE id=?"
+            "UPDATE products SET code=?, name=?, category_id=?, price=?, cost=?, min_stock=COALESCE(?, min_stock), unit=?, image_url=?, store_id=? WHERE id=?"
         )
         .bind(code)
         .bind(name)
         .bind(category_id)
         .bind(price)
         .bind(cost)
-        .bind(stock)
+        .bind(min_stock)
         .bind(unit)
         .bind(image_url)
         .bind(store_id)
         .bind(id)
-        .execute(&self.pool)
+        .execute(&mut *tx)
         .await?;
+
+        if let Some((previous_price, previous_cost)) = previous {
+            PriceChangeRepository::record_change(&mut tx, id, previous_price, price, source).await?;
+            ProductCostRepository::record_change(&mut tx, id, previous_cost, cost, source).await?;
+        }
+
+        tx.commit().await?;
         Ok(())
     }
 
+    /// Adds units bought at `unit_cost`, averaging them into the product's
+    /// cost. Returns the new cost.
+    pub async fn receive_stock(
+        &self,
+        id: i64,
+        quantity: i64,
+        unit_cost: Money,
+        source: &MovementSource,
+    ) -> Result<Money, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let cost = ProductCostRepository::receive(&mut tx, id, quantity, unit_cost, source).await?;
+        tx.commit().await?;
+        Ok(cost)
+    }
+
+    /// Books a purchase of an existing product in one transaction: takes the
+    /// code, name and category of the purchase line (and its image, if any)
+    /// and adds the units, averaging their cost. Price and cost are not
+    /// written back. Returns the new cost.
+    #[allow(clippy::too_many_arguments)]
+    pub async fn restock(
+        &self,
+        id: i64,
+        code: Option<&str>,
+        name: &str,
+        category_id: Option<i64>,
+        image_url: Option<&str>,
+        quantity: i64,
+        unit_cost: Money,
+        source: &MovementSource,
+    ) -> Result<Money, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            "UPDATE products SET code = ?, name = ?, category_id = ?, image_url = COALESCE(?, image_url) WHERE id = ?",
+        )
+        .bind(code)
+        .bind(name)
+        .bind(category_id)
+        .bind(image_url)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let cost = ProductCostRepository::receive(&mut tx, id, quantity, unit_cost, source).await?;
+        tx.commit().await?;
+        Ok(cost)
+    }
+
+    /// Adds `delta` units (negative to remove) and records the movement.
+    pub async fn add_stock(
+        &self,
+        id: i64,
+        delta: i64,
+        source: &MovementSource,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let balance = StockMovementRepository::apply(&mut tx, id, delta, source).await?;
+        tx.commit().await?;
+        Ok(balance)
+    }
+
     pub async fn soft_delete_product(&self, id: i64) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE products SET is_active = 0 WHERE id = ?")
             .bind(id)
@@ -133,6 +441,34 @@ impl InventoryRepository {
         Ok(())
     }
 
+    /// Units that can still be sold: stock minus layaway reservations, or the
+    /// packs the components allow for a kit.
+    pub async fn sellable_stock(&self, product_id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT CASE WHEN p.is_kit = 1 THEN {kit} ELSE p.stock - {reserved} END FROM products p WHERE p.id = ?",
+            kit = KIT_STOCK_SQL,
+            reserved = RESERVED_STOCK_SQL
+        ))
+        .bind(product_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<Product>, sqlx::Error> {
+        sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = ? AND is_active = 1")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Like `find_by_id` but also returns deleted products (for history views).
+    pub async fn find_any_by_id(&self, id: i64) -> Result<Option<Product>, sqlx::Error> {
+        sqlx::query_as::<_, Product>("SELECT * FROM products WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
     pub async fn find_by_code(
         &self,
         code: &str,
@@ -146,4 +482,98 @@ impl InventoryRepository {
         .fetch_optional(&self.pool)
         .await
     }
+
+    /// Full-text search over `products_fts`; `query` is an FTS5 match
+    /// expression. Ranked by bm25 with name weighing most, then code.
+    pub async fn search_products(
+        &self,
+        store_id: i64,
+        query: &str,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ProductWithCategory>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT
+                p.id, p.code, p.name, p.category_id, c.name as category_name,
+                p.price, p.cost, CASE WHEN p.is_kit = 1 THEN {kit} ELSE p.stock - {reserved} END AS stock, {reserved} AS reserved_stock,
+                p.min_stock, p.unit, p.image_url, p.is_active, p.store_id, p.created_at,
+                p.style_id, p.size, p.color, p.barcode, p.is_kit
+            FROM products_fts f
+            INNER JOIN products p ON p.id = f.rowid
+            LEFT JOIN categories c ON p.category_id = c.id
+            WHERE products_fts MATCH ?1 AND p.is_active = 1 AND p.store_id = ?2
+            ORDER BY bm25(products_fts, 10.0, 6.0, 2.0, 1.0), p.name ASC
+            LIMIT ?3 OFFSET ?4
+            "#,
+            reserved = RESERVED_STOCK_SQL,
+            kit = KIT_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductWithCategory>(&sql)
+            .bind(query)
+            .bind(store_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn count_search_products(&self, store_id: i64, query: &str) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM products_fts f
+            INNER JOIN products p ON p.id = f.rowid
+            WHERE products_fts MATCH ?1 AND p.is_active = 1 AND p.store_id = ?2
+            "#,
+        )
+        .bind(query)
+        .bind(store_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Active product of the store whose barcode or code is `code`, with the
+    /// same sellable stock as `get_products`. A barcode match wins over a code.
+    pub async fn find_sellable(
+        &self,
+        code: &str,
+        store_id: i64,
+    ) -> Result<Option<ProductWithCategory>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT
+                p.id, p.code, p.name, p.category_id, c.name as category_name,
+                p.price, p.cost, CASE WHEN p.is_kit = 1 THEN {kit} ELSE p.stock - {reserved} END AS stock, {reserved} AS reserved_stock,
+                p.min_stock, p.unit, p.image_url, p.is_active, p.store_id, p.created_at,
+                p.style_id, p.size, p.color, p.barcode, p.is_kit
+            FROM products p
+            LEFT JOIN categories c ON p.category_id = c.id
+            WHERE p.is_active = 1 AND p.store_id = ?1 AND (p.barcode = ?2 OR p.code = ?2)
+            ORDER BY p.barcode = ?2 DESC
+            LIMIT 1
+            "#,
+            reserved = RESERVED_STOCK_SQL,
+            kit = KIT_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductWithCategory>(&sql)
+            .bind(store_id)
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_by_barcode(
+        &self,
+        barcode: &str,
+        store_id: i64,
+    ) -> Result<Option<Product>, sqlx::Error> {
+        sqlx::query_as::<_, Product>(
+            "SELECT * FROM products WHERE barcode = ? AND store_id = ? AND is_active = 1",
+        )
+        .bind(barcode)
+        .bind(store_id)
+        .fetch_optional(&self.pool)
+        .await
+    }
 }
@@ -0,0 +1,227 @@
+use crate::models::kit::{
+    KitComponent, KitComponentPayload, KitComponentSales, KitSaleComponent, ProductKit,
+};
+use crate::models::money::Money;
+use crate::repositories::inventory_repo::{KIT_STOCK_SQL, RESERVED_STOCK_SQL};
+use sqlx::{SqliteConnection, SqlitePool};
+
+pub struct KitRepository {
+    pool: SqlitePool,
+}
+
+fn kit_select() -> String {
+    format!(
+        r#"
+        SELECT p.id AS kit_id, p.code, p.name, p.store_id, CAST(p.price AS INTEGER) AS price,
+               COALESCE((
+                   SELECT SUM(CAST(c.price AS INTEGER) * k.quantity) FROM product_kit_components k
+                   INNER JOIN products c ON c.id = k.component_id WHERE k.kit_id = p.id
+               ), 0) AS components_price,
+               COALESCE((
+                   SELECT SUM(CAST(c.cost AS INTEGER) * k.quantity) FROM product_kit_components k
+                   INNER JOIN products c ON c.id = k.component_id WHERE k.kit_id = p.id
+               ), 0) AS components_cost,
+               {kit} AS available
+        FROM products p
+        "#,
+        kit = KIT_STOCK_SQL
+    )
+}
+
+impl KitRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_all(&self, store_id: i64) -> Result<Vec<ProductKit>, sqlx::Error> {
+        sqlx::query_as::<_, ProductKit>(&format!(
+            "{} WHERE p.is_kit = 1 AND p.is_active = 1 AND p.store_id = ? ORDER BY p.name ASC",
+            kit_select()
+        ))
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_id(&self, kit_id: i64) -> Result<Option<ProductKit>, sqlx::Error> {
+        sqlx::query_as::<_, ProductKit>(&format!("{} WHERE p.is_kit = 1 AND p.id = ?", kit_select()))
+            .bind(kit_id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_components(&self, kit_id: i64) -> Result<Vec<KitComponent>, sqlx::Error> {
+        sqlx::query_as::<_, KitComponent>(&format!(
+            r#"
+            SELECT p.id AS component_id, p.code, p.name, k.quantity,
+                   CAST(p.price AS INTEGER) AS price, CAST(p.cost AS INTEGER) AS cost,
+                   p.stock - {reserved} AS stock
+            FROM product_kit_components k
+            INNER JOIN products p ON p.id = k.component_id
+            WHERE k.kit_id = ?
+            ORDER BY k.id ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL
+        ))
+        .bind(kit_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Whether some pack uses the product.
+    pub async fn is_component(&self, product_id: i64) -> Result<bool, sqlx::Error> {
+        let found: Option<i64> =
+            sqlx::query_scalar("SELECT 1 FROM product_kit_components WHERE component_id = ? LIMIT 1")
+                .bind(product_id)
+                .fetch_optional(&self.pool)
+                .await?;
+        Ok(found.is_some())
+    }
+
+    /// Replaces the pack's components. Without components the product stops
+    /// being a pack.
+    pub async fn save_components(
+        &self,
+        kit_id: i64,
+        components: &[KitComponentPayload],
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query("DELETE FROM product_kit_components WHERE kit_id = ?")
+            .bind(kit_id)
+            .execute(&mut *tx)
+            .await?;
+
+        for component in components {
+            sqlx::query(
+                "INSERT INTO product_kit_components (kit_id, component_id, quantity) VALUES (?, ?, ?)",
+            )
+            .bind(kit_id)
+            .bind(component.product_id)
+            .bind(component.quantity)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        sqlx::query("UPDATE products SET is_kit = ? WHERE id = ?")
+            .bind(!components.is_empty())
+            .bind(kit_id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Components of `product_id` if it is a pack; empty for any other product.
+    pub async fn components_for_sale(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+    ) -> Result<Vec<KitSaleComponent>, sqlx::Error> {
+        sqlx::query_as::<_, KitSaleComponent>(
+            r#"
+            SELECT c.id AS product_id, c.name, k.quantity,
+                   CAST(c.price AS INTEGER) AS price, CAST(c.cost AS INTEGER) AS cost
+            FROM product_kit_components k
+            INNER JOIN products p ON p.id = k.kit_id AND p.is_kit = 1
+            INNER JOIN products c ON c.id = k.component_id
+            WHERE k.kit_id = ?
+            ORDER BY k.id ASC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&mut *conn)
+        .await
+    }
+
+    /// Records the components sold in a pack line, splitting the line subtotal
+    /// by the components' list prices. Returns the cost of one pack.
+    pub async fn record_sale_components(
+        conn: &mut SqliteConnection,
+        order_item_id: i64,
+        order_id: i64,
+        packs: i64,
+        subtotal: Money,
+        components: &[KitSaleComponent],
+    ) -> Result<Money, sqlx::Error> {
+        let shares = allocate(subtotal, components);
+        for (component, share) in components.iter().zip(shares) {
+            sqlx::query(
+                r#"
+                INSERT INTO order_item_components (order_item_id, order_id, product_id, product_name, quantity, unit_cost, subtotal)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(order_item_id)
+            .bind(order_id)
+            .bind(component.product_id)
+            .bind(&component.name)
+            .bind(component.quantity * packs)
+            .bind(component.cost)
+            .bind(share)
+            .execute(&mut *conn)
+            .await?;
+        }
+        Ok(components.iter().map(|c| c.cost.times(c.quantity)).sum())
+    }
+
+    /// Units, allocated revenue and cost of the products sold inside packs in
+    /// completed sales of the period, best sellers first.
+    pub async fn component_sales(
+        &self,
+        store_id: i64,
+        date_from: &str,
+        date_to: &str,
+    ) -> Result<Vec<KitComponentSales>, sqlx::Error> {
+        sqlx::query_as::<_, KitComponentSales>(
+            r#"
+            SELECT oc.product_id, p.code, oc.product_name AS name,
+                   SUM(oc.quantity) AS units_sold,
+                   SUM(oc.subtotal) AS revenue,
+                   SUM(oc.quantity * COALESCE(oc.unit_cost, 0)) AS cost,
+                   SUM(oc.subtotal) - SUM(oc.quantity * COALESCE(oc.unit_cost, 0)) AS margin
+            FROM order_item_components oc
+            INNER JOIN orders o ON o.id = oc.order_id
+            LEFT JOIN products p ON p.id = oc.product_id
+            WHERE o.store_id = ? AND o.status = 'completed'
+              AND date(o.created_at) BETWEEN ? AND ?
+            GROUP BY oc.product_id
+            ORDER BY revenue DESC, name ASC
+            "#,
+        )
+        .bind(store_id)
+        .bind(date_from)
+        .bind(date_to)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// Splits `total` in proportion to each component's list price times its
+/// units per pack (by units if nothing has a price). The rounding difference
+/// goes to the largest share so the parts add up to the total.
+fn allocate(total: Money, components: &[KitSaleComponent]) -> Vec<Money> {
+    let mut weights: Vec<i64> = components
+        .iter()
+        .map(|c| c.price.cents().max(0) * c.quantity)
+        .collect();
+    if weights.iter().all(|w| *w == 0) {
+        weights = components.iter().map(|c| c.quantity).collect();
+    }
+    let sum: i64 = weights.iter().sum();
+    if sum == 0 {
+        return vec![Money::ZERO; components.len()];
+    }
+
+    let mut shares: Vec<Money> = weights.iter().map(|w| total.mul_div(*w, sum)).collect();
+    let difference = total - shares.iter().sum::<Money>();
+    if let Some(largest) = weights
+        .iter()
+        .enumerate()
+        .max_by_key(|(_, w)| **w)
+        .map(|(i, _)| i)
+    {
+        shares[largest] += difference;
+    }
+    shares
+}
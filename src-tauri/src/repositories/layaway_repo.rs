@@ -0,0 +1,260 @@
+use crate::models::layaway::{CreateLayawayPayload, Layaway, LayawayItem, LayawayPayment};
+use crate::models::sales::{CreateOrderItemPayload, CreateOrderPayload};
+use crate::models::money::Money;
+use crate::repositories::sales_repo::{SalesRepository, CASH_SESSION_CLOSED};
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// Message a deposit fails with, as `sqlx::Error::InvalidArgument`, when the
+/// layaway was closed or paid by someone else in the meantime.
+pub const LAYAWAY_PAYMENT_REJECTED: &str =
+    "La separación ya no está activa o el abono excede el saldo pendiente";
+
+pub struct LayawayRepository {
+    pool: SqlitePool,
+}
+
+impl LayawayRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the layaway, its items and the initial deposit (if any) in a
+    /// single transaction. Stock is reserved, not decremented.
+    pub async fn create_layaway(
+        &self,
+        uuid: &str,
+        payload: &CreateLayawayPayload,
+        items: &[CreateOrderItemPayload],
+        total: Money,
+        expires_at: &str,
+        cash_session_id: Option<i64>,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let layaway_id = sqlx::query(
+            r#"
+            INSERT INTO layaways (uuid, store_id, user_id, client_document, client_phone, client_name, total, paid_amount, status, expires_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 0, 'active', ?)
+            "#,
+        )
+        .bind(uuid)
+        .bind(payload.store_id)
+        .bind(payload.user_id)
+        .bind(&payload.client_document)
+        .bind(&payload.client_phone)
+        .bind(&payload.client_name)
+        .bind(total)
+        .bind(expires_at)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for item in items {
+            sqlx::query(
+                r#"
+                INSERT INTO layaway_items (layaway_id, product_id, product_name, unit_price, quantity, subtotal, price_list_id)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(layaway_id)
+            .bind(item.product_id)
+            .bind(&item.product_name)
+            .bind(item.unit_price)
+            .bind(item.quantity)
+            .bind(item.subtotal)
+            .bind(item.price_list_id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        if let Some(session_id) = cash_session_id {
+            Self::insert_payment(
+                &mut tx,
+                layaway_id,
+                session_id,
+                payload.deposit,
+                &payload.payment_method,
+                payload.user_id,
+                payload.store_id,
+            )
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(layaway_id)
+    }
+
+    /// Records a deposit in the open cash session. When it pays the layaway
+    /// off, the layaway is closed and `sale` is created in the same
+    /// transaction. Returns the id of the created order, if any.
+    pub async fn add_payment(
+        &self,
+        layaway: &Layaway,
+        cash_session_id: i64,
+        amount: Money,
+        payment_method: &str,
+        user_id: i64,
+        sale: &CreateOrderPayload,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let (paid_amount, total) = Self::insert_payment(
+            &mut tx,
+            layaway.id,
+            cash_session_id,
+            amount,
+            payment_method,
+            user_id,
+            layaway.store_id,
+        )
+        .await?;
+
+        let order_id = if paid_amount == total {
+            // Release the reservation first so the sale can take the units
+            let completed = sqlx::query(
+                "UPDATE layaways SET status = 'completed' WHERE id = ? AND status = 'active'",
+            )
+            .bind(layaway.id)
+            .execute(&mut *tx)
+            .await?;
+            if completed.rows_affected() == 0 {
+                return Err(sqlx::Error::InvalidArgument(LAYAWAY_PAYMENT_REJECTED.to_string()));
+            }
+
+            let order_id = SalesRepository::insert_order(&mut tx, sale).await?;
+
+            sqlx::query("UPDATE layaways SET order_id = ? WHERE id = ?")
+                .bind(order_id)
+                .bind(layaway.id)
+                .execute(&mut *tx)
+                .await?;
+            Some(order_id)
+        } else {
+            None
+        };
+
+        tx.commit().await?;
+        Ok(order_id)
+    }
+
+    /// Books the deposit against the layaway, which must still be active and
+    /// owe at least `amount`, and into the cash session, which must still be
+    /// open. Returns the new paid amount and the total.
+    async fn insert_payment(
+        tx: &mut Transaction<'_, Sqlite>,
+        layaway_id: i64,
+        cash_session_id: i64,
+        amount: Money,
+        payment_method: &str,
+        user_id: i64,
+        store_id: i64,
+    ) -> Result<(Money, Money), sqlx::Error> {
+        let claimed: Option<(Money, Money)> = sqlx::query_as(
+            r#"
+            UPDATE layaways SET paid_amount = paid_amount + ?1
+            WHERE id = ?2 AND status = 'active' AND paid_amount + ?1 <= total
+            RETURNING paid_amount, total
+            "#,
+        )
+        .bind(amount)
+        .bind(layaway_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+        let Some(balance) = claimed else {
+            return Err(sqlx::Error::InvalidArgument(LAYAWAY_PAYMENT_REJECTED.to_string()));
+        };
+
+        sqlx::query(
+            "INSERT INTO layaway_payments (layaway_id, cash_session_id, amount, payment_method, user_id, store_id) VALUES (?, ?, ?, ?, ?, ?)",
+        )
+        .bind(layaway_id)
+        .bind(cash_session_id)
+        .bind(amount)
+        .bind(payment_method)
+        .bind(user_id)
+        .bind(store_id)
+        .execute(&mut **tx)
+        .await?;
+
+        let column = if payment_method == "cash" {
+            "expected_closing_cash"
+        } else {
+            "expected_closing_virtual"
+        };
+        let updated = sqlx::query(&format!(
+            "UPDATE cash_sessions SET {0} = {0} + ? WHERE id = ? AND status = 'open'",
+            column
+        ))
+        .bind(amount)
+        .bind(cash_session_id)
+        .execute(&mut **tx)
+        .await?;
+        if updated.rows_affected() == 0 {
+            return Err(sqlx::Error::InvalidArgument(CASH_SESSION_CLOSED.to_string()));
+        }
+
+        Ok(balance)
+    }
+
+    pub async fn find_by_store(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<Layaway>, sqlx::Error> {
+        sqlx::query_as::<_, Layaway>(
+            "SELECT * FROM layaways WHERE store_id = ? AND (? IS NULL OR status = ?) ORDER BY created_at DESC, id DESC",
+        )
+        .bind(store_id)
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<Layaway>, sqlx::Error> {
+        sqlx::query_as::<_, Layaway>("SELECT * FROM layaways WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_items(&self, layaway_id: i64) -> Result<Vec<LayawayItem>, sqlx::Error> {
+        sqlx::query_as::<_, LayawayItem>(
+            "SELECT * FROM layaway_items WHERE layaway_id = ? ORDER BY id ASC",
+        )
+        .bind(layaway_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_payments(&self, layaway_id: i64) -> Result<Vec<LayawayPayment>, sqlx::Error> {
+        sqlx::query_as::<_, LayawayPayment>(
+            "SELECT * FROM layaway_payments WHERE layaway_id = ? ORDER BY created_at ASC, id ASC",
+        )
+        .bind(layaway_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn update_status(&self, id: i64, status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE layaways SET status = ? WHERE id = ?")
+            .bind(status)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Flags active layaways past their expiry date. Their stock is already
+    /// released by `RESERVED_STOCK_SQL`; this only keeps the status accurate.
+    pub async fn expire_overdue(&self, store_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE layaways SET status = 'expired'
+             WHERE store_id = ? AND status = 'active' AND expires_at < datetime('now', 'localtime')",
+        )
+        .bind(store_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,344 @@
+use crate::models::loyalty::{CategoryMultiplier, Customer, LoyaltyEntry, LoyaltySettings};
+use sqlx::{Sqlite, SqliteConnection, SqlitePool, Transaction};
+
+/// Points lots that can still be redeemed (not expired, not used up).
+const AVAILABLE_LOTS_FILTER: &str = "entry_type IN ('earn', 'refund') AND remaining > 0 AND expires_at >= datetime('now', 'localtime')";
+
+pub struct LoyaltyRepository {
+    pool: SqlitePool,
+}
+
+impl LoyaltyRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Reads the `loyalty_*` keys from `app_config`.
+    pub async fn settings(conn: &mut SqliteConnection) -> Result<LoyaltySettings, sqlx::Error> {
+        let rows: Vec<(String, String)> =
+            sqlx::query_as("SELECT key, value FROM app_config WHERE key LIKE 'loyalty_%'")
+                .fetch_all(&mut *conn)
+                .await?;
+
+        let get = |key: &str| {
+            rows.iter()
+                .find(|(k, _)| k == key)
+                .map(|(_, v)| v.as_str())
+        };
+
+        Ok(LoyaltySettings {
+            enabled: get("loyalty_enabled") != Some("0"),
+            points_per_sol: get("loyalty_points_per_sol")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1.0),
+            point_value: get("loyalty_point_value")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.05),
+            expiry_days: get("loyalty_expiry_days")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(365),
+        })
+    }
+
+    pub async fn get_settings(&self) -> Result<LoyaltySettings, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::settings(&mut conn).await
+    }
+
+    /// Finds the customer by document (preferred) or phone, creating it when
+    /// neither matches. Returns `None` for anonymous sales.
+    pub async fn upsert_customer(
+        conn: &mut SqliteConnection,
+        document: Option<&str>,
+        phone: Option<&str>,
+        name: Option<&str>,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let document = document.map(str::trim).filter(|d| !d.is_empty());
+        let phone = phone.map(str::trim).filter(|p| !p.is_empty());
+
+        let existing: Option<i64> = match (document, phone) {
+            (Some(doc), _) => {
+                sqlx::query_scalar("SELECT id FROM customers WHERE document = ?")
+                    .bind(doc)
+                    .fetch_optional(&mut *conn)
+                    .await?
+            }
+            (None, Some(phone)) => {
+                sqlx::query_scalar("SELECT id FROM customers WHERE phone = ? ORDER BY id LIMIT 1")
+                    .bind(phone)
+                    .fetch_optional(&mut *conn)
+                    .await?
+            }
+            (None, None) => return Ok(None),
+        };
+
+        match existing {
+            Some(id) => {
+                sqlx::query(
+                    "UPDATE customers SET phone = COALESCE(?, phone), name = COALESCE(?, name) WHERE id = ?",
+                )
+                .bind(phone)
+                .bind(name)
+                .bind(id)
+                .execute(&mut *conn)
+                .await?;
+                Ok(Some(id))
+            }
+            None => {
+                let id = sqlx::query("INSERT INTO customers (document, phone, name) VALUES (?, ?, ?)")
+                    .bind(document)
+                    .bind(phone)
+                    .bind(name)
+                    .execute(&mut *conn)
+                    .await?
+                    .last_insert_rowid();
+                Ok(Some(id))
+            }
+        }
+    }
+
+    /// Credits the points earned by an order. Each line earns
+    /// `subtotal * points_per_sol * category multiplier`, scaled by the share of
+    /// the total that was not paid with points.
+    pub async fn earn_for_order(
+        tx: &mut Transaction<'_, Sqlite>,
+        order_id: i64,
+        customer_id: i64,
+        settings: &LoyaltySettings,
+        paid_fraction: f64,
+    ) -> Result<i64, sqlx::Error> {
+        let weighted: f64 = sqlx::query_scalar(
+            r#"
+            SELECT COALESCE(SUM(oi.subtotal * COALESCE(m.multiplier, 1)), 0)
+            FROM order_items oi
+            LEFT JOIN products p ON p.id = oi.product_id
+            LEFT JOIN loyalty_category_multipliers m ON m.category_id = p.category_id
+            WHERE oi.order_id = ?
+            "#,
+        )
+        .bind(order_id)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        // `weighted` is in céntimos
+        let points = (weighted / 100.0 * settings.points_per_sol * paid_fraction).floor() as i64;
+        if points > 0 {
+            Self::insert_lot(tx, customer_id, Some(order_id), "earn", points, settings.expiry_days).await?;
+        }
+        Ok(points)
+    }
+
+    /// Consumes `points` from the customer's lots, oldest expiry first.
+    /// Returns `false`, consuming nothing, if the balance is not enough.
+    pub async fn redeem(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: i64,
+        order_id: i64,
+        points: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let lots: Vec<(i64, i64)> = sqlx::query_as(&format!(
+            "SELECT id, remaining FROM loyalty_ledger WHERE customer_id = ? AND {} ORDER BY expires_at ASC, id ASC",
+            AVAILABLE_LOTS_FILTER
+        ))
+        .bind(customer_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        if lots.iter().map(|(_, remaining)| remaining).sum::<i64>() < points {
+            return Ok(false);
+        }
+
+        let mut pending = points;
+        for (lot_id, remaining) in lots {
+            if pending == 0 {
+                break;
+            }
+            let used = remaining.min(pending);
+            sqlx::query("UPDATE loyalty_ledger SET remaining = remaining - ? WHERE id = ?")
+                .bind(used)
+                .bind(lot_id)
+                .execute(&mut **tx)
+                .await?;
+            pending -= used;
+        }
+
+        sqlx::query(
+            "INSERT INTO loyalty_ledger (customer_id, order_id, entry_type, points) VALUES (?, ?, 'redeem', ?)",
+        )
+        .bind(customer_id)
+        .bind(order_id)
+        .bind(-points)
+        .execute(&mut **tx)
+        .await?;
+        Ok(true)
+    }
+
+    /// Undoes `fraction` (0..=1) of an order's points: claws back what was
+    /// earned (as far as it hasn't been spent) and gives back what was redeemed.
+    pub async fn reverse_order(
+        tx: &mut Transaction<'_, Sqlite>,
+        order_id: i64,
+        fraction: f64,
+    ) -> Result<(), sqlx::Error> {
+        let fraction = fraction.clamp(0.0, 1.0);
+        let settings = Self::settings(tx).await?;
+
+        let earned: Vec<(i64, i64, i64, i64)> = sqlx::query_as(
+            "SELECT id, customer_id, points, remaining FROM loyalty_ledger WHERE order_id = ? AND entry_type = 'earn'",
+        )
+        .bind(order_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for (lot_id, customer_id, points, remaining) in earned {
+            let clawback = ((points as f64 * fraction).round() as i64).min(remaining);
+            if clawback <= 0 {
+                continue;
+            }
+            sqlx::query("UPDATE loyalty_ledger SET remaining = remaining - ? WHERE id = ?")
+                .bind(clawback)
+                .bind(lot_id)
+                .execute(&mut **tx)
+                .await?;
+            sqlx::query(
+                "INSERT INTO loyalty_ledger (customer_id, order_id, entry_type, points) VALUES (?, ?, 'reverse', ?)",
+            )
+            .bind(customer_id)
+            .bind(order_id)
+            .bind(-clawback)
+            .execute(&mut **tx)
+            .await?;
+        }
+
+        let redeemed: Vec<(i64, i64)> = sqlx::query_as(
+            "SELECT customer_id, points FROM loyalty_ledger WHERE order_id = ? AND entry_type = 'redeem'",
+        )
+        .bind(order_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for (customer_id, points) in redeemed {
+            let refund = (-points as f64 * fraction).round() as i64;
+            if refund > 0 {
+                Self::insert_lot(tx, customer_id, Some(order_id), "refund", refund, settings.expiry_days)
+                    .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn insert_lot(
+        tx: &mut Transaction<'_, Sqlite>,
+        customer_id: i64,
+        order_id: Option<i64>,
+        entry_type: &str,
+        points: i64,
+        expiry_days: i64,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO loyalty_ledger (customer_id, order_id, entry_type, points, remaining, expires_at)
+            VALUES (?, ?, ?, ?, ?, datetime('now', 'localtime', ?))
+            "#,
+        )
+        .bind(customer_id)
+        .bind(order_id)
+        .bind(entry_type)
+        .bind(points)
+        .bind(points)
+        .bind(format!("+{} days", expiry_days))
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    /// Writes an 'expire' entry for every lot that ran out with points left.
+    pub async fn expire_points(&self, customer_id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO loyalty_ledger (customer_id, entry_type, points)
+            SELECT customer_id, 'expire', -SUM(remaining) FROM loyalty_ledger
+            WHERE customer_id = ? AND entry_type IN ('earn', 'refund') AND remaining > 0
+              AND expires_at < datetime('now', 'localtime')
+            GROUP BY customer_id
+            "#,
+        )
+        .bind(customer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            "UPDATE loyalty_ledger SET remaining = 0
+             WHERE customer_id = ? AND entry_type IN ('earn', 'refund') AND remaining > 0
+               AND expires_at < datetime('now', 'localtime')",
+        )
+        .bind(customer_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn find_customer(
+        &self,
+        document: Option<&str>,
+        phone: Option<&str>,
+    ) -> Result<Option<Customer>, sqlx::Error> {
+        sqlx::query_as::<_, Customer>(
+            "SELECT * FROM customers WHERE (? IS NOT NULL AND document = ?) OR (? IS NOT NULL AND phone = ?) ORDER BY document IS NULL, id LIMIT 1",
+        )
+        .bind(document)
+        .bind(document)
+        .bind(phone)
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn balance(&self, customer_id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar(&format!(
+            "SELECT COALESCE(SUM(remaining), 0) FROM loyalty_ledger WHERE customer_id = ? AND {}",
+            AVAILABLE_LOTS_FILTER
+        ))
+        .bind(customer_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    pub async fn find_entries(&self, customer_id: i64) -> Result<Vec<LoyaltyEntry>, sqlx::Error> {
+        sqlx::query_as::<_, LoyaltyEntry>(
+            "SELECT * FROM loyalty_ledger WHERE customer_id = ? ORDER BY created_at DESC, id DESC",
+        )
+        .bind(customer_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn get_multipliers(&self) -> Result<Vec<CategoryMultiplier>, sqlx::Error> {
+        sqlx::query_as::<_, CategoryMultiplier>(
+            r#"
+            SELECT m.category_id, c.name AS category_name, m.multiplier
+            FROM loyalty_category_multipliers m
+            LEFT JOIN categories c ON c.id = m.category_id
+            ORDER BY c.name ASC
+            "#,
+        )
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn set_multiplier(&self, category_id: i64, multiplier: f64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO loyalty_category_multipliers (category_id, multiplier) VALUES (?, ?)
+             ON CONFLICT(category_id) DO UPDATE SET multiplier = excluded.multiplier",
+        )
+        .bind(category_id)
+        .bind(multiplier)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
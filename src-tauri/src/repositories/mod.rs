@@ -1,6 +1,25 @@
+pub mod barcode_repo;
 pub mod cash_repo;
+pub mod catalog_repo;
+pub mod commission_repo;
+pub mod inventory_count_repo;
 pub mod inventory_repo;
+pub mod kit_repo;
+pub mod layaway_repo;
+pub mod loyalty_repo;
+pub mod price_change_repo;
+pub mod price_list_repo;
+pub mod product_cost_repo;
+pub mod product_image_repo;
+pub mod product_import_repo;
 pub mod purchase_order_repo;
+pub mod quotation_repo;
+pub mod reorder_repo;
 pub mod sales_repo;
+pub mod stock_adjustment_repo;
+pub mod stock_movement_repo;
+pub mod stock_transfer_repo;
 pub mod store_repo;
+pub mod stored_value_repo;
 pub mod user_repo;
+pub mod variant_repo;
@@ -0,0 +1,361 @@
+use crate::models::money::Money;
+use crate::models::price_change::{
+    PendingPurchasePrice, ProductPriceChange, ScheduledPriceChange, ScheduledPriceItemPayload,
+};
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::stock_movement_repo::PRODUCT_WITHOUT_STORE;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const SCHEDULED_SELECT: &str = r#"
+    SELECT sc.id, sc.product_id, p.code AS product_code, p.name AS product_name, sc.store_id,
+           CAST(p.price AS INTEGER) AS current_price, sc.new_price, sc.effective_at, sc.status,
+           sc.purchase_order_item_id, sc.note, sc.created_by, u.username AS created_by_name,
+           sc.created_at, sc.applied_at
+    FROM scheduled_price_changes sc
+    INNER JOIN products p ON p.id = sc.product_id
+    LEFT JOIN users u ON u.id = sc.created_by
+"#;
+
+/// (id, product_id, new_price, created_by, purchase_order_item_id)
+type DueChange = (i64, i64, Money, Option<i64>, Option<i64>);
+
+pub struct PriceChangeRepository {
+    pool: SqlitePool,
+}
+
+impl PriceChangeRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Sets a product's price and records the change. A variant keeps it as
+    /// an override only if it differs from its style price.
+    pub async fn set_price(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+        price: Money,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        let previous = Self::current_price(conn, product_id).await?;
+        sqlx::query(
+            r#"
+            UPDATE products SET
+                price = ?1,
+                price_override = CASE
+                    WHEN style_id IS NULL THEN price_override
+                    WHEN ?1 = (SELECT s.price FROM product_styles s WHERE s.id = products.style_id) THEN NULL
+                    ELSE ?1
+                END
+            WHERE id = ?2
+            "#,
+        )
+        .bind(price)
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await?;
+        Self::record_change(conn, product_id, previous, price, source).await
+    }
+
+    pub async fn current_price(conn: &mut SqliteConnection, product_id: i64) -> Result<Money, sqlx::Error> {
+        sqlx::query_scalar("SELECT CAST(price AS INTEGER) FROM products WHERE id = ?")
+            .bind(product_id)
+            .fetch_one(&mut *conn)
+            .await
+    }
+
+    /// Current price of every variant of a style, taken before updating it so
+    /// `record_changes` can tell which ones changed.
+    pub async fn style_prices(conn: &mut SqliteConnection, style_id: i64) -> Result<Vec<(i64, Money)>, sqlx::Error> {
+        sqlx::query_as("SELECT id, CAST(price AS INTEGER) FROM products WHERE style_id = ?")
+            .bind(style_id)
+            .fetch_all(&mut *conn)
+            .await
+    }
+
+    /// Records the products whose price is no longer the one in `previous`.
+    pub async fn record_changes(
+        conn: &mut SqliteConnection,
+        previous: &[(i64, Money)],
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        for (product_id, previous_price) in previous {
+            let price = Self::current_price(conn, *product_id).await?;
+            Self::record_change(conn, *product_id, *previous_price, price, source).await?;
+        }
+        Ok(())
+    }
+
+    /// Writes a history line if the price changed.
+    pub async fn record_change(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+        previous_price: Money,
+        new_price: Money,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        if previous_price == new_price {
+            return Ok(());
+        }
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO product_price_history (product_id, store_id, previous_price, new_price, reason, document_type, document_id, user_id, note)
+            SELECT id, store_id, ?, ?, ?, ?, ?, ?, ? FROM products WHERE id = ? AND store_id IS NOT NULL
+            "#,
+        )
+        .bind(previous_price)
+        .bind(new_price)
+        .bind(source.reason)
+        .bind(source.document_type)
+        .bind(source.document_id)
+        .bind(source.user_id)
+        .bind(&source.note)
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            return Err(sqlx::Error::InvalidArgument(PRODUCT_WITHOUT_STORE.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn find_history(&self, product_id: i64) -> Result<Vec<ProductPriceChange>, sqlx::Error> {
+        sqlx::query_as::<_, ProductPriceChange>(
+            r#"
+            SELECT h.id, h.product_id, h.store_id, h.previous_price, h.new_price, h.reason,
+                   h.document_type, h.document_id, h.user_id, u.username AS user_name,
+                   h.note, h.created_at
+            FROM product_price_history h
+            LEFT JOIN users u ON u.id = h.user_id
+            WHERE h.product_id = ?
+            ORDER BY h.id DESC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn schedule(
+        &self,
+        store_id: i64,
+        user_id: i64,
+        effective_at: &str,
+        note: Option<&str>,
+        items: &[ScheduledPriceItemPayload],
+    ) -> Result<Vec<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut ids = Vec::with_capacity(items.len());
+        for item in items {
+            let id = sqlx::query(
+                r#"
+                INSERT INTO scheduled_price_changes (product_id, store_id, new_price, effective_at, note, created_by)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(item.product_id)
+            .bind(store_id)
+            .bind(item.new_price)
+            .bind(effective_at)
+            .bind(note)
+            .bind(user_id)
+            .execute(&mut *tx)
+            .await?
+            .last_insert_rowid();
+            ids.push(id);
+        }
+        tx.commit().await?;
+        Ok(ids)
+    }
+
+    pub async fn find_scheduled(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<ScheduledPriceChange>, sqlx::Error> {
+        sqlx::query_as::<_, ScheduledPriceChange>(&format!(
+            r#"{} WHERE sc.store_id = ? AND (? IS NULL OR sc.status = ?)
+                ORDER BY sc.effective_at ASC, sc.id ASC"#,
+            SCHEDULED_SELECT
+        ))
+        .bind(store_id)
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_scheduled_by_ids(&self, ids: &[i64]) -> Result<Vec<ScheduledPriceChange>, sqlx::Error> {
+        let mut changes = Vec::with_capacity(ids.len());
+        for id in ids {
+            let change = sqlx::query_as::<_, ScheduledPriceChange>(&format!("{} WHERE sc.id = ?", SCHEDULED_SELECT))
+                .bind(id)
+                .fetch_one(&self.pool)
+                .await?;
+            changes.push(change);
+        }
+        Ok(changes)
+    }
+
+    /// Cancels a pending change. A change confirmed from a purchase order
+    /// leaves its line discarded. Returns `false` if it was no longer pending.
+    pub async fn cancel_scheduled(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let item_id: Option<Option<i64>> = sqlx::query_scalar(
+            "UPDATE scheduled_price_changes SET status = 'cancelled' WHERE id = ? AND status = 'pending' RETURNING purchase_order_item_id",
+        )
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let Some(item_id) = item_id else {
+            return Ok(false);
+        };
+        if let Some(item_id) = item_id {
+            sqlx::query("UPDATE purchase_order_items SET price_status = 'discarded' WHERE id = ?")
+                .bind(item_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Applies every pending change whose time has come, oldest first, so the
+    /// latest one wins for a product. Returns how many were applied.
+    pub async fn apply_due(&self) -> Result<usize, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let due: Vec<DueChange> = sqlx::query_as(
+            r#"
+            SELECT id, product_id, new_price, created_by, purchase_order_item_id
+            FROM scheduled_price_changes
+            WHERE status = 'pending' AND effective_at <= datetime('now', 'localtime')
+            ORDER BY effective_at ASC, id ASC
+            "#,
+        )
+        .fetch_all(&mut *tx)
+        .await?;
+
+        for (id, product_id, new_price, created_by, item_id) in &due {
+            sqlx::query(
+                "UPDATE scheduled_price_changes SET status = 'applied', applied_at = datetime('now', 'localtime') WHERE id = ?",
+            )
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+            let source = MovementSource::document("scheduled", "scheduled_price_change", *id, *created_by);
+            Self::set_price(&mut tx, *product_id, *new_price, &source).await?;
+            if let Some(item_id) = item_id {
+                sqlx::query("UPDATE purchase_order_items SET price_status = 'applied' WHERE id = ?")
+                    .bind(item_id)
+                    .execute(&mut *tx)
+                    .await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(due.len())
+    }
+
+    /// Purchase order lines of the store whose batch price waits for confirmation.
+    pub async fn find_pending_purchase_prices(&self, store_id: i64) -> Result<Vec<PendingPurchasePrice>, sqlx::Error> {
+        sqlx::query_as::<_, PendingPurchasePrice>(
+            r#"
+            SELECT i.id AS item_id, i.purchase_order_id, po.alias AS order_alias, po.supplier_name,
+                   p.id AS product_id, p.code AS product_code, p.name AS product_name,
+                   CAST(p.price AS INTEGER) AS current_price,
+                   CAST(i.unit_price AS INTEGER) AS proposed_price,
+                   CAST(i.unit_cost AS INTEGER) AS unit_cost,
+                   CAST(po.created_at AS TEXT) AS created_at
+            FROM purchase_order_items i
+            INNER JOIN purchase_orders po ON po.id = i.purchase_order_id
+            INNER JOIN products p ON p.id = i.product_id
+            WHERE po.store_id = ? AND i.price_status = 'pending'
+            ORDER BY po.created_at DESC, i.id ASC
+            "#,
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Applies (or schedules, with `effective_at`) the batch prices of the
+    /// given pending lines. Returns how many lines were confirmed.
+    pub async fn confirm_purchase_prices(
+        &self,
+        store_id: i64,
+        user_id: i64,
+        item_ids: &[i64],
+        effective_at: Option<&str>,
+    ) -> Result<usize, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut confirmed = 0;
+        for item_id in item_ids {
+            let status = if effective_at.is_some() { "scheduled" } else { "applied" };
+            let line: Option<(i64, i64, Money)> = sqlx::query_as(
+                r#"
+                UPDATE purchase_order_items SET price_status = ?
+                WHERE id = ? AND price_status = 'pending'
+                  AND purchase_order_id IN (SELECT id FROM purchase_orders WHERE store_id = ?)
+                RETURNING purchase_order_id, product_id, CAST(unit_price AS INTEGER)
+                "#,
+            )
+            .bind(status)
+            .bind(item_id)
+            .bind(store_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+            let Some((order_id, product_id, price)) = line else {
+                continue;
+            };
+
+            match effective_at {
+                Some(effective_at) => {
+                    sqlx::query(
+                        r#"
+                        INSERT INTO scheduled_price_changes (product_id, store_id, new_price, effective_at, purchase_order_item_id, note, created_by)
+                        VALUES (?, ?, ?, ?, ?, ?, ?)
+                        "#,
+                    )
+                    .bind(product_id)
+                    .bind(store_id)
+                    .bind(price)
+                    .bind(effective_at)
+                    .bind(item_id)
+                    .bind(format!("Orden de compra #{}", order_id))
+                    .bind(user_id)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+                None => {
+                    let source =
+                        MovementSource::document("purchase_order", "purchase_order", order_id, Some(user_id));
+                    Self::set_price(&mut tx, product_id, price, &source).await?;
+                }
+            }
+            confirmed += 1;
+        }
+        tx.commit().await?;
+        Ok(confirmed)
+    }
+
+    /// Keeps the current prices of the given pending lines. Returns how many were discarded.
+    pub async fn discard_purchase_prices(&self, store_id: i64, item_ids: &[i64]) -> Result<usize, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut discarded = 0;
+        for item_id in item_ids {
+            let result = sqlx::query(
+                r#"
+                UPDATE purchase_order_items SET price_status = 'discarded'
+                WHERE id = ? AND price_status = 'pending'
+                  AND purchase_order_id IN (SELECT id FROM purchase_orders WHERE store_id = ?)
+                "#,
+            )
+            .bind(item_id)
+            .bind(store_id)
+            .execute(&mut *tx)
+            .await?;
+            discarded += result.rows_affected() as usize;
+        }
+        tx.commit().await?;
+        Ok(discarded)
+    }
+}
@@ -0,0 +1,170 @@
+use crate::models::price_list::{PriceList, PriceListItem};
+use crate::models::money::Money;
+use sqlx::SqlitePool;
+
+pub struct PriceListRepository {
+    pool: SqlitePool,
+}
+
+impl PriceListRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn get_price_lists(&self) -> Result<Vec<PriceList>, sqlx::Error> {
+        sqlx::query_as::<_, PriceList>("SELECT * FROM price_lists ORDER BY id ASC")
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<PriceList>, sqlx::Error> {
+        sqlx::query_as::<_, PriceList>("SELECT * FROM price_lists WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn create_price_list(
+        &self,
+        name: &str,
+        description: Option<&str>,
+    ) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query("INSERT INTO price_lists (name, description) VALUES (?, ?)")
+            .bind(name)
+            .bind(description)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    pub async fn update_price_list(
+        &self,
+        id: i64,
+        name: &str,
+        description: Option<&str>,
+        is_active: bool,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE price_lists SET name = ?, description = ?, is_active = ? WHERE id = ?")
+            .bind(name)
+            .bind(description)
+            .bind(is_active)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Lists the prices of a list (all tiers), optionally for one store's products.
+    pub async fn get_items(
+        &self,
+        price_list_id: i64,
+        store_id: Option<i64>,
+    ) -> Result<Vec<PriceListItem>, sqlx::Error> {
+        sqlx::query_as::<_, PriceListItem>(
+            r#"
+            SELECT
+                i.id,
+                i.price_list_id,
+                i.product_id,
+                p.code AS product_code,
+                p.name AS product_name,
+                CAST(p.price AS INTEGER) AS base_price,
+                i.min_quantity,
+                CAST(i.price AS INTEGER) AS price
+            FROM price_list_items i
+            LEFT JOIN products p ON p.id = i.product_id
+            WHERE i.price_list_id = ? AND (? IS NULL OR p.store_id = ?)
+            ORDER BY p.name ASC, i.min_quantity ASC
+            "#,
+        )
+        .bind(price_list_id)
+        .bind(store_id)
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn set_item_price(
+        &self,
+        price_list_id: i64,
+        product_id: i64,
+        min_quantity: i64,
+        price: Money,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "INSERT INTO price_list_items (price_list_id, product_id, min_quantity, price) VALUES (?, ?, ?, ?)
+             ON CONFLICT(price_list_id, product_id, min_quantity) DO UPDATE SET price = excluded.price",
+        )
+        .bind(price_list_id)
+        .bind(product_id)
+        .bind(min_quantity)
+        .bind(price)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn delete_item(&self, id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query("DELETE FROM price_list_items WHERE id = ?")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Unit price for `quantity` units: the list's highest quantity break that
+    /// applies, or the product's shelf price if the list has none.
+    pub async fn resolve_price(
+        &self,
+        price_list_id: i64,
+        product_id: i64,
+        quantity: i64,
+    ) -> Result<Money, sqlx::Error> {
+        sqlx::query_scalar(
+            r#"
+            SELECT CAST(COALESCE(
+                (SELECT i.price FROM price_list_items i
+                 WHERE i.price_list_id = ? AND i.product_id = p.id AND i.min_quantity <= ?
+                 ORDER BY i.min_quantity DESC LIMIT 1),
+                p.price) AS INTEGER)
+            FROM products p WHERE p.id = ?
+            "#,
+        )
+        .bind(price_list_id)
+        .bind(quantity)
+        .bind(product_id)
+        .fetch_one(&self.pool)
+        .await
+    }
+
+    /// Price list assigned to the customer identified by document or phone.
+    pub async fn find_customer_price_list(
+        &self,
+        document: Option<&str>,
+        phone: Option<&str>,
+    ) -> Result<Option<i64>, sqlx::Error> {
+        let list: Option<Option<i64>> = sqlx::query_scalar(
+            "SELECT price_list_id FROM customers WHERE (? IS NOT NULL AND document = ?) OR (? IS NOT NULL AND phone = ?) ORDER BY document IS NULL, id LIMIT 1",
+        )
+        .bind(document)
+        .bind(document)
+        .bind(phone)
+        .bind(phone)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(list.flatten())
+    }
+
+    pub async fn set_customer_price_list(
+        &self,
+        customer_id: i64,
+        price_list_id: Option<i64>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE customers SET price_list_id = ? WHERE id = ?")
+            .bind(price_list_id)
+            .bind(customer_id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+use crate::models::money::Money;
+use crate::models::product_cost::ProductCostChange;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::stock_movement_repo::{StockMovementRepository, PRODUCT_WITHOUT_STORE};
+use sqlx::{SqliteConnection, SqlitePool};
+
+pub struct ProductCostRepository {
+    pool: SqlitePool,
+}
+
+impl ProductCostRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Books `quantity` units bought at `unit_cost`: averages the product's cost
+    /// with the stock on hand, records the change and adds the stock through
+    /// the kardex. Call it inside the transaction of the receiving document.
+    /// Returns the new cost.
+    pub async fn receive(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+        quantity: i64,
+        unit_cost: Money,
+        source: &MovementSource,
+    ) -> Result<Money, sqlx::Error> {
+        let (stock, cost, store_id): (i64, Money, Option<i64>) = sqlx::query_as(
+            "SELECT stock, CAST(cost AS INTEGER), store_id FROM products WHERE id = ?",
+        )
+        .bind(product_id)
+        .fetch_one(&mut *conn)
+        .await?;
+        let store_id = store_id
+            .ok_or_else(|| sqlx::Error::InvalidArgument(PRODUCT_WITHOUT_STORE.to_string()))?;
+
+        let new_cost = weighted_average(cost, stock, unit_cost, quantity);
+        sqlx::query("UPDATE products SET cost = ? WHERE id = ?")
+            .bind(new_cost)
+            .bind(product_id)
+            .execute(&mut *conn)
+            .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO product_cost_history (product_id, store_id, previous_cost, new_cost, quantity, unit_cost, stock_before, reason, document_type, document_id, user_id, note)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(product_id)
+        .bind(store_id)
+        .bind(cost)
+        .bind(new_cost)
+        .bind(quantity)
+        .bind(unit_cost)
+        .bind(stock)
+        .bind(source.reason)
+        .bind(source.document_type)
+        .bind(source.document_id)
+        .bind(source.user_id)
+        .bind(&source.note)
+        .execute(&mut *conn)
+        .await?;
+
+        StockMovementRepository::apply(conn, product_id, quantity, source).await?;
+        Ok(new_cost)
+    }
+
+    /// Records a cost set by hand (product edit, import) if it changed.
+    pub async fn record_change(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+        previous_cost: Money,
+        new_cost: Money,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        if previous_cost == new_cost {
+            return Ok(());
+        }
+        let inserted = sqlx::query(
+            r#"
+            INSERT INTO product_cost_history (product_id, store_id, previous_cost, new_cost, reason, document_type, document_id, user_id, note)
+            SELECT id, store_id, ?, ?, ?, ?, ?, ?, ? FROM products WHERE id = ? AND store_id IS NOT NULL
+            "#,
+        )
+        .bind(previous_cost)
+        .bind(new_cost)
+        .bind(source.reason)
+        .bind(source.document_type)
+        .bind(source.document_id)
+        .bind(source.user_id)
+        .bind(&source.note)
+        .bind(product_id)
+        .execute(&mut *conn)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            return Err(sqlx::Error::InvalidArgument(PRODUCT_WITHOUT_STORE.to_string()));
+        }
+        Ok(())
+    }
+
+    pub async fn find_by_product(&self, product_id: i64) -> Result<Vec<ProductCostChange>, sqlx::Error> {
+        sqlx::query_as::<_, ProductCostChange>(
+            r#"
+            SELECT h.id, h.product_id, h.store_id, h.previous_cost, h.new_cost, h.quantity,
+                   h.unit_cost, h.stock_before, h.reason, h.document_type, h.document_id,
+                   h.user_id, u.username AS user_name, h.note, h.created_at
+            FROM product_cost_history h
+            LEFT JOIN users u ON u.id = h.user_id
+            WHERE h.product_id = ?
+            ORDER BY h.id DESC
+            "#,
+        )
+        .bind(product_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
+
+/// Moving average of the units on hand and the ones received. Negative stock
+/// (sold before it was received) carries no cost, so the batch cost wins.
+fn weighted_average(cost: Money, stock: i64, unit_cost: Money, quantity: i64) -> Money {
+    let on_hand = stock.max(0);
+    if quantity <= 0 {
+        return cost;
+    }
+    if on_hand == 0 {
+        return unit_cost;
+    }
+    (cost.times(on_hand) + unit_cost.times(quantity)).mul_div(1, on_hand + quantity)
+}
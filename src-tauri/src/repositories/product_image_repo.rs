@@ -0,0 +1,98 @@
+use crate::models::product_image::ProductImageRecord;
+use sqlx::SqlitePool;
+
+/// Images imported less than an hour ago are never orphans: the product
+/// that will use them may not be saved yet.
+const ORPHAN_GRACE_SQL: &str = "created_at < datetime('now', '-1 hour')";
+
+/// Hashes referenced by active products or styles, as `image_url` values.
+const REFERENCED_IMAGES_SQL: &str = r#"
+    SELECT image_url FROM products WHERE is_active = 1 AND image_url LIKE 'local-image:%'
+    UNION
+    SELECT image_url FROM product_styles WHERE is_active = 1 AND image_url LIKE 'local-image:%'
+"#;
+
+pub struct ProductImageRepository {
+    pool: SqlitePool,
+}
+
+impl ProductImageRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn find_by_hash(&self, hash: &str) -> Result<Option<ProductImageRecord>, sqlx::Error> {
+        sqlx::query_as::<_, ProductImageRecord>("SELECT * FROM product_images WHERE hash = ?")
+            .bind(hash)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn insert(&self, image: &ProductImageRecord) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO product_images (hash, extension, width, height, size_bytes)
+            VALUES (?, ?, ?, ?, ?)
+            ON CONFLICT(hash) DO NOTHING
+            "#,
+        )
+        .bind(&image.hash)
+        .bind(&image.extension)
+        .bind(image.width)
+        .bind(image.height)
+        .bind(image.size_bytes)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
+    /// `image_url` values of active products and styles that point to local images.
+    pub async fn find_referenced(&self) -> Result<Vec<String>, sqlx::Error> {
+        sqlx::query_scalar(REFERENCED_IMAGES_SQL)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Stored images no active product or style uses anymore.
+    pub async fn find_orphans(&self) -> Result<Vec<ProductImageRecord>, sqlx::Error> {
+        let sql = format!(
+            "SELECT * FROM product_images WHERE {} AND 'local-image:' || hash NOT IN ({})",
+            ORPHAN_GRACE_SQL, REFERENCED_IMAGES_SQL
+        );
+        sqlx::query_as::<_, ProductImageRecord>(&sql)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Forgets the image if it is still unused and returns whether it did.
+    /// Deleted products and styles that point to it lose the reference so
+    /// they don't show a broken picture if restored.
+    pub async fn delete_if_orphaned(&self, hash: &str) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let sql = format!(
+            "DELETE FROM product_images WHERE hash = ? AND {} AND 'local-image:' || hash NOT IN ({})",
+            ORPHAN_GRACE_SQL, REFERENCED_IMAGES_SQL
+        );
+        let deleted = sqlx::query(&sql)
+            .bind(hash)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected()
+            > 0;
+        if !deleted {
+            return Ok(false);
+        }
+
+        let reference = format!("local-image:{}", hash);
+        sqlx::query("UPDATE products SET image_url = NULL WHERE image_url = ? AND is_active = 0")
+            .bind(&reference)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE product_styles SET image_url = NULL WHERE image_url = ? AND is_active = 0")
+            .bind(&reference)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(true)
+    }
+}
@@ -0,0 +1,185 @@
+use crate::models::money::Money;
+use crate::models::product_import::ProductImportRow;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::price_change_repo::PriceChangeRepository;
+use crate::repositories::product_cost_repo::ProductCostRepository;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use sqlx::SqlitePool;
+
+/// Code, barcode and active flag of a store product, used to validate imports.
+#[derive(Debug, sqlx::FromRow)]
+pub struct ExistingProduct {
+    pub id: i64,
+    pub code: Option<String>,
+    pub barcode: Option<String>,
+}
+
+pub struct ProductImportRepository {
+    pool: SqlitePool,
+}
+
+impl ProductImportRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Every product of the store, deleted ones included: their code and
+    /// barcode are still taken and an import brings them back.
+    pub async fn find_store_products(&self, store_id: i64) -> Result<Vec<ExistingProduct>, sqlx::Error> {
+        sqlx::query_as::<_, ExistingProduct>(
+            "SELECT id, code, barcode FROM products WHERE store_id = ?",
+        )
+        .bind(store_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    /// Creates the missing categories (for this store) and upserts every row
+    /// by (store, code) in one transaction. Stock differences are booked as
+    /// adjustments; price and cost changes go to their history. Returns (created, updated).
+    pub async fn apply(
+        &self,
+        store_id: i64,
+        user_id: i64,
+        rows: &[ProductImportRow],
+        new_categories: &[String],
+    ) -> Result<(usize, usize), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        for name in new_categories {
+            sqlx::query("INSERT INTO categories (name, store_id) VALUES (?, ?)")
+                .bind(name)
+                .bind(store_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+
+        let (mut created, mut updated) = (0, 0);
+        for row in rows {
+            let category_id: Option<i64> = match &row.category {
+                Some(name) => {
+                    // Full path first, then the store's own categories
+                    sqlx::query_scalar(
+                        r#"
+                        SELECT c.id FROM categories c
+                        INNER JOIN category_paths cp ON cp.id = c.id
+                        WHERE (c.store_id IS NULL OR c.store_id = ?1)
+                          AND (cp.path = ?2 COLLATE NOCASE OR c.name = ?2 COLLATE NOCASE)
+                        ORDER BY cp.path = ?2 COLLATE NOCASE DESC, c.store_id IS NULL, c.id
+                        LIMIT 1
+                        "#,
+                    )
+                    .bind(store_id)
+                    .bind(name)
+                    .fetch_optional(&mut *tx)
+                    .await?
+                }
+                None => None,
+            };
+
+            let existing: Option<(i64, i64, Money, Money)> = sqlx::query_as(
+                "SELECT id, stock, CAST(price AS INTEGER), CAST(cost AS INTEGER) FROM products WHERE store_id = ? AND code = ?",
+            )
+            .bind(store_id)
+            .bind(&row.code)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            match existing {
+                Some((id, stock, price, cost)) => {
+                    // Variants keep their price as an override of the style price
+                    sqlx::query(
+                        r#"
+                        UPDATE products SET
+                            name = COALESCE(?1, name),
+                            category_id = COALESCE(?2, category_id),
+                            price = COALESCE(?3, price),
+                            price_override = CASE
+                                WHEN ?3 IS NULL OR style_id IS NULL THEN price_override
+                                WHEN ?3 = (SELECT s.price FROM product_styles s WHERE s.id = products.style_id) THEN NULL
+                                ELSE ?3
+                            END,
+                            cost = COALESCE(?4, cost),
+                            min_stock = COALESCE(?5, min_stock),
+                            unit = COALESCE(?6, unit),
+                            barcode = COALESCE(?7, barcode),
+                            size = COALESCE(?8, size),
+                            color = COALESCE(?9, color),
+                            is_active = 1
+                        WHERE id = ?10
+                        "#,
+                    )
+                    .bind(&row.name)
+                    .bind(category_id)
+                    .bind(row.price)
+                    .bind(row.cost)
+                    .bind(row.min_stock)
+                    .bind(&row.unit)
+                    .bind(&row.barcode)
+                    .bind(&row.size)
+                    .bind(&row.color)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+
+                    let source = MovementSource::manual("import", Some(user_id), "Importación de productos");
+                    if let Some(new_price) = row.price {
+                        PriceChangeRepository::record_change(&mut tx, id, price, new_price, &source).await?;
+                    }
+                    if let Some(new_cost) = row.cost {
+                        ProductCostRepository::record_change(&mut tx, id, cost, new_cost, &source).await?;
+                    }
+
+                    if let Some(target) = row.stock {
+                        if target != stock {
+                            StockMovementRepository::apply(
+                                &mut tx,
+                                id,
+                                target - stock,
+                                &MovementSource::manual("adjustment", Some(user_id), "Importación de productos"),
+                            )
+                            .await?;
+                        }
+                    }
+                    updated += 1;
+                }
+                None => {
+                    let id = sqlx::query(
+                        r#"
+                        INSERT INTO products (code, name, category_id, price, cost, stock, min_stock, unit, store_id, barcode, size, color)
+                        VALUES (?, ?, ?, COALESCE(?, 0), COALESCE(?, 0), 0, COALESCE(?, 5), COALESCE(?, 'Unidades'), ?, ?, ?, ?)
+                        "#,
+                    )
+                    .bind(&row.code)
+                    .bind(&row.name)
+                    .bind(category_id)
+                    .bind(row.price)
+                    .bind(row.cost)
+                    .bind(row.min_stock)
+                    .bind(&row.unit)
+                    .bind(store_id)
+                    .bind(&row.barcode)
+                    .bind(&row.size)
+                    .bind(&row.color)
+                    .execute(&mut *tx)
+                    .await?
+                    .last_insert_rowid();
+
+                    if let Some(stock) = row.stock.filter(|s| *s != 0) {
+                        StockMovementRepository::apply(
+                            &mut tx,
+                            id,
+                            stock,
+                            &MovementSource::manual("adjustment", Some(user_id), "Stock inicial (importación)"),
+                        )
+                        .await?;
+                    }
+                    created += 1;
+                }
+            }
+        }
+
+        tx.commit().await?;
+        Ok((created, updated))
+    }
+}
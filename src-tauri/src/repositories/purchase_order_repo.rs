@@ -1,5 +1,11 @@
-use crate::models::purchase_order::{PurchaseOrder, PurchaseOrderItem};
-use sqlx::SqlitePool;
+use crate::models::purchase_order::{
+    PurchaseOrder, PurchaseOrderItem, PurchaseOrderWithItems, ReceivedItem,
+};
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::product_cost_repo::ProductCostRepository;
+use sqlx::{SqliteConnection, SqlitePool};
 
 pub struct PurchaseOrderRepository {
     pool: SqlitePool,
@@ -17,12 +23,13 @@ impl PurchaseOrderRepository {
         supplier_name: Option<&str>,
         batch_date: &str,
         alias: Option<&str>,
-        total_cost: f64,
+        total_cost: Money,
         created_by: i64,
+        status: &str,
     ) -> Result<PurchaseOrder, sqlx::Error> {
         let result = sqlx::query(
-            "INSERT INTO purchase_orders (uuid, store_id, supplier_name, batch_date, alias, total_cost, created_by)
-             VALUES (?, ?, ?, ?, ?, ?, ?)",
+            "INSERT INTO purchase_orders (uuid, store_id, supplier_name, batch_date, alias, total_cost, created_by, status)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )
         .bind(uuid)
         .bind(store_id)
@@ -31,6 +38,7 @@ impl PurchaseOrderRepository {
         .bind(alias)
         .bind(total_cost)
         .bind(created_by)
+        .bind(status)
         .execute(&self.pool)
         .await?;
 
@@ -46,6 +54,7 @@ impl PurchaseOrderRepository {
             total_cost,
             created_by: Some(created_by),
             created_at: None,
+            status: status.to_string(),
         })
     }
 
@@ -57,8 +66,8 @@ impl PurchaseOrderRepository {
         sku: Option<&str>,
         category_id: Option<i64>,
         quantity: i64,
-        unit_cost: f64,
-        unit_price: f64,
+        unit_cost: Money,
+        unit_price: Money,
     ) -> Result<PurchaseOrderItem, sqlx::Error> {
         let result = sqlx::query(
             "INSERT INTO purchase_order_items (purchase_order_id, product_id, product_name, sku, category_id, quantity, unit_cost, unit_price)
@@ -88,6 +97,7 @@ impl PurchaseOrderRepository {
             unit_cost,
             unit_price,
             created_at: None,
+            price_status: None,
         })
     }
 
@@ -124,4 +134,104 @@ impl PurchaseOrderRepository {
         .fetch_all(&self.pool)
         .await
     }
+
+    /// Marks a draft as received. Returns `false` if it wasn't a draft anymore.
+    /// Receives a draft in one transaction: marks it received, adds each
+    /// line's units averaging their cost, updates the lines, flags the price
+    /// changes and records the expense. Returns `false` if it was no longer
+    /// a draft.
+    pub async fn receive(
+        &self,
+        order: &PurchaseOrderWithItems,
+        items: &[ReceivedItem],
+        payment_method: &str,
+        expense_uuid: &str,
+        source: &MovementSource,
+    ) -> Result<bool, sqlx::Error> {
+        let total_cost: Money = items.iter().map(|i| i.unit_cost.times(i.quantity)).sum();
+        let mut tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            "UPDATE purchase_orders SET status = 'received', total_cost = ? WHERE id = ? AND status = 'draft'",
+        )
+        .bind(total_cost)
+        .bind(order.id)
+        .execute(&mut *tx)
+        .await?;
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        for item in items {
+            if item.quantity > 0 {
+                ProductCostRepository::receive(&mut tx, item.product_id, item.quantity, item.unit_cost, source)
+                    .await?;
+            }
+            Self::update_item(&mut tx, item.item_id, item.product_id, item.quantity, item.unit_cost).await?;
+            if item.quantity > 0 {
+                Self::flag_item_price(&mut tx, item.item_id).await?;
+            }
+        }
+
+        CashRepository::insert_expense_standalone(
+            &mut tx,
+            &order.alias.clone().unwrap_or_else(|| format!("Lote #{}", order.id)),
+            total_cost,
+            payment_method,
+            Some("Mercadería"),
+            order.supplier_name.as_deref(),
+            order.store_id,
+            expense_uuid,
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    async fn update_item(
+        conn: &mut SqliteConnection,
+        id: i64,
+        product_id: i64,
+        quantity: i64,
+        unit_cost: Money,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE purchase_order_items SET product_id = ?, quantity = ?, unit_cost = ? WHERE id = ?")
+            .bind(product_id)
+            .bind(quantity)
+            .bind(unit_cost)
+            .bind(id)
+            .execute(&mut *conn)
+            .await?;
+        Ok(())
+    }
+
+    /// Leaves the line's price waiting for confirmation when it differs from
+    /// the product's current price. Returns whether it was flagged.
+    pub async fn flag_price_change(&self, item_id: i64) -> Result<bool, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::flag_item_price(&mut conn, item_id).await
+    }
+
+    async fn flag_item_price(conn: &mut SqliteConnection, item_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE purchase_order_items SET price_status = 'pending'
+            WHERE id = ? AND product_id IS NOT NULL
+              AND unit_price <> (SELECT p.price FROM products p WHERE p.id = purchase_order_items.product_id)
+            "#,
+        )
+        .bind(item_id)
+        .execute(&mut *conn)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn delete_draft(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query("DELETE FROM purchase_orders WHERE id = ? AND status = 'draft'")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
@@ -0,0 +1,164 @@
+use crate::models::quotation::{CreateQuotationPayload, Quotation, QuotationItem};
+use crate::models::sales::{CreateOrderItemPayload, CreateOrderPayload};
+use crate::models::money::Money;
+use crate::repositories::sales_repo::SalesRepository;
+use sqlx::SqlitePool;
+
+pub struct QuotationRepository {
+    pool: SqlitePool,
+}
+
+impl QuotationRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Inserts the quotation header and its items in a single transaction.
+    /// Stock and cash sessions are never touched.
+    pub async fn create_quotation(
+        &self,
+        uuid: &str,
+        payload: &CreateQuotationPayload,
+        items: &[CreateOrderItemPayload],
+        subtotal: Money,
+        igv: Money,
+        total: Money,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let quotation_id = sqlx::query(
+            r#"
+            INSERT INTO quotations (uuid, store_id, user_id, client_document, client_phone, client_name, valid_until, status, notes, subtotal, igv, total)
+            VALUES (?, ?, ?, ?, ?, ?, ?, 'pending', ?, ?, ?, ?)
+            "#,
+        )
+        .bind(uuid)
+        .bind(payload.store_id)
+        .bind(payload.user_id)
+        .bind(&payload.client_document)
+        .bind(&payload.client_phone)
+        .bind(&payload.client_name)
+        .bind(&payload.valid_until)
+        .bind(&payload.notes)
+        .bind(subtotal)
+        .bind(igv)
+        .bind(total)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for item in items {
+            sqlx::query(
+                r#"
+                INSERT INTO quotation_items (quotation_id, product_id, product_name, unit_price, quantity, subtotal)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(quotation_id)
+            .bind(item.product_id)
+            .bind(&item.product_name)
+            .bind(item.unit_price)
+            .bind(item.quantity)
+            .bind(item.subtotal)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(quotation_id)
+    }
+
+    /// Lists the quotations of a store, optionally filtered by status and by
+    /// a client name / document fragment.
+    pub async fn find_by_store(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+        client: Option<&str>,
+    ) -> Result<Vec<Quotation>, sqlx::Error> {
+        let client_pattern = client.map(|c| format!("%{}%", c));
+        sqlx::query_as::<_, Quotation>(
+            r#"
+            SELECT * FROM quotations
+            WHERE store_id = ?
+              AND (? IS NULL OR status = ?)
+              AND (? IS NULL OR client_name LIKE ? OR client_document LIKE ?)
+            ORDER BY created_at DESC, id DESC
+            "#,
+        )
+        .bind(store_id)
+        .bind(status)
+        .bind(status)
+        .bind(&client_pattern)
+        .bind(&client_pattern)
+        .bind(&client_pattern)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<Quotation>, sqlx::Error> {
+        sqlx::query_as::<_, Quotation>("SELECT * FROM quotations WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_items(&self, quotation_id: i64) -> Result<Vec<QuotationItem>, sqlx::Error> {
+        sqlx::query_as::<_, QuotationItem>(
+            "SELECT * FROM quotation_items WHERE quotation_id = ? ORDER BY id ASC",
+        )
+        .bind(quotation_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn update_status(&self, id: i64, status: &str) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE quotations SET status = ? WHERE id = ?")
+            .bind(status)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    /// Creates the sale and marks the accepted quotation as converted in one
+    /// transaction. Returns `None`, with nothing written, if the quotation is
+    /// no longer accepted (e.g. another terminal converted it first).
+    pub async fn convert(&self, id: i64, order: &CreateOrderPayload) -> Result<Option<i64>, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        // Claim the quotation first: the write locks the database, so a second
+        // conversion waits here and then finds it already converted
+        let claimed = sqlx::query("UPDATE quotations SET status = 'converted' WHERE id = ? AND status = 'accepted'")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?
+            .rows_affected();
+        if claimed == 0 {
+            return Ok(None);
+        }
+
+        let order_id = SalesRepository::insert_order(&mut tx, order).await?;
+
+        sqlx::query("UPDATE quotations SET order_id = ? WHERE id = ?")
+            .bind(order_id)
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+
+        tx.commit().await?;
+        Ok(Some(order_id))
+    }
+
+    /// Flags every pending/accepted quotation whose validity date has passed.
+    pub async fn expire_overdue(&self, store_id: i64) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE quotations SET status = 'expired'
+             WHERE store_id = ? AND status IN ('pending', 'accepted') AND valid_until < date('now', 'localtime')",
+        )
+        .bind(store_id)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+}
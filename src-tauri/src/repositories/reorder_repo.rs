@@ -0,0 +1,70 @@
+use crate::models::reorder::{LowStockProduct, ProductSalesVelocity};
+use crate::repositories::inventory_repo::RESERVED_STOCK_SQL;
+use sqlx::SqlitePool;
+
+pub struct ReorderRepository {
+    pool: SqlitePool,
+}
+
+impl ReorderRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Active products whose sellable stock is at or below `min_stock`,
+    /// most urgent first. Packs have no stock of their own and are left out.
+    pub async fn low_stock(&self, store_id: i64) -> Result<Vec<LowStockProduct>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT p.id AS product_id, p.code AS product_code, p.name AS product_name,
+                   c.name AS category_name,
+                   p.stock - {reserved} AS stock, {reserved} AS reserved_stock,
+                   p.min_stock AS min_stock,
+                   p.min_stock - (p.stock - {reserved}) AS shortage
+            FROM products p
+            LEFT JOIN categories c ON c.id = p.category_id
+            WHERE p.store_id = ? AND p.is_active = 1 AND p.is_kit = 0
+              AND p.min_stock IS NOT NULL
+              AND p.stock - {reserved} <= p.min_stock
+            ORDER BY shortage DESC, p.name ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL
+        );
+        sqlx::query_as::<_, LowStockProduct>(&sql)
+            .bind(store_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Sellable stock and units sold in completed sales of the last `days` days
+    /// (units sold inside packs included) for every active product of the
+    /// store. Packs are left out: they are restocked through their components.
+    pub async fn sales_velocity(
+        &self,
+        store_id: i64,
+        days: i64,
+    ) -> Result<Vec<ProductSalesVelocity>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT p.id AS product_id, p.code AS product_code, p.name AS product_name, p.category_id,
+                   p.stock - {reserved} AS stock, COALESCE(p.min_stock, 0) AS min_stock,
+                   CAST(p.cost AS INTEGER) AS unit_cost, CAST(p.price AS INTEGER) AS unit_price,
+                   COALESCE((
+                       SELECT SUM(oi.quantity) FROM order_product_lines oi
+                       INNER JOIN orders o ON o.id = oi.order_id
+                       WHERE oi.product_id = p.id AND o.status = 'completed'
+                         AND date(o.created_at) > date('now', 'localtime', ?)
+                   ), 0) AS units_sold
+            FROM products p
+            WHERE p.store_id = ? AND p.is_active = 1 AND p.is_kit = 0
+            ORDER BY p.name ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductSalesVelocity>(&sql)
+            .bind(format!("-{} days", days))
+            .bind(store_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
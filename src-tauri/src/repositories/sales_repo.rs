@@ -1,5 +1,34 @@
-use crate::models::sales::{CreateOrderPayload, OrderItemExport, Sale, SaleDetail, SaleItem};
-use sqlx::SqlitePool;
+use crate::models::money::Money;
+use crate::models::sales::{
+    CreateOrderPayload, OrderItemExport, Sale, SaleDetail, SaleItem, SaleItemComponent,
+};
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::inventory_repo::RESERVED_STOCK_SQL;
+use crate::repositories::kit_repo::KitRepository;
+use crate::repositories::loyalty_repo::LoyaltyRepository;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use crate::repositories::stored_value_repo::StoredValueRepository;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+/// SUNAT series for a store: the document letter and the store number in
+/// three digits ("B001", "F012"). Series are four characters, so stores past
+/// #999 have none.
+pub fn document_series(prefix: &str, store_id: i64) -> Option<String> {
+    (1..=999)
+        .contains(&store_id)
+        .then(|| format!("{}{:03}", prefix, store_id))
+}
+
+/// Messages `insert_order` fails with, as `sqlx::Error::InvalidArgument`, when
+/// something checked before the sale changed while it was being booked.
+pub const STORED_VALUE_REJECTED: &str =
+    "La tarjeta o saldo a favor ya no está activo o no tiene saldo suficiente";
+pub const LOYALTY_POINTS_REJECTED: &str = "El cliente ya no tiene puntos suficientes para canjear";
+pub const CASH_SESSION_CLOSED: &str =
+    "La caja ya fue cerrada; actualice la pantalla y abra una nueva caja";
+/// Message `void_order` fails with when the sale's cash session was closed
+/// while the void was being booked.
+pub const VOID_CASH_SESSION_CLOSED: &str = "Solo se pueden anular ventas de una caja abierta";
 
 pub struct SalesRepository {
     pool: SqlitePool,
@@ -14,12 +43,80 @@ impl SalesRepository {
     /// all inside a single SQLite transaction.
     pub async fn create_order(&self, payload: CreateOrderPayload) -> Result<i64, sqlx::Error> {
         let mut tx = self.pool.begin().await?;
+        let order_id = Self::insert_order(&mut tx, &payload).await?;
+        tx.commit().await?;
+        Ok(order_id)
+    }
+
+    /// Same as `create_order` but runs inside the caller's transaction, so other
+    /// documents (layaways, quotations, ...) can be settled atomically with the sale.
+    /// Orders paid with "layaway" were already booked into the cash session one
+    /// deposit at a time, so they don't touch the session balance again.
+    pub async fn insert_order(
+        tx: &mut Transaction<'_, Sqlite>,
+        payload: &CreateOrderPayload,
+    ) -> Result<i64, sqlx::Error> {
+        // 0. Allocate the next series-number for this store and document type
+        let document_type = payload.document_type.clone().unwrap_or_else(|| {
+            // A RUC (11 digits) means the customer asked for a factura
+            match payload.client_document.as_deref() {
+                Some(doc) if doc.len() == 11 => "factura".to_string(),
+                _ => "boleta".to_string(),
+            }
+        });
+        let series_prefix = if document_type == "factura" { "F" } else { "B" };
+
+        sqlx::query(
+            "INSERT INTO document_series (store_id, document_type, series, last_number) VALUES (?, ?, ?, 0)
+             ON CONFLICT(store_id, document_type) DO NOTHING",
+        )
+        .bind(payload.store_id)
+        .bind(&document_type)
+        .bind(document_series(series_prefix, payload.store_id).ok_or_else(|| {
+            sqlx::Error::Protocol(format!(
+                "La tienda #{} no tiene una serie de comprobantes válida",
+                payload.store_id
+            ))
+        })?)
+        .execute(&mut **tx)
+        .await?;
+
+        let (series, number): (String, i64) = sqlx::query_as(
+            "UPDATE document_series SET last_number = last_number + 1
+             WHERE store_id = ? AND document_type = ?
+             RETURNING series, last_number",
+        )
+        .bind(payload.store_id)
+        .bind(&document_type)
+        .fetch_one(&mut **tx)
+        .await?;
+
+        let redeemed = if payload.stored_value_code.is_some() {
+            payload.stored_value_amount.unwrap_or(Money::ZERO)
+        } else {
+            Money::ZERO
+        };
+
+        // 0b. Attach the sale to a customer record and price the redeemed points
+        let loyalty = LoyaltyRepository::settings(tx).await?;
+        let customer_id = LoyaltyRepository::upsert_customer(
+            tx,
+            payload.client_document.as_deref(),
+            payload.client_phone.as_deref(),
+            payload.client_name.as_deref(),
+        )
+        .await?;
+        let points_redeemed = match customer_id {
+            Some(_) if loyalty.enabled => payload.loyalty_points.unwrap_or(0).max(0),
+            _ => 0,
+        };
+        let loyalty_amount = loyalty.value_of(points_redeemed);
 
         // 1. Insert the order header
         let order_id = sqlx::query(
             r#"
-            INSERT INTO orders (user_id, client_document, client_phone, client_name, payment_method, subtotal, igv, total, cash_session_id, store_id)
-            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+            INSERT INTO orders (user_id, client_document, client_phone, client_name, payment_method, subtotal, igv, total, cash_session_id, store_id, document_type, series, number, stored_value_amount, customer_id, loyalty_amount)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(payload.user_id)
@@ -32,43 +129,83 @@ impl SalesRepository {
         .bind(payload.total)
         .bind(payload.cash_session_id)
         .bind(payload.store_id)
-        .execute(&mut *tx)
+        .bind(&document_type)
+        .bind(&series)
+        .bind(number)
+        .bind(redeemed)
+        .bind(customer_id)
+        .bind(loyalty_amount)
+        .execute(&mut **tx)
         .await?
         .last_insert_rowid();
 
-        // 3. Update cash session balance
-        if payload.payment_method == "cash" {
-            sqlx::query("UPDATE cash_sessions SET expected_closing_cash = expected_closing_cash + ? WHERE id = ?")
-                .bind(payload.total)
-                .bind(payload.cash_session_id)
-                .execute(&mut *tx)
+        // 1b. Charge the gift card / store credit part of the payment
+        if let Some(code) = payload.stored_value_code.as_deref() {
+            if redeemed.is_positive() {
+                let charged = StoredValueRepository::redeem(
+                    tx,
+                    code,
+                    redeemed,
+                    order_id,
+                    payload.cash_session_id,
+                    payload.user_id,
+                )
                 .await?;
+                if !charged {
+                    return Err(sqlx::Error::InvalidArgument(STORED_VALUE_REJECTED.to_string()));
+                }
+            }
+        }
+
+        // 1c. Spend the customer's points
+        if let (Some(customer_id), true) = (customer_id, points_redeemed > 0) {
+            if !LoyaltyRepository::redeem(tx, customer_id, order_id, points_redeemed).await? {
+                return Err(sqlx::Error::InvalidArgument(LOYALTY_POINTS_REJECTED.to_string()));
+            }
+        }
+
+        // 3. Update cash session balance with the part not paid by stored value or points
+        let booked = payload.total - redeemed - loyalty_amount;
+        if payload.payment_method == "layaway" || !booked.is_positive() {
+            // Layaway deposits were booked when they were received
         } else {
-            sqlx::query("UPDATE cash_sessions SET expected_closing_virtual = expected_closing_virtual + ? WHERE id = ?")
-                .bind(payload.total)
-                .bind(payload.cash_session_id)
-                .execute(&mut *tx)
-                .await?;
+            let column = if payload.payment_method == "cash" {
+                "expected_closing_cash"
+            } else {
+                "expected_closing_virtual"
+            };
+            let updated = sqlx::query(&format!(
+                "UPDATE cash_sessions SET {0} = {0} + ? WHERE id = ? AND status = 'open'",
+                column
+            ))
+            .bind(booked)
+            .bind(payload.cash_session_id)
+            .execute(&mut **tx)
+            .await?;
+            if updated.rows_affected() == 0 {
+                return Err(sqlx::Error::InvalidArgument(CASH_SESSION_CLOSED.to_string()));
+            }
         }
 
-        // 2. Insert each item and decrement stock
+        // 2. Insert each item and decrement stock; a pack takes the stock of
+        // its components instead of its own
+        let source = MovementSource::document("sale", "order", order_id, Some(payload.user_id));
         for item in &payload.items {
-            // Validate stock before decrementing
-            let current_stock: i64 =
-                sqlx::query_scalar("SELECT stock FROM products WHERE id = ? AND is_active = 1")
-                    .bind(item.product_id)
-                    .fetch_one(&mut *tx)
-                    .await?;
-
-            if current_stock < item.quantity {
-                return Err(sqlx::Error::RowNotFound);
+            let components = KitRepository::components_for_sale(tx, item.product_id).await?;
+            if components.is_empty() {
+                Self::take_stock(tx, item.product_id, item.quantity, &source).await?;
+            } else {
+                for component in &components {
+                    Self::take_stock(tx, component.product_id, component.quantity * item.quantity, &source)
+                        .await?;
+                }
             }
 
-            // Insert order item
-            sqlx::query(
+            // Insert order item with the product's current cost (COGS)
+            let order_item_id = sqlx::query(
                 r#"
-                INSERT INTO order_items (order_id, product_id, product_name, unit_price, quantity, subtotal)
-                VALUES (?, ?, ?, ?, ?, ?)
+                INSERT INTO order_items (order_id, product_id, product_name, unit_price, quantity, subtotal, price_list_id, unit_cost)
+                VALUES (?, ?, ?, ?, ?, ?, ?, (SELECT CAST(cost AS INTEGER) FROM products WHERE id = ?))
                 "#,
             )
             .bind(order_id)
@@ -77,21 +214,154 @@ impl SalesRepository {
             .bind(item.unit_price)
             .bind(item.quantity)
             .bind(item.subtotal)
-            .execute(&mut *tx)
-            .await?;
+            .bind(item.price_list_id)
+            .bind(item.product_id)
+            .execute(&mut **tx)
+            .await?
+            .last_insert_rowid();
 
-            // Decrement stock
-            sqlx::query("UPDATE products SET stock = stock - ? WHERE id = ?")
-                .bind(item.quantity)
-                .bind(item.product_id)
-                .execute(&mut *tx)
+            if !components.is_empty() {
+                let pack_cost = KitRepository::record_sale_components(
+                    tx,
+                    order_item_id,
+                    order_id,
+                    item.quantity,
+                    item.subtotal,
+                    &components,
+                )
+                .await?;
+                sqlx::query("UPDATE order_items SET unit_cost = ? WHERE id = ?")
+                    .bind(pack_cost)
+                    .bind(order_item_id)
+                    .execute(&mut **tx)
+                    .await?;
+            }
+        }
+
+        // 4. Credit the points earned on the part not paid with points
+        if let (Some(customer_id), true) = (customer_id, loyalty.enabled) {
+            let paid_fraction = (payload.total - loyalty_amount).ratio(payload.total).max(0.0);
+            LoyaltyRepository::earn_for_order(tx, order_id, customer_id, &loyalty, paid_fraction)
                 .await?;
         }
 
-        tx.commit().await?;
         Ok(order_id)
     }
 
+    /// Takes `quantity` units of the product for a sale. Fails with
+    /// `RowNotFound` if the product is inactive or not enough units are left.
+    async fn take_stock(
+        tx: &mut Transaction<'_, Sqlite>,
+        product_id: i64,
+        quantity: i64,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        // Decrement first: the write locks the database, so the check below
+        // sees every concurrent sale. Units held by layaways are not sellable.
+        StockMovementRepository::apply(tx, product_id, -quantity, source).await?;
+
+        let remaining: Option<i64> = sqlx::query_scalar(&format!(
+            "SELECT p.stock - {} FROM products p WHERE p.id = ? AND p.is_active = 1",
+            RESERVED_STOCK_SQL
+        ))
+        .bind(product_id)
+        .fetch_optional(&mut **tx)
+        .await?;
+
+        if remaining.map(|r| r < 0).unwrap_or(true) {
+            return Err(sqlx::Error::RowNotFound);
+        }
+        Ok(())
+    }
+
+    /// Voids a completed sale: puts the stock back, takes the money out of the
+    /// cash session, refunds gift card / store credit redemptions and reverses
+    /// the loyalty points. Fails with `RowNotFound` if the sale is not completed.
+    pub async fn void_order(
+        &self,
+        order_id: i64,
+        user_id: i64,
+        reason: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let (cash_session_id, payment_method, total, stored_value_amount, loyalty_amount): (
+            i64,
+            String,
+            Money,
+            Money,
+            Money,
+        ) = sqlx::query_as(
+            r#"
+            SELECT cash_session_id, payment_method, CAST(total AS INTEGER), CAST(stored_value_amount AS INTEGER), CAST(loyalty_amount AS INTEGER)
+            FROM orders WHERE id = ? AND status = 'completed'
+            "#,
+        )
+        .bind(order_id)
+        .fetch_one(&mut *tx)
+        .await?;
+
+        // Pack lines give the units back to their components
+        let items: Vec<(i64, i64)> = sqlx::query_as(
+            r#"
+            SELECT COALESCE(oc.product_id, oi.product_id), COALESCE(oc.quantity, oi.quantity)
+            FROM order_items oi
+            LEFT JOIN order_item_components oc ON oc.order_item_id = oi.id
+            WHERE oi.order_id = ?
+            "#,
+        )
+        .bind(order_id)
+        .fetch_all(&mut *tx)
+        .await?;
+
+        let source = MovementSource::document("return", "order", order_id, Some(user_id));
+        for (product_id, quantity) in items {
+            StockMovementRepository::apply(&mut tx, product_id, quantity, &source).await?;
+        }
+
+        let booked = total - stored_value_amount - loyalty_amount;
+        if payment_method != "layaway" && booked.is_positive() {
+            let column = if payment_method == "cash" {
+                "expected_closing_cash"
+            } else {
+                "expected_closing_virtual"
+            };
+            let updated = sqlx::query(&format!(
+                "UPDATE cash_sessions SET {0} = {0} - ? WHERE id = ? AND status = 'open'",
+                column
+            ))
+            .bind(booked)
+            .bind(cash_session_id)
+            .execute(&mut *tx)
+            .await?;
+            if updated.rows_affected() == 0 {
+                return Err(sqlx::Error::InvalidArgument(VOID_CASH_SESSION_CLOSED.to_string()));
+            }
+        }
+
+        StoredValueRepository::refund_order(&mut tx, order_id, user_id).await?;
+        LoyaltyRepository::reverse_order(&mut tx, order_id, 1.0).await?;
+
+        sqlx::query(
+            "UPDATE orders SET status = 'voided', voided_at = datetime('now', 'localtime'), voided_by = ?, void_reason = ? WHERE id = ?",
+        )
+        .bind(user_id)
+        .bind(reason)
+        .bind(order_id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_cash_session_id(&self, order_id: i64) -> Result<i64, sqlx::Error> {
+        sqlx::query_scalar("SELECT cash_session_id FROM orders WHERE id = ?")
+            .bind(order_id)
+            .fetch_one(&self.pool)
+            .await
+    }
+
     /// Returns all sales ordered by date descending, joining with users for the seller name.
     pub async fn get_sales(&self, store_id: i64) -> Result<Vec<Sale>, sqlx::Error> {
         sqlx::query_as::<_, Sale>(
@@ -104,10 +374,17 @@ impl SalesRepository {
                 o.client_phone,
                 o.client_name,
                 o.payment_method,
-                CAST(o.subtotal AS REAL) AS subtotal,
-                CAST(o.igv AS REAL) AS igv,
-                CAST(o.total AS REAL) AS total,
+                CAST(o.subtotal AS INTEGER) AS subtotal,
+                CAST(o.igv AS INTEGER) AS igv,
+                CAST(o.total AS INTEGER) AS total,
                 o.store_id,
+                o.document_type,
+                o.series,
+                o.number,
+                CAST(o.stored_value_amount AS INTEGER) AS stored_value_amount,
+                o.customer_id,
+                CAST(o.loyalty_amount AS INTEGER) AS loyalty_amount,
+                o.status,
                 o.created_at
             FROM orders o
             LEFT JOIN users u ON u.id = o.user_id
@@ -132,9 +409,17 @@ impl SalesRepository {
                 o.client_phone,
                 o.client_name,
                 o.payment_method,
-                CAST(o.subtotal AS REAL) AS subtotal,
-                CAST(o.igv AS REAL) AS igv,
-                CAST(o.total AS REAL) AS total,
+                CAST(o.subtotal AS INTEGER) AS subtotal,
+                CAST(o.igv AS INTEGER) AS igv,
+                CAST(o.total AS INTEGER) AS total,
+                o.store_id,
+                o.document_type,
+                o.series,
+                o.number,
+                CAST(o.stored_value_amount AS INTEGER) AS stored_value_amount,
+                o.customer_id,
+                CAST(o.loyalty_amount AS INTEGER) AS loyalty_amount,
+                o.status,
                 o.created_at
             FROM orders o
             LEFT JOIN users u ON u.id = o.user_id
@@ -151,13 +436,29 @@ impl SalesRepository {
                 let items = sqlx::query_as::<_, SaleItem>(
                     r#"
                     SELECT
-                        id,
-                        product_id,
-                        product_name,
-                        CAST(unit_price AS REAL) AS unit_price,
-                        quantity,
-                        CAST(subtotal AS REAL) AS subtotal
-                    FROM order_items
+                        oi.id,
+                        oi.product_id,
+                        oi.product_name,
+                        CAST(oi.unit_price AS INTEGER) AS unit_price,
+                        oi.quantity,
+                        CAST(oi.subtotal AS INTEGER) AS subtotal,
+                        oi.price_list_id,
+                        pl.name AS price_list_name
+                    FROM order_items oi
+                    LEFT JOIN price_lists pl ON pl.id = oi.price_list_id
+                    WHERE oi.order_id = ?
+                    ORDER BY oi.id ASC
+                    "#,
+                )
+                .bind(sale_id)
+                .fetch_all(&self.pool)
+                .await?;
+
+                let components = sqlx::query_as::<_, SaleItemComponent>(
+                    r#"
+                    SELECT order_item_id, product_id, product_name, quantity,
+                           COALESCE(unit_cost, 0) AS unit_cost, subtotal
+                    FROM order_item_components
                     WHERE order_id = ?
                     ORDER BY id ASC
                     "#,
@@ -166,7 +467,7 @@ impl SalesRepository {
                 .fetch_all(&self.pool)
                 .await?;
 
-                Ok(Some(SaleDetail { sale: s, items }))
+                Ok(Some(SaleDetail { sale: s, items, components }))
             }
         }
     }
@@ -182,9 +483,10 @@ impl SalesRepository {
                 o.client_document,
                 o.payment_method,
                 oi.product_name,
-                CAST(oi.unit_price AS REAL) AS unit_price,
+                CAST(oi.unit_price AS INTEGER) AS unit_price,
                 oi.quantity,
-                CAST(oi.subtotal AS REAL) AS subtotal,
+                CAST(oi.subtotal AS INTEGER) AS subtotal,
+                CAST(COALESCE(oi.unit_cost, 0) AS INTEGER) AS unit_cost,
                 o.store_id
             FROM order_items oi
             INNER JOIN orders o ON o.id = oi.order_id
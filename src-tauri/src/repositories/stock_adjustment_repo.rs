@@ -0,0 +1,152 @@
+use crate::models::stock_adjustment::{CreateStockAdjustmentPayload, StockAdjustment};
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::inventory_repo::RESERVED_STOCK_SQL;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use sqlx::{SqliteConnection, SqlitePool};
+
+const ADJUSTMENT_SELECT: &str = r#"
+    SELECT a.id, a.store_id, a.product_id, p.code AS product_code, p.name AS product_name,
+           a.reason_code, a.delta, a.note, a.status,
+           a.requested_by, ru.username AS requested_by_name,
+           a.reviewed_by, vu.username AS reviewed_by_name, a.reviewed_at, a.created_at
+    FROM stock_adjustments a
+    LEFT JOIN products p ON p.id = a.product_id
+    LEFT JOIN users ru ON ru.id = a.requested_by
+    LEFT JOIN users vu ON vu.id = a.reviewed_by
+"#;
+
+pub struct StockAdjustmentRepository {
+    pool: SqlitePool,
+}
+
+impl StockAdjustmentRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Registers the adjustment and, when `approved_by` is given, applies it in
+    /// the same transaction. Fails with `RowNotFound` if the sellable stock
+    /// would go negative.
+    pub async fn create(
+        &self,
+        payload: &CreateStockAdjustmentPayload,
+        approved_by: Option<i64>,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query(
+            r#"
+            INSERT INTO stock_adjustments (store_id, product_id, reason_code, delta, note, requested_by)
+            VALUES (?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(payload.store_id)
+        .bind(payload.product_id)
+        .bind(&payload.reason_code)
+        .bind(payload.delta)
+        .bind(&payload.note)
+        .bind(payload.user_id)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        if let Some(manager_id) = approved_by {
+            Self::apply(&mut tx, id, manager_id).await?;
+        }
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Applies a pending adjustment. Returns `false` if it was no longer pending.
+    pub async fn approve(&self, id: i64, manager_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let applied = Self::apply(&mut tx, id, manager_id).await?;
+        tx.commit().await?;
+        Ok(applied)
+    }
+
+    /// Claims the pending row first, so two managers approving at once can't
+    /// post it twice, then moves the stock.
+    async fn apply(conn: &mut SqliteConnection, id: i64, manager_id: i64) -> Result<bool, sqlx::Error> {
+        let claimed: Option<(i64, i64, String, Option<String>)> = sqlx::query_as(
+            r#"
+            UPDATE stock_adjustments
+            SET status = 'applied', reviewed_by = ?, reviewed_at = datetime('now', 'localtime')
+            WHERE id = ? AND status = 'pending'
+            RETURNING product_id, delta, reason_code, note
+            "#,
+        )
+        .bind(manager_id)
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+
+        let (product_id, delta, reason_code, note) = match claimed {
+            Some(row) => row,
+            None => return Ok(false),
+        };
+
+        let source = MovementSource {
+            note: Some(match note {
+                Some(note) => format!("{}: {}", reason_code, note),
+                None => reason_code,
+            }),
+            ..MovementSource::document("adjustment", "stock_adjustment", id, Some(manager_id))
+        };
+        StockMovementRepository::apply(conn, product_id, delta, &source).await?;
+
+        // Units held by layaways can't be written off either
+        if delta < 0 {
+            let sellable: i64 = sqlx::query_scalar(&format!(
+                "SELECT p.stock - {} FROM products p WHERE p.id = ?",
+                RESERVED_STOCK_SQL
+            ))
+            .bind(product_id)
+            .fetch_one(&mut *conn)
+            .await?;
+            if sellable < 0 {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+        Ok(true)
+    }
+
+    pub async fn reject(&self, id: i64, manager_id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE stock_adjustments
+            SET status = 'rejected', reviewed_by = ?, reviewed_at = datetime('now', 'localtime')
+            WHERE id = ? AND status = 'pending'
+            "#,
+        )
+        .bind(manager_id)
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<StockAdjustment>, sqlx::Error> {
+        sqlx::query_as::<_, StockAdjustment>(&format!("{} WHERE a.id = ?", ADJUSTMENT_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_by_store(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<StockAdjustment>, sqlx::Error> {
+        sqlx::query_as::<_, StockAdjustment>(&format!(
+            "{} WHERE a.store_id = ? AND (? IS NULL OR a.status = ?) ORDER BY a.created_at DESC, a.id DESC",
+            ADJUSTMENT_SELECT
+        ))
+        .bind(store_id)
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,93 @@
+use crate::models::stock_movement::{MovementSource, StockMovement};
+use sqlx::{SqliteConnection, SqlitePool};
+
+/// Message the stock, cost and price history writers fail with, as
+/// `sqlx::Error::InvalidArgument`, for a product that belongs to no store:
+/// its lines cannot be filed under any store's history.
+pub const PRODUCT_WITHOUT_STORE: &str = "El producto no está asignado a ninguna tienda";
+
+pub struct StockMovementRepository {
+    pool: SqlitePool,
+}
+
+impl StockMovementRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Adds `delta` to the product's stock and writes the kardex line with the
+    /// resulting balance. Call it inside the transaction of the document that
+    /// moves the stock. Returns the new balance.
+    pub async fn apply(
+        conn: &mut SqliteConnection,
+        product_id: i64,
+        delta: i64,
+        source: &MovementSource,
+    ) -> Result<i64, sqlx::Error> {
+        let (balance, store_id): (i64, Option<i64>) = sqlx::query_as(
+            "UPDATE products SET stock = stock + ? WHERE id = ? RETURNING stock, store_id",
+        )
+        .bind(delta)
+        .bind(product_id)
+        .fetch_one(&mut *conn)
+        .await?;
+        let store_id = store_id
+            .ok_or_else(|| sqlx::Error::InvalidArgument(PRODUCT_WITHOUT_STORE.to_string()))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO stock_movements (product_id, store_id, delta, balance, reason, document_type, document_id, user_id, note)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(product_id)
+        .bind(store_id)
+        .bind(delta)
+        .bind(balance)
+        .bind(source.reason)
+        .bind(source.document_type)
+        .bind(source.document_id)
+        .bind(source.user_id)
+        .bind(&source.note)
+        .execute(&mut *conn)
+        .await?;
+
+        Ok(balance)
+    }
+
+    /// Balance at the end of the day before `date_from` (0 if the product had no movements).
+    pub async fn balance_before(&self, product_id: i64, date_from: &str) -> Result<i64, sqlx::Error> {
+        let balance: Option<i64> = sqlx::query_scalar(
+            "SELECT balance FROM stock_movements WHERE product_id = ? AND date(created_at) < ? ORDER BY id DESC LIMIT 1",
+        )
+        .bind(product_id)
+        .bind(date_from)
+        .fetch_optional(&self.pool)
+        .await?;
+        Ok(balance.unwrap_or(0))
+    }
+
+    pub async fn find_by_product(
+        &self,
+        product_id: i64,
+        date_from: &str,
+        date_to: &str,
+    ) -> Result<Vec<StockMovement>, sqlx::Error> {
+        sqlx::query_as::<_, StockMovement>(
+            r#"
+            SELECT m.id, m.product_id, m.store_id, m.delta, m.balance, m.reason,
+                   m.document_type, m.document_id, m.user_id, u.username AS user_name,
+                   m.note, m.created_at
+            FROM stock_movements m
+            LEFT JOIN users u ON u.id = m.user_id
+            WHERE m.product_id = ? AND date(m.created_at) BETWEEN ? AND ?
+            ORDER BY m.id ASC
+            "#,
+        )
+        .bind(product_id)
+        .bind(date_from)
+        .bind(date_to)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,291 @@
+use crate::models::stock_movement::MovementSource;
+use crate::models::stock_transfer::{CreateStockTransferPayload, StockTransfer, StockTransferItem};
+use crate::repositories::inventory_repo::RESERVED_STOCK_SQL;
+use crate::repositories::product_cost_repo::ProductCostRepository;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use sqlx::{SqliteConnection, SqlitePool};
+use std::collections::HashMap;
+
+const TRANSFER_SELECT: &str = r#"
+    SELECT t.id, t.origin_store_id, os.name AS origin_store_name,
+           t.destination_store_id, ds.name AS destination_store_name,
+           t.status, t.notes, t.created_by, t.dispatched_by, t.dispatched_at,
+           t.received_by, t.received_at, t.created_at
+    FROM stock_transfers t
+    LEFT JOIN stores os ON os.id = t.origin_store_id
+    LEFT JOIN stores ds ON ds.id = t.destination_store_id
+"#;
+
+/// Product line validated by the service: (product_id, code, name, quantity).
+pub type TransferLine = (i64, String, String, i64);
+
+pub struct StockTransferRepository {
+    pool: SqlitePool,
+}
+
+impl StockTransferRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    pub async fn create(
+        &self,
+        payload: &CreateStockTransferPayload,
+        lines: &[TransferLine],
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let id = sqlx::query(
+            "INSERT INTO stock_transfers (origin_store_id, destination_store_id, notes, created_by) VALUES (?, ?, ?, ?)",
+        )
+        .bind(payload.origin_store_id)
+        .bind(payload.destination_store_id)
+        .bind(&payload.notes)
+        .bind(payload.user_id)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        for (product_id, code, name, quantity) in lines {
+            sqlx::query(
+                r#"
+                INSERT INTO stock_transfer_items (transfer_id, product_id, product_code, product_name, quantity)
+                VALUES (?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(id)
+            .bind(product_id)
+            .bind(code)
+            .bind(name)
+            .bind(quantity)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(id)
+    }
+
+    /// Takes the units out of the origin store. Returns `false` if the transfer
+    /// was no longer a draft; fails with `RowNotFound` when an item lacks
+    /// available stock (units held by layaways don't count).
+    pub async fn dispatch(&self, id: i64, user_id: i64) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let claimed = sqlx::query(
+            r#"
+            UPDATE stock_transfers
+            SET status = 'dispatched', dispatched_by = ?, dispatched_at = datetime('now', 'localtime')
+            WHERE id = ? AND status = 'draft'
+            "#,
+        )
+        .bind(user_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+        if claimed.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        let source = MovementSource::document("transfer", "stock_transfer", id, Some(user_id));
+        for item in Self::items(&mut tx, id).await? {
+            // Decrement first so concurrent sales see the reduced stock
+            StockMovementRepository::apply(&mut tx, item.product_id, -item.quantity, &source).await?;
+
+            let remaining: Option<i64> = sqlx::query_scalar(&format!(
+                "SELECT p.stock - {} FROM products p WHERE p.id = ? AND p.is_active = 1",
+                RESERVED_STOCK_SQL
+            ))
+            .bind(item.product_id)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            if remaining.map(|r| r < 0).unwrap_or(true) {
+                return Err(sqlx::Error::RowNotFound);
+            }
+        }
+
+        // The units travel at the origin's cost
+        sqlx::query(
+            r#"
+            UPDATE stock_transfer_items
+            SET unit_cost = (SELECT CAST(p.cost AS INTEGER) FROM products p WHERE p.id = stock_transfer_items.product_id)
+            WHERE transfer_id = ?
+            "#,
+        )
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Books the received units in the destination store, matching each item by
+    /// code and creating the product there (with the origin's data) when it
+    /// doesn't exist yet. Their cost is averaged into the destination's. `received` maps item id to the units counted; missing
+    /// items are taken as complete. Returns `false` if it wasn't dispatched.
+    pub async fn receive(
+        &self,
+        id: i64,
+        user_id: i64,
+        received: &HashMap<i64, i64>,
+        status: &str,
+    ) -> Result<bool, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let destination: Option<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE stock_transfers
+            SET status = ?, received_by = ?, received_at = datetime('now', 'localtime')
+            WHERE id = ? AND status = 'dispatched'
+            RETURNING destination_store_id
+            "#,
+        )
+        .bind(status)
+        .bind(user_id)
+        .bind(id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let destination = match destination {
+            Some(store_id) => store_id,
+            None => return Ok(false),
+        };
+
+        let source = MovementSource::document("transfer", "stock_transfer", id, Some(user_id));
+        for item in Self::items(&mut tx, id).await? {
+            let quantity = received.get(&item.id).copied().unwrap_or(item.quantity);
+            let product_id =
+                Self::destination_product(&mut tx, item.product_id, &item.product_code, destination).await?;
+
+            if quantity > 0 {
+                // Transfers dispatched before costs were recorded use the origin's current one
+                let unit_cost = match item.unit_cost {
+                    Some(cost) => cost,
+                    None => {
+                        sqlx::query_scalar("SELECT CAST(cost AS INTEGER) FROM products WHERE id = ?")
+                            .bind(item.product_id)
+                            .fetch_one(&mut *tx)
+                            .await?
+                    }
+                };
+                ProductCostRepository::receive(&mut tx, product_id, quantity, unit_cost, &source).await?;
+            }
+
+            sqlx::query(
+                "UPDATE stock_transfer_items SET received_quantity = ?, destination_product_id = ? WHERE id = ?",
+            )
+            .bind(quantity)
+            .bind(product_id)
+            .bind(item.id)
+            .execute(&mut *tx)
+            .await?;
+        }
+
+        tx.commit().await?;
+        Ok(true)
+    }
+
+    /// Product with `code` in the destination store, brought back if it was
+    /// deleted there, or created from the origin product (stock 0) if the
+    /// store doesn't carry it yet.
+    async fn destination_product(
+        conn: &mut SqliteConnection,
+        origin_product_id: i64,
+        code: &str,
+        store_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        // The code is unique per store among deleted products too
+        let existing: Option<(i64, bool)> =
+            sqlx::query_as("SELECT id, is_active FROM products WHERE code = ? AND store_id = ?")
+                .bind(code)
+                .bind(store_id)
+                .fetch_optional(&mut *conn)
+                .await?;
+        if let Some((id, is_active)) = existing {
+            if !is_active {
+                sqlx::query("UPDATE products SET is_active = 1 WHERE id = ?")
+                    .bind(id)
+                    .execute(&mut *conn)
+                    .await?;
+            }
+            return Ok(id);
+        }
+
+        // Same catalog item as the origin: the code alone may point to another
+        // version of it. The barcode is unique per store: keep it only if it's
+        // free there
+        let id = sqlx::query(
+            r#"
+            INSERT INTO products (code, name, category_id, price, cost, stock, min_stock, unit, image_url, store_id, size, color, barcode, catalog_id, local_price)
+            SELECT p.code, p.name, p.category_id, p.price, p.cost, 0, p.min_stock, p.unit, p.image_url, ?, p.size, p.color,
+                   CASE WHEN EXISTS (SELECT 1 FROM products d WHERE d.store_id = ? AND d.barcode = p.barcode)
+                        THEN NULL ELSE p.barcode END,
+                   p.catalog_id, p.local_price
+            FROM products p
+            WHERE p.id = ?
+            "#,
+        )
+        .bind(store_id)
+        .bind(store_id)
+        .bind(origin_product_id)
+        .execute(&mut *conn)
+        .await?
+        .last_insert_rowid();
+        Ok(id)
+    }
+
+    pub async fn cancel(&self, id: i64) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE stock_transfers SET status = 'cancelled' WHERE id = ? AND status = 'draft'",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<StockTransfer>, sqlx::Error> {
+        sqlx::query_as::<_, StockTransfer>(&format!("{} WHERE t.id = ?", TRANSFER_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    /// Transfers leaving or arriving at the store.
+    pub async fn find_by_store(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<StockTransfer>, sqlx::Error> {
+        sqlx::query_as::<_, StockTransfer>(&format!(
+            r#"{} WHERE (t.origin_store_id = ? OR t.destination_store_id = ?)
+                  AND (? IS NULL OR t.status = ?)
+                ORDER BY t.created_at DESC, t.id DESC"#,
+            TRANSFER_SELECT
+        ))
+        .bind(store_id)
+        .bind(store_id)
+        .bind(status)
+        .bind(status)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_items(&self, transfer_id: i64) -> Result<Vec<StockTransferItem>, sqlx::Error> {
+        let mut conn = self.pool.acquire().await?;
+        Self::items(&mut conn, transfer_id).await
+    }
+
+    async fn items(
+        conn: &mut SqliteConnection,
+        transfer_id: i64,
+    ) -> Result<Vec<StockTransferItem>, sqlx::Error> {
+        sqlx::query_as::<_, StockTransferItem>(
+            "SELECT * FROM stock_transfer_items WHERE transfer_id = ? ORDER BY id ASC",
+        )
+        .bind(transfer_id)
+        .fetch_all(&mut *conn)
+        .await
+    }
+}
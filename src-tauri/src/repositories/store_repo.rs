@@ -38,6 +38,8 @@ impl StoreRepository {
             code: code.map(|s| s.to_string()),
             is_active: true,
             created_at: None, // DB handles default
+            ruc: None,
+            logo_path: None,
         })
     }
 
@@ -58,6 +60,28 @@ impl StoreRepository {
         Ok(())
     }
 
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<Store>, sqlx::Error> {
+        sqlx::query_as::<_, Store>("SELECT * FROM stores WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn update_fiscal_info(
+        &self,
+        id: i64,
+        ruc: Option<&str>,
+        logo_path: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query("UPDATE stores SET ruc = ?, logo_path = ? WHERE id = ?")
+            .bind(ruc)
+            .bind(logo_path)
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
     pub async fn soft_delete(&self, id: i64) -> Result<(), sqlx::Error> {
         sqlx::query("UPDATE stores SET is_active = 0 WHERE id = ?")
             .bind(id)
@@ -0,0 +1,291 @@
+use crate::models::money::Money;
+use crate::models::stored_value::{
+    IssueGiftCardPayload, IssueStoreCreditPayload, StoredValueAccount, StoredValueEntry,
+};
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::loyalty_repo::LoyaltyRepository;
+use sqlx::{Sqlite, SqlitePool, Transaction};
+
+pub struct StoredValueRepository {
+    pool: SqlitePool,
+}
+
+impl StoredValueRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    /// Creates the card with its opening ledger entry and books the money
+    /// received into the cash session as other income.
+    pub async fn issue_gift_card(
+        &self,
+        uuid: &str,
+        code: &str,
+        payload: &IssueGiftCardPayload,
+        cash_session_id: i64,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let account_id = sqlx::query(
+            r#"
+            INSERT INTO stored_value_accounts (uuid, code, kind, store_id, customer_document, customer_name, balance, status, expires_at, created_by)
+            VALUES (?, ?, 'gift_card', ?, ?, ?, ?, 'active', ?, ?)
+            "#,
+        )
+        .bind(uuid)
+        .bind(code)
+        .bind(payload.store_id)
+        .bind(&payload.customer_document)
+        .bind(&payload.customer_name)
+        .bind(payload.amount)
+        .bind(&payload.expires_at)
+        .bind(payload.user_id)
+        .execute(&mut *tx)
+        .await?
+        .last_insert_rowid();
+
+        Self::insert_entry(
+            &mut tx,
+            account_id,
+            "issue",
+            payload.amount,
+            None,
+            Some(cash_session_id),
+            payload.user_id,
+            None,
+        )
+        .await?;
+
+        CashRepository::insert_other_income(
+            &mut tx,
+            cash_session_id,
+            &format!("Venta tarjeta de regalo {}", code),
+            payload.amount,
+            &payload.payment_method,
+        )
+        .await?;
+
+        tx.commit().await?;
+        Ok(account_id)
+    }
+
+    /// Credits a customer's store-credit account, creating it on first use.
+    /// No cash moves: the credit replaces a cash refund.
+    pub async fn issue_store_credit(
+        &self,
+        uuid: &str,
+        code: &str,
+        payload: &IssueStoreCreditPayload,
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+
+        let existing: Option<i64> = sqlx::query_scalar(
+            "SELECT id FROM stored_value_accounts WHERE kind = 'store_credit' AND customer_document = ? AND status = 'active'",
+        )
+        .bind(&payload.customer_document)
+        .fetch_optional(&mut *tx)
+        .await?;
+
+        let (account_id, entry_type) = match existing {
+            Some(id) => {
+                sqlx::query("UPDATE stored_value_accounts SET balance = balance + ? WHERE id = ?")
+                    .bind(payload.amount)
+                    .bind(id)
+                    .execute(&mut *tx)
+                    .await?;
+                (id, "topup")
+            }
+            None => {
+                let id = sqlx::query(
+                    r#"
+                    INSERT INTO stored_value_accounts (uuid, code, kind, store_id, customer_document, customer_name, balance, status, created_by)
+                    VALUES (?, ?, 'store_credit', ?, ?, ?, ?, 'active', ?)
+                    "#,
+                )
+                .bind(uuid)
+                .bind(code)
+                .bind(payload.store_id)
+                .bind(&payload.customer_document)
+                .bind(&payload.customer_name)
+                .bind(payload.amount)
+                .bind(payload.user_id)
+                .execute(&mut *tx)
+                .await?
+                .last_insert_rowid();
+                (id, "issue")
+            }
+        };
+
+        Self::insert_entry(
+            &mut tx,
+            account_id,
+            entry_type,
+            payload.amount,
+            payload.order_id,
+            None,
+            payload.user_id,
+            payload.note.as_deref(),
+        )
+        .await?;
+
+        // A refund against a sale takes back the points earned on that share of it
+        if let Some(order_id) = payload.order_id {
+            let total: Option<Money> =
+                sqlx::query_scalar("SELECT CAST(total AS INTEGER) FROM orders WHERE id = ?")
+                    .bind(order_id)
+                    .fetch_optional(&mut *tx)
+                    .await?;
+            if let Some(total) = total.filter(|t| t.is_positive()) {
+                LoyaltyRepository::reverse_order(&mut tx, order_id, payload.amount.ratio(total)).await?;
+            }
+        }
+
+        tx.commit().await?;
+        Ok(account_id)
+    }
+
+    /// Gives back every stored-value redemption made by a voided order.
+    pub async fn refund_order(
+        tx: &mut Transaction<'_, Sqlite>,
+        order_id: i64,
+        user_id: i64,
+    ) -> Result<(), sqlx::Error> {
+        let redemptions: Vec<(i64, Money)> = sqlx::query_as(
+            "SELECT account_id, amount FROM stored_value_ledger WHERE order_id = ? AND entry_type = 'redeem'",
+        )
+        .bind(order_id)
+        .fetch_all(&mut **tx)
+        .await?;
+
+        for (account_id, amount) in redemptions {
+            sqlx::query("UPDATE stored_value_accounts SET balance = balance - ? WHERE id = ?")
+                .bind(amount)
+                .bind(account_id)
+                .execute(&mut **tx)
+                .await?;
+            Self::insert_entry(tx, account_id, "refund", -amount, Some(order_id), None, user_id, None)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Deducts `amount` from the account inside the sale transaction.
+    /// Returns `false`, charging nothing, if the account is not active, has
+    /// expired or lacks balance.
+    pub async fn redeem(
+        tx: &mut Transaction<'_, Sqlite>,
+        code: &str,
+        amount: Money,
+        order_id: i64,
+        cash_session_id: i64,
+        user_id: i64,
+    ) -> Result<bool, sqlx::Error> {
+        let account_id: Option<i64> = sqlx::query_scalar(
+            "UPDATE stored_value_accounts SET balance = balance - ?
+             WHERE code = ? AND status = 'active' AND balance >= ?
+               AND (expires_at IS NULL OR expires_at >= date('now', 'localtime'))
+             RETURNING id",
+        )
+        .bind(amount)
+        .bind(code.trim().to_uppercase())
+        .bind(amount)
+        .fetch_optional(&mut **tx)
+        .await?;
+        let Some(account_id) = account_id else {
+            return Ok(false);
+        };
+
+        Self::insert_entry(
+            tx,
+            account_id,
+            "redeem",
+            -amount,
+            Some(order_id),
+            Some(cash_session_id),
+            user_id,
+            None,
+        )
+        .await?;
+        Ok(true)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    async fn insert_entry(
+        tx: &mut Transaction<'_, Sqlite>,
+        account_id: i64,
+        entry_type: &str,
+        amount: Money,
+        order_id: Option<i64>,
+        cash_session_id: Option<i64>,
+        user_id: i64,
+        note: Option<&str>,
+    ) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO stored_value_ledger (account_id, entry_type, amount, balance_after, order_id, cash_session_id, user_id, note)
+            VALUES (?, ?, ?, (SELECT balance FROM stored_value_accounts WHERE id = ?), ?, ?, ?, ?)
+            "#,
+        )
+        .bind(account_id)
+        .bind(entry_type)
+        .bind(amount)
+        .bind(account_id)
+        .bind(order_id)
+        .bind(cash_session_id)
+        .bind(user_id)
+        .bind(note)
+        .execute(&mut **tx)
+        .await?;
+        Ok(())
+    }
+
+    pub async fn find_by_id(&self, id: i64) -> Result<Option<StoredValueAccount>, sqlx::Error> {
+        sqlx::query_as::<_, StoredValueAccount>("SELECT * FROM stored_value_accounts WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_by_code(&self, code: &str) -> Result<Option<StoredValueAccount>, sqlx::Error> {
+        sqlx::query_as::<_, StoredValueAccount>("SELECT * FROM stored_value_accounts WHERE code = ?")
+            .bind(code)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn find_store_credit(
+        &self,
+        customer_document: &str,
+    ) -> Result<Option<StoredValueAccount>, sqlx::Error> {
+        sqlx::query_as::<_, StoredValueAccount>(
+            "SELECT * FROM stored_value_accounts WHERE kind = 'store_credit' AND customer_document = ? AND status = 'active'",
+        )
+        .bind(customer_document)
+        .fetch_optional(&self.pool)
+        .await
+    }
+
+    pub async fn find_by_store(
+        &self,
+        store_id: i64,
+        kind: Option<&str>,
+    ) -> Result<Vec<StoredValueAccount>, sqlx::Error> {
+        sqlx::query_as::<_, StoredValueAccount>(
+            "SELECT * FROM stored_value_accounts WHERE store_id = ? AND (? IS NULL OR kind = ?) ORDER BY created_at DESC, id DESC",
+        )
+        .bind(store_id)
+        .bind(kind)
+        .bind(kind)
+        .fetch_all(&self.pool)
+        .await
+    }
+
+    pub async fn find_entries(&self, account_id: i64) -> Result<Vec<StoredValueEntry>, sqlx::Error> {
+        sqlx::query_as::<_, StoredValueEntry>(
+            "SELECT * FROM stored_value_ledger WHERE account_id = ? ORDER BY created_at ASC, id ASC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+    }
+}
@@ -0,0 +1,315 @@
+use crate::models::money::Money;
+use crate::models::variant::{
+    ProductStyle, ProductVariant, SaveProductStylePayload, UpdateVariantPayload, VariantReportRow,
+};
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::inventory_repo::RESERVED_STOCK_SQL;
+use crate::repositories::price_change_repo::PriceChangeRepository;
+use sqlx::SqlitePool;
+
+/// A variant to create: size, colour and SKU.
+pub type NewVariant = (Option<String>, Option<String>, String);
+
+pub struct VariantRepository {
+    pool: SqlitePool,
+}
+
+impl VariantRepository {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn style_select(filter: &str) -> String {
+        format!(
+            r#"
+            SELECT
+                s.id, s.store_id, s.code, s.name, s.category_id, c.name AS category_name,
+                CAST(s.price AS INTEGER) AS price, CAST(s.cost AS INTEGER) AS cost,
+                s.unit, s.image_url, s.is_active, s.created_at,
+                (SELECT COUNT(*) FROM products p WHERE p.style_id = s.id AND p.is_active = 1) AS variant_count,
+                (SELECT COALESCE(SUM(p.stock - {reserved}), 0) FROM products p
+                 WHERE p.style_id = s.id AND p.is_active = 1) AS stock
+            FROM product_styles s
+            LEFT JOIN categories c ON c.id = s.category_id
+            WHERE {filter}
+            ORDER BY s.name ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL,
+            filter = filter
+        )
+    }
+
+    pub async fn get_styles(&self, store_id: i64) -> Result<Vec<ProductStyle>, sqlx::Error> {
+        sqlx::query_as::<_, ProductStyle>(&Self::style_select("s.store_id = ? AND s.is_active = 1"))
+            .bind(store_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    pub async fn find_style(&self, id: i64) -> Result<Option<ProductStyle>, sqlx::Error> {
+        sqlx::query_as::<_, ProductStyle>(&Self::style_select("s.id = ? AND s.is_active = 1"))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+    }
+
+    pub async fn create_style(&self, payload: &SaveProductStylePayload) -> Result<i64, sqlx::Error> {
+        let result = sqlx::query(
+            "INSERT INTO product_styles (store_id, code, name, category_id, price, cost, unit, image_url) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .bind(payload.store_id)
+        .bind(payload.code.trim())
+        .bind(payload.name.trim())
+        .bind(payload.category_id)
+        .bind(payload.price)
+        .bind(payload.cost)
+        .bind(&payload.unit)
+        .bind(&payload.image_url)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.last_insert_rowid())
+    }
+
+    /// Updates the style and carries name, category, unit and price over to
+    /// its variants (variants with their own price keep it).
+    pub async fn update_style(&self, id: i64, payload: &SaveProductStylePayload) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let previous = PriceChangeRepository::style_prices(&mut tx, id).await?;
+
+        sqlx::query(
+            "UPDATE product_styles SET code = ?, name = ?, category_id = ?, price = ?, cost = ?, unit = ?, image_url = ? WHERE id = ?",
+        )
+        .bind(payload.code.trim())
+        .bind(payload.name.trim())
+        .bind(payload.category_id)
+        .bind(payload.price)
+        .bind(payload.cost)
+        .bind(&payload.unit)
+        .bind(&payload.image_url)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE products SET
+                name = ? || COALESCE(' ' || size, '') || COALESCE(' ' || color, ''),
+                category_id = ?,
+                unit = ?,
+                price = COALESCE(price_override, ?)
+            WHERE style_id = ?
+            "#,
+        )
+        .bind(payload.name.trim())
+        .bind(payload.category_id)
+        .bind(&payload.unit)
+        .bind(payload.price)
+        .bind(id)
+        .execute(&mut *tx)
+        .await?;
+
+        let source = MovementSource::document("style", "product_style", id, payload.user_id);
+        PriceChangeRepository::record_changes(&mut tx, &previous, &source).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Deactivates the style and all its variants.
+    pub async fn delete_style(&self, id: i64) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        sqlx::query("UPDATE product_styles SET is_active = 0 WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query("UPDATE products SET is_active = 0 WHERE style_id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    pub async fn get_variants(&self, style_id: i64) -> Result<Vec<ProductVariant>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT
+                p.id, p.style_id, p.code, p.barcode, p.name, p.size, p.color,
+                CAST(p.price AS INTEGER) AS price,
+                CAST(p.price_override AS INTEGER) AS price_override,
+                CAST(p.cost AS INTEGER) AS cost,
+                p.stock - {reserved} AS stock, {reserved} AS reserved_stock,
+                p.is_active
+            FROM products p
+            WHERE p.style_id = ? AND p.is_active = 1
+            ORDER BY p.id ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL
+        );
+        sqlx::query_as::<_, ProductVariant>(&sql)
+            .bind(style_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+
+    /// Creates the missing variants of a style with no stock, reactivating the
+    /// ones that were deleted. Returns how many were created or reactivated.
+    pub async fn generate_variants(
+        &self,
+        style: &ProductStyle,
+        variants: &[NewVariant],
+    ) -> Result<i64, sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let mut created = 0;
+
+        for (size, color, sku) in variants {
+            let existing: Option<(i64, bool)> = sqlx::query_as(
+                "SELECT id, is_active FROM products WHERE style_id = ? AND COALESCE(size, '') = COALESCE(?, '') AND COALESCE(color, '') = COALESCE(?, '')",
+            )
+            .bind(style.id)
+            .bind(size)
+            .bind(color)
+            .fetch_optional(&mut *tx)
+            .await?;
+
+            match existing {
+                Some((_, true)) => continue,
+                Some((id, false)) => {
+                    sqlx::query("UPDATE products SET is_active = 1 WHERE id = ?")
+                        .bind(id)
+                        .execute(&mut *tx)
+                        .await?;
+                }
+                None => {
+                    let name = [Some(style.name.as_str()), size.as_deref(), color.as_deref()]
+                        .into_iter()
+                        .flatten()
+                        .collect::<Vec<_>>()
+                        .join(" ");
+                    sqlx::query(
+                        r#"
+                        INSERT INTO products (code, name, category_id, price, cost, stock, unit, image_url, store_id, style_id, size, color)
+                        VALUES (?, ?, ?, ?, ?, 0, ?, ?, ?, ?, ?, ?)
+                        "#,
+                    )
+                    .bind(sku)
+                    .bind(name)
+                    .bind(style.category_id)
+                    .bind(style.price)
+                    .bind(style.cost)
+                    .bind(&style.unit)
+                    .bind(&style.image_url)
+                    .bind(style.store_id)
+                    .bind(style.id)
+                    .bind(size)
+                    .bind(color)
+                    .execute(&mut *tx)
+                    .await?;
+                }
+            }
+            created += 1;
+        }
+
+        tx.commit().await?;
+        Ok(created)
+    }
+
+    pub async fn update_variant(&self, payload: &UpdateVariantPayload) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        let previous: Vec<(i64, Money)> =
+            sqlx::query_as("SELECT id, CAST(price AS INTEGER) FROM products WHERE id = ?")
+                .bind(payload.id)
+                .fetch_all(&mut *tx)
+                .await?;
+
+        sqlx::query(
+            r#"
+            UPDATE products SET
+                code = ?,
+                barcode = ?,
+                price_override = ?,
+                price = COALESCE(?, (SELECT s.price FROM product_styles s WHERE s.id = products.style_id))
+            WHERE id = ? AND style_id IS NOT NULL
+            "#,
+        )
+        .bind(&payload.code)
+        .bind(&payload.barcode)
+        .bind(payload.price_override)
+        .bind(payload.price_override)
+        .bind(payload.id)
+        .execute(&mut *tx)
+        .await?;
+
+        let source = MovementSource::manual("manual", payload.user_id, "Edición de la variante");
+        PriceChangeRepository::record_changes(&mut tx, &previous, &source).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Sets a variant's price, as an override only if it differs from the style price.
+    pub async fn set_variant_price(
+        &self,
+        product_id: i64,
+        price: Money,
+        source: &MovementSource,
+    ) -> Result<(), sqlx::Error> {
+        let mut tx = self.pool.begin().await?;
+        PriceChangeRepository::set_price(&mut tx, product_id, price, source).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// Current stock plus units sold (completed sales) and received (purchase
+    /// orders) between two dates, for every active variant of a store.
+    pub async fn get_variant_report(
+        &self,
+        store_id: i64,
+        date_from: &str,
+        date_to: &str,
+    ) -> Result<Vec<VariantReportRow>, sqlx::Error> {
+        let sql = format!(
+            r#"
+            SELECT
+                p.id AS product_id,
+                p.style_id,
+                p.code,
+                p.size,
+                p.color,
+                p.stock - {reserved} AS stock,
+                {reserved} AS reserved_stock,
+                COALESCE(sold.units, 0) AS units_sold,
+                CAST(COALESCE(sold.amount, 0) AS INTEGER) AS sales_amount,
+                COALESCE(bought.units, 0) AS units_purchased
+            FROM products p
+            INNER JOIN product_styles s ON s.id = p.style_id
+            LEFT JOIN (
+                SELECT oi.product_id, SUM(oi.quantity) AS units, SUM(oi.subtotal) AS amount
+                FROM order_product_lines oi
+                INNER JOIN orders o ON o.id = oi.order_id
+                WHERE o.store_id = ? AND o.status = 'completed'
+                  AND date(o.created_at) BETWEEN ? AND ?
+                GROUP BY oi.product_id
+            ) sold ON sold.product_id = p.id
+            LEFT JOIN (
+                SELECT pi.product_id, SUM(pi.quantity) AS units
+                FROM purchase_order_items pi
+                INNER JOIN purchase_orders po ON po.id = pi.purchase_order_id
+                WHERE po.store_id = ? AND date(po.created_at) BETWEEN ? AND ?
+                GROUP BY pi.product_id
+            ) bought ON bought.product_id = p.id
+            WHERE s.store_id = ? AND s.is_active = 1 AND p.is_active = 1
+            ORDER BY s.name ASC, p.id ASC
+            "#,
+            reserved = RESERVED_STOCK_SQL
+        );
+        sqlx::query_as::<_, VariantReportRow>(&sql)
+            .bind(store_id)
+            .bind(date_from)
+            .bind(date_to)
+            .bind(store_id)
+            .bind(date_from)
+            .bind(date_to)
+            .bind(store_id)
+            .fetch_all(&self.pool)
+            .await
+    }
+}
@@ -0,0 +1,343 @@
+use crate::models::barcode::{AssignBarcodesPayload, AssignedBarcode, LabelProduct, LabelRequest};
+use crate::repositories::barcode_repo::BarcodeRepository;
+use crate::repositories::purchase_order_repo::PurchaseOrderRepository;
+use crate::services::config_service::ConfigService;
+use crate::services::pdf_service;
+use sqlx::SqlitePool;
+use std::fs;
+use std::path::Path;
+
+/// GS1 prefix reserved for in-store numbering.
+const EAN_INTERNAL_PREFIX: &str = "20";
+const SYMBOLOGIES: [&str; 2] = ["ean13", "code128"];
+
+pub struct BarcodeService {
+    pub barcode_repo: BarcodeRepository,
+    pub purchase_order_repo: PurchaseOrderRepository,
+    pub config_service: ConfigService,
+}
+
+impl BarcodeService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            barcode_repo: BarcodeRepository::new(pool.clone()),
+            purchase_order_repo: PurchaseOrderRepository::new(pool.clone()),
+            config_service: ConfigService::new(pool),
+        }
+    }
+
+    /// Gives an internal barcode to the products that have none. Products
+    /// without a code also take it as their code.
+    pub async fn assign_barcodes(
+        &self,
+        payload: AssignBarcodesPayload,
+    ) -> Result<Vec<AssignedBarcode>, String> {
+        let symbology = match payload.symbology {
+            Some(symbology) => symbology,
+            None => self.default_symbology().await?,
+        };
+        if !SYMBOLOGIES.contains(&symbology.as_str()) {
+            return Err("Simbología inválida. Debe ser: ean13 o code128".to_string());
+        }
+        if payload.store_id > 99 && symbology == "ean13" {
+            return Err("El EAN-13 interno admite hasta la tienda #99; use code128".to_string());
+        }
+
+        let store_id = payload.store_id;
+        let format = |seq: i64| internal_barcode(&symbology, store_id, seq);
+        self.barcode_repo
+            .assign(store_id, &payload.product_ids, &symbology, &format)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_symbology(&self, symbology: &str) -> Result<(), String> {
+        if !SYMBOLOGIES.contains(&symbology) {
+            return Err("Simbología inválida. Debe ser: ean13 o code128".to_string());
+        }
+        self.config_service.set_config("barcode_symbology", symbology).await
+    }
+
+    /// Writes the labels as ZPL ("zpl") or as an A4 sheet ("pdf") inside
+    /// `output_dir` and returns the file path. Products without a barcode get
+    /// one first.
+    pub async fn generate_labels(
+        &self,
+        store_id: i64,
+        requests: &[LabelRequest],
+        format: &str,
+        output_dir: &Path,
+        file_stem: &str,
+    ) -> Result<String, String> {
+        if !matches!(format, "zpl" | "pdf") {
+            return Err("Formato inválido. Debe ser: zpl o pdf".to_string());
+        }
+        let requests: Vec<&LabelRequest> = requests.iter().filter(|r| r.copies > 0).collect();
+        if requests.is_empty() {
+            return Err("No hay etiquetas que imprimir".to_string());
+        }
+
+        let product_ids: Vec<i64> = requests.iter().map(|r| r.product_id).collect();
+        self.assign_barcodes(AssignBarcodesPayload {
+            store_id,
+            product_ids: product_ids.clone(),
+            symbology: None,
+        })
+        .await?;
+
+        let products = self
+            .barcode_repo
+            .find_label_products(store_id, &product_ids)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut labels: Vec<(LabelProduct, i64)> = Vec::with_capacity(requests.len());
+        for request in requests {
+            let product = products
+                .iter()
+                .find(|p| p.product_id == request.product_id)
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", request.product_id))?;
+            labels.push((
+                LabelProduct {
+                    product_id: product.product_id,
+                    name: product.name.clone(),
+                    size: product.size.clone(),
+                    color: product.color.clone(),
+                    price: product.price,
+                    barcode: product.barcode.clone(),
+                },
+                request.copies,
+            ));
+        }
+
+        let dir = output_dir.join("labels");
+        match format {
+            "zpl" => {
+                fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                let path = dir.join(format!("{}.zpl", file_stem));
+                let zpl: String = labels.iter().map(|(l, copies)| zpl_label(l, *copies)).collect();
+                fs::write(&path, zpl).map_err(|e| e.to_string())?;
+                Ok(path.to_string_lossy().to_string())
+            }
+            _ => pdf_service::write_label_sheet(&labels, &dir, &format!("{}.pdf", file_stem)),
+        }
+    }
+
+    /// One label per unit received in the purchase order.
+    pub async fn generate_purchase_order_labels(
+        &self,
+        purchase_order_id: i64,
+        format: &str,
+        output_dir: &Path,
+    ) -> Result<String, String> {
+        let order = self
+            .purchase_order_repo
+            .find_by_id(purchase_order_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Orden de compra no encontrada".to_string())?;
+        let items = self
+            .purchase_order_repo
+            .find_items_by_order(order.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let requests: Vec<LabelRequest> = items
+            .iter()
+            .filter_map(|item| {
+                item.product_id.map(|product_id| LabelRequest {
+                    product_id,
+                    copies: item.quantity,
+                })
+            })
+            .collect();
+
+        self.generate_labels(
+            order.store_id,
+            &requests,
+            format,
+            output_dir,
+            &format!("etiquetas_oc_{:06}", order.id),
+        )
+        .await
+    }
+
+    async fn default_symbology(&self) -> Result<String, String> {
+        Ok(self
+            .config_service
+            .get_config("barcode_symbology")
+            .await?
+            .unwrap_or_else(|| "ean13".to_string()))
+    }
+}
+
+/// "2001000001234" style EAN-13 (prefix, store, sequence, check digit) or
+/// "T01000123" style Code128.
+fn internal_barcode(symbology: &str, store_id: i64, seq: i64) -> String {
+    match symbology {
+        "ean13" => {
+            let body = format!("{}{:02}{:08}", EAN_INTERNAL_PREFIX, store_id, seq);
+            let check = ean13_check_digit(&body);
+            format!("{}{}", body, check)
+        }
+        _ => format!("T{:02}{:06}", store_id, seq),
+    }
+}
+
+/// Splits a variable-measure EAN-13 (prefixes 21-29) into its 5-digit item
+/// code and 5-digit value. Prefix 20 holds our own sequential barcodes and is
+/// never parsed.
+pub fn parse_embedded_ean13(code: &str) -> Option<(&str, i64)> {
+    if !is_ean13(code) || !("21"..="29").contains(&&code[..2]) {
+        return None;
+    }
+    let value = code[7..12].parse().ok()?;
+    Some((&code[2..7], value))
+}
+
+/// Check digit for the first 12 digits of an EAN-13.
+pub fn ean13_check_digit(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .chars()
+        .filter_map(|c| c.to_digit(10))
+        .enumerate()
+        .map(|(i, d)| if i % 2 == 0 { d } else { d * 3 })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// True for 13 digits with a valid check digit.
+pub fn is_ean13(code: &str) -> bool {
+    code.len() == 13
+        && code.chars().all(|c| c.is_ascii_digit())
+        && ean13_check_digit(&code[..12]) == code[12..].parse::<u32>().unwrap_or(99)
+}
+
+const EAN_L: [&str; 10] = [
+    "0001101", "0011001", "0010011", "0111101", "0100011",
+    "0110001", "0101111", "0111011", "0110111", "0001011",
+];
+const EAN_G: [&str; 10] = [
+    "0100111", "0110011", "0011011", "0100001", "0011101",
+    "0111001", "0000101", "0010001", "0001001", "0010111",
+];
+const EAN_R: [&str; 10] = [
+    "1110010", "1100110", "1101100", "1000010", "1011100",
+    "1001110", "1010000", "1000100", "1001000", "1110100",
+];
+/// Left-half parity (L or G) selected by the first digit.
+const EAN_PARITY: [&str; 10] = [
+    "LLLLLL", "LLGLGG", "LLGGLG", "LLGGGL", "LGLLGG",
+    "LGGLLG", "LGGGLL", "LGLGLG", "LGLGGL", "LGGLGL",
+];
+
+/// The 95 modules (true = bar) of a valid EAN-13.
+pub fn ean13_modules(code: &str) -> Vec<bool> {
+    let digits: Vec<usize> = code.chars().filter_map(|c| c.to_digit(10)).map(|d| d as usize).collect();
+    let mut pattern = String::from("101");
+    for (i, parity) in EAN_PARITY[digits[0]].chars().enumerate() {
+        let d = digits[i + 1];
+        pattern.push_str(if parity == 'L' { EAN_L[d] } else { EAN_G[d] });
+    }
+    pattern.push_str("01010");
+    for &d in &digits[7..13] {
+        pattern.push_str(EAN_R[d]);
+    }
+    pattern.push_str("101");
+    pattern.chars().map(|c| c == '1').collect()
+}
+
+/// Bar/space widths of Code 128 symbols 0..=105 (bar first).
+const CODE128_WIDTHS: [&str; 106] = [
+    "212222", "222122", "222221", "121223", "121322", "131222", "122213", "122312", "132212", "221213",
+    "221312", "231212", "112232", "122132", "122231", "113222", "123122", "123221", "223211", "221132",
+    "221231", "213212", "223112", "312131", "311222", "321122", "321221", "312212", "322112", "322211",
+    "212123", "212321", "232121", "111323", "131123", "131321", "112313", "132113", "132311", "211313",
+    "231113", "231311", "112133", "112331", "132131", "113123", "113321", "133121", "313121", "211331",
+    "231131", "213113", "213311", "213131", "311123", "311321", "331121", "312113", "312311", "332111",
+    "314111", "221411", "431111", "111224", "111422", "121124", "121421", "141122", "141221", "112214",
+    "112412", "122114", "122411", "142112", "142211", "241211", "221114", "413111", "241112", "134111",
+    "111242", "121142", "121241", "114212", "124112", "124211", "411212", "421112", "421211", "212141",
+    "214121", "412121", "111143", "111341", "131141", "114113", "114311", "411113", "411311", "113141",
+    "114131", "311141", "411131", "211412", "211214", "211232",
+];
+const CODE128_STOP: &str = "2331112";
+const CODE128_START_B: usize = 104;
+const CODE128_START_C: usize = 105;
+
+/// Modules of a Code 128 symbol: set C for even-length digit strings, set B
+/// otherwise. Fails on characters outside printable ASCII.
+pub fn code128_modules(text: &str) -> Result<Vec<bool>, String> {
+    let all_digits = !text.is_empty() && text.chars().all(|c| c.is_ascii_digit());
+    let mut values: Vec<usize> = if all_digits && text.len().is_multiple_of(2) {
+        let mut values = vec![CODE128_START_C];
+        for pair in text.as_bytes().chunks(2) {
+            values.push(((pair[0] - b'0') * 10 + (pair[1] - b'0')) as usize);
+        }
+        values
+    } else {
+        let mut values = vec![CODE128_START_B];
+        for c in text.chars() {
+            if !(' '..='~').contains(&c) {
+                return Err(format!("El código \"{}\" tiene caracteres no válidos para Code128", text));
+            }
+            values.push(c as usize - 32);
+        }
+        values
+    };
+
+    let checksum = values
+        .iter()
+        .enumerate()
+        .map(|(i, v)| if i == 0 { *v } else { i * v })
+        .sum::<usize>()
+        % 103;
+    values.push(checksum);
+
+    let mut modules = Vec::new();
+    let widths = values
+        .iter()
+        .map(|v| CODE128_WIDTHS[*v])
+        .chain(std::iter::once(CODE128_STOP));
+    for symbol in widths {
+        for (i, w) in symbol.chars().enumerate() {
+            let bar = i % 2 == 0;
+            for _ in 0..w.to_digit(10).unwrap_or(0) {
+                modules.push(bar);
+            }
+        }
+    }
+    Ok(modules)
+}
+
+/// ZPL for a 50 x 25 mm label at 203 dpi.
+fn zpl_label(label: &LabelProduct, copies: i64) -> String {
+    let clean = |text: &str| text.replace(['^', '~'], " ");
+    let barcode = label.barcode.as_deref().unwrap_or("");
+    let variant = [label.size.as_deref(), label.color.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" / ");
+    let symbol = if is_ean13(barcode) {
+        // ^BE takes the first 12 digits and prints the check digit itself
+        format!("^BY2^BEN,80,Y,N^FD{}^FS", &barcode[..12])
+    } else {
+        format!("^BY2^BCN,80,Y,N,N^FD{}^FS", clean(barcode))
+    };
+
+    format!(
+        "^XA^CI28^PW400^LL200\n\
+         ^FO16,10^A0N,24,24^FB250,1,0,L^FD{}^FS\n\
+         ^FO16,38^A0N,20,20^FD{}^FS\n\
+         ^FO270,10^A0N,30,30^FDS/ {}^FS\n\
+         ^FO40,70{}\n\
+         ^PQ{}\n\
+         ^XZ\n",
+        clean(&label.name),
+        clean(&variant),
+        label.price,
+        symbol,
+        copies
+    )
+}
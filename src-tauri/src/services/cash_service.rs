@@ -1,4 +1,5 @@
 use crate::models::cash::{CashSession, CloseCashPayload, OpenCashPayload, UpdateExpensePayload};
+use crate::models::money::Money;
 use crate::repositories::cash_repo::CashRepository;
 use sqlx::SqlitePool;
 
@@ -53,7 +54,7 @@ impl CashService {
         &self,
         session_id: i64,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
     ) -> Result<i64, String> {
         self.cash_repo
@@ -86,7 +87,7 @@ impl CashService {
     pub async fn add_expense_standalone(
         &self,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
         category: Option<String>,
         supplier: Option<String>,
@@ -103,7 +104,7 @@ impl CashService {
         &self,
         session_id: i64,
         description: String,
-        amount: f64,
+        amount: Money,
         payment_method: String,
     ) -> Result<i64, String> {
         self.cash_repo
@@ -0,0 +1,187 @@
+use crate::models::catalog::{
+    AddCatalogProductToStorePayload, CatalogProduct, CatalogProductDetail, UpdateCatalogProductPayload,
+};
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
+use crate::repositories::catalog_repo::CatalogRepository;
+use crate::repositories::inventory_repo::InventoryRepository;
+use sqlx::SqlitePool;
+
+pub struct CatalogService {
+    pub catalog_repo: CatalogRepository,
+    pub inventory_repo: InventoryRepository,
+}
+
+impl CatalogService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            catalog_repo: CatalogRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool),
+        }
+    }
+
+    pub async fn get_catalog_products(&self, search: Option<String>) -> Result<Vec<CatalogProduct>, String> {
+        let search = search
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty());
+        self.catalog_repo
+            .find_all(search)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// The catalog item with its stock, cost and price in each store.
+    pub async fn get_catalog_product(&self, id: i64) -> Result<CatalogProductDetail, String> {
+        let product = self
+            .catalog_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Producto no encontrado en el catálogo".to_string())?;
+        let stores = self
+            .catalog_repo
+            .find_inventory(id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(CatalogProductDetail { product, stores })
+    }
+
+    /// Edits the data shared by every store. The base price reaches the
+    /// stores that have no local price.
+    pub async fn update_catalog_product(
+        &self,
+        mut payload: UpdateCatalogProductPayload,
+    ) -> Result<CatalogProductDetail, String> {
+        payload.name = payload.name.trim().to_string();
+        if payload.name.is_empty() {
+            return Err("El nombre del producto es obligatorio".to_string());
+        }
+        if payload.price.is_negative() {
+            return Err("El precio no puede ser negativo".to_string());
+        }
+        payload.code = trimmed(payload.code);
+        payload.barcode = trimmed(payload.barcode);
+
+        self.catalog_repo
+            .find_by_id(payload.id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Producto no encontrado en el catálogo".to_string())?;
+
+        let source = MovementSource::manual("catalog", payload.user_id, "Precio base del catálogo");
+        self.catalog_repo
+            .update(&payload, &source)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.message().contains("catalog_products.code") => {
+                    "Ya existe otro producto con ese código en el catálogo".to_string()
+                }
+                sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                    "El código de barras ya está en uso en alguna tienda".to_string()
+                }
+                other => other.to_string(),
+            })?;
+
+        self.get_catalog_product(payload.id).await
+    }
+
+    /// Starts carrying a catalog item in a store, with no stock. A store that
+    /// carried it before gets its old row back (with its kardex and history).
+    /// Returns the store's product id.
+    pub async fn add_catalog_product_to_store(
+        &self,
+        payload: AddCatalogProductToStorePayload,
+    ) -> Result<i64, String> {
+        if payload.local_price.is_some_and(|p| p.is_negative()) {
+            return Err("El precio no puede ser negativo".to_string());
+        }
+        self.catalog_repo
+            .find_by_id(payload.catalog_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Producto no encontrado en el catálogo".to_string())?;
+        if self
+            .catalog_repo
+            .is_variant(payload.catalog_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            return Err("Las variantes se agregan a la tienda desde su modelo".to_string());
+        }
+        if let Some(category_id) = payload.category_id {
+            let visible = self
+                .inventory_repo
+                .category_visible(category_id, payload.store_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if !visible {
+                return Err("La categoría no existe o pertenece a otra tienda".to_string());
+            }
+        }
+
+        match self
+            .catalog_repo
+            .find_store_row(payload.catalog_id, payload.store_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some((_, true)) => Err("El producto ya está en esta tienda".to_string()),
+            Some((product_id, false)) => {
+                let source = MovementSource::manual("manual", payload.user_id, "Producto agregado a la tienda");
+                self.catalog_repo
+                    .reactivate(product_id, &payload, &source)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(product_id)
+            }
+            None => self
+                .catalog_repo
+                .add_to_store(&payload)
+                .await
+                .map_err(|e| match e {
+                    sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                        "La tienda ya tiene otro producto con ese código".to_string()
+                    }
+                    other => other.to_string(),
+                }),
+        }
+    }
+
+    /// Sets the store's own price for a product, or with `None` goes back to
+    /// the catalog base price.
+    pub async fn set_local_price(
+        &self,
+        product_id: i64,
+        local_price: Option<Money>,
+        user_id: Option<i64>,
+    ) -> Result<(), String> {
+        if local_price.is_some_and(|p| p.is_negative()) {
+            return Err("El precio no puede ser negativo".to_string());
+        }
+        let product = self
+            .inventory_repo
+            .find_by_id(product_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Producto no encontrado".to_string())?;
+        if product.style_id.is_some() {
+            return Err("Las variantes toman el precio de su modelo".to_string());
+        }
+        let note = if local_price.is_some() {
+            "Precio local de la tienda"
+        } else {
+            "Vuelve al precio base del catálogo"
+        };
+        self.catalog_repo
+            .set_local_price(product_id, local_price, &MovementSource::manual("manual", user_id, note))
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+fn trimmed(value: Option<String>) -> Option<String> {
+    value
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
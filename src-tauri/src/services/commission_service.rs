@@ -0,0 +1,258 @@
+use crate::models::commission::{
+    CommissionReport, CommissionReportRow, CommissionRule, SaveCommissionRulePayload,
+    COMMISSION_EXPORT_HEADERS,
+};
+use crate::models::money::Money;
+use crate::repositories::commission_repo::CommissionRepository;
+use crate::services::product_import_service::{write_csv, write_xlsx, ExportCell};
+use crate::services::sales_service::split_igv;
+use chrono::{Local, NaiveDate};
+use sqlx::SqlitePool;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+pub struct CommissionService {
+    pub commission_repo: CommissionRepository,
+}
+
+impl CommissionService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            commission_repo: CommissionRepository::new(pool),
+        }
+    }
+
+    pub async fn get_rules(&self, store_id: i64) -> Result<Vec<CommissionRule>, String> {
+        self.commission_repo
+            .get_rules(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn save_rule(&self, payload: SaveCommissionRulePayload) -> Result<i64, String> {
+        match payload.scope.as_str() {
+            "global" => {}
+            "category" if payload.category_id.is_some() => {}
+            "product" if payload.product_id.is_some() => {}
+            "category" | "product" => {
+                return Err("Seleccione la categoría o el producto de la regla".to_string())
+            }
+            _ => return Err("Tipo de regla no válido".to_string()),
+        }
+        if !(0.0..=100.0).contains(&payload.rate) {
+            return Err("El porcentaje debe estar entre 0 y 100".to_string());
+        }
+        if payload.min_monthly_volume.is_negative() {
+            return Err("El volumen mínimo no puede ser negativo".to_string());
+        }
+
+        self.commission_repo
+            .save_rule(&payload)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete_rule(&self, id: i64) -> Result<(), String> {
+        self.commission_repo
+            .delete_rule(id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Commission per seller for the sales of a store between two dates.
+    /// Voided sales earn nothing and returns (store credit issued against a
+    /// sale) reduce that sale's lines proportionally.
+    pub async fn get_commission_report(
+        &self,
+        store_id: i64,
+        date_from: String,
+        date_to: String,
+    ) -> Result<CommissionReport, String> {
+        let from = NaiveDate::parse_from_str(&date_from, "%Y-%m-%d")
+            .map_err(|_| "Fecha inicial inválida".to_string())?;
+        let to = NaiveDate::parse_from_str(&date_to, "%Y-%m-%d")
+            .map_err(|_| "Fecha final inválida".to_string())?;
+        if from > to {
+            return Err("La fecha inicial no puede ser posterior a la final".to_string());
+        }
+
+        let rules: Vec<CommissionRule> = self
+            .get_rules(store_id)
+            .await?
+            .into_iter()
+            .filter(|r| r.is_active)
+            .collect();
+        let lines = self
+            .commission_repo
+            .get_lines(store_id, &date_from, &date_to)
+            .await
+            .map_err(|e| e.to_string())?;
+        let volumes: HashMap<(i64, String), Money> = self
+            .commission_repo
+            .get_monthly_volumes(
+                store_id,
+                &from.format("%Y-%m").to_string(),
+                &to.format("%Y-%m").to_string(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .into_iter()
+            .map(|(user_id, month, volume)| ((user_id, month), volume))
+            .collect();
+        let voided = self
+            .commission_repo
+            .get_voided(store_id, &date_from, &date_to)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut rows: BTreeMap<i64, CommissionReportRow> = BTreeMap::new();
+        let mut counted_orders = HashSet::new();
+
+        for line in &lines {
+            let row = rows.entry(line.user_id).or_insert_with(|| empty_row(line.user_id));
+            row.user_name = line.user_name.clone();
+
+            if counted_orders.insert(line.order_id) {
+                row.sales_count += 1;
+                row.gross_sales += line.order_total;
+                row.returns += line.returned_amount;
+            }
+
+            let kept = (1.0 - line.returned_amount.ratio(line.order_total)).clamp(0.0, 1.0);
+            let volume = volumes
+                .get(&(line.user_id, line.month.clone()))
+                .copied()
+                .unwrap_or(Money::ZERO);
+            if let Some(rate) = rate_for(&rules, line.product_id, line.category_id, volume) {
+                let (base, _) = split_igv(line.subtotal.scale(kept));
+                row.commission += base.scale(rate / 100.0);
+            }
+        }
+
+        for v in voided {
+            let row = rows.entry(v.user_id).or_insert_with(|| empty_row(v.user_id));
+            row.voided_count = v.voided_count;
+            row.voided_amount = v.voided_amount;
+        }
+
+        let mut rows: Vec<CommissionReportRow> = rows
+            .into_values()
+            .map(|mut row| {
+                row.net_sales = row.gross_sales - row.returns;
+                row
+            })
+            .collect();
+        rows.sort_by_key(|r| std::cmp::Reverse(r.net_sales));
+        let total_commission = rows.iter().map(|r| r.commission).sum();
+
+        Ok(CommissionReport {
+            store_id,
+            date_from,
+            date_to,
+            rows,
+            total_commission,
+        })
+    }
+
+    /// Writes the commission report as CSV or XLSX under `output_dir/exports`,
+    /// one row per seller plus a total row. Returns the file path.
+    pub async fn export_commission_report(
+        &self,
+        store_id: i64,
+        date_from: String,
+        date_to: String,
+        format: &str,
+        output_dir: &Path,
+    ) -> Result<String, String> {
+        if !matches!(format, "csv" | "xlsx") {
+            return Err("Formato inválido. Debe ser: csv o xlsx".to_string());
+        }
+        let report = self.get_commission_report(store_id, date_from, date_to).await?;
+
+        let dir = output_dir.join("exports");
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let path = dir.join(format!(
+            "comisiones_tienda{}_{}_{}_{}.{}",
+            store_id,
+            report.date_from,
+            report.date_to,
+            Local::now().format("%Y%m%d_%H%M%S"),
+            format
+        ));
+
+        let mut rows: Vec<[ExportCell; 9]> = report
+            .rows
+            .iter()
+            .map(|r| {
+                [
+                    ExportCell::Number(r.user_id),
+                    ExportCell::Text(r.user_name.clone().unwrap_or_default()),
+                    ExportCell::Number(r.sales_count),
+                    ExportCell::Money(r.gross_sales),
+                    ExportCell::Money(r.returns),
+                    ExportCell::Number(r.voided_count),
+                    ExportCell::Money(r.voided_amount),
+                    ExportCell::Money(r.net_sales),
+                    ExportCell::Money(r.commission),
+                ]
+            })
+            .collect();
+        let sum = |field: fn(&CommissionReportRow) -> Money| report.rows.iter().map(field).sum();
+        rows.push([
+            ExportCell::Text(String::new()),
+            ExportCell::Text("TOTAL".to_string()),
+            ExportCell::Number(report.rows.iter().map(|r| r.sales_count).sum()),
+            ExportCell::Money(sum(|r| r.gross_sales)),
+            ExportCell::Money(sum(|r| r.returns)),
+            ExportCell::Number(report.rows.iter().map(|r| r.voided_count).sum()),
+            ExportCell::Money(sum(|r| r.voided_amount)),
+            ExportCell::Money(sum(|r| r.net_sales)),
+            ExportCell::Money(report.total_commission),
+        ]);
+
+        match format {
+            "csv" => write_csv(&path, &COMMISSION_EXPORT_HEADERS, &rows)?,
+            _ => write_xlsx(&path, "Comisiones", &COMMISSION_EXPORT_HEADERS, &rows, 1)?,
+        }
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+fn empty_row(user_id: i64) -> CommissionReportRow {
+    CommissionReportRow {
+        user_id,
+        user_name: None,
+        sales_count: 0,
+        gross_sales: Money::ZERO,
+        returns: Money::ZERO,
+        voided_count: 0,
+        voided_amount: Money::ZERO,
+        net_sales: Money::ZERO,
+        commission: Money::ZERO,
+    }
+}
+
+/// Picks the most specific scope (product, then category, then global) with a
+/// tier reached by `monthly_volume`, and the highest such tier within it. A
+/// scope whose tiers are all out of reach falls through to the broader one.
+fn rate_for(
+    rules: &[CommissionRule],
+    product_id: i64,
+    category_id: Option<i64>,
+    monthly_volume: Money,
+) -> Option<f64> {
+    let scopes: [&dyn Fn(&CommissionRule) -> bool; 3] = [
+        &|r| r.scope == "product" && r.product_id == Some(product_id),
+        &|r| r.scope == "category" && category_id.is_some() && r.category_id == category_id,
+        &|r| r.scope == "global",
+    ];
+
+    scopes.iter().find_map(|matches| {
+        rules
+            .iter()
+            .filter(|r| matches(r) && r.min_monthly_volume <= monthly_volume)
+            .max_by_key(|r| r.min_monthly_volume)
+            .map(|r| r.rate)
+    })
+}
@@ -0,0 +1,202 @@
+use crate::models::inventory_count::{
+    InventoryCount, InventoryCountReport, InventoryCountVariance, ScanInventoryCountPayload,
+};
+use crate::models::money::Money;
+use crate::repositories::inventory_count_repo::InventoryCountRepository;
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::user_repo::UserRepository;
+use sqlx::SqlitePool;
+
+pub struct InventoryCountService {
+    pub inventory_count_repo: InventoryCountRepository,
+    pub inventory_repo: InventoryRepository,
+    pub user_repo: UserRepository,
+}
+
+impl InventoryCountService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            inventory_count_repo: InventoryCountRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool),
+        }
+    }
+
+    /// Opens a count for the store, snapshotting its current stock. Sales can
+    /// go on while counting.
+    pub async fn start_count(
+        &self,
+        store_id: i64,
+        user_id: i64,
+        notes: Option<String>,
+    ) -> Result<InventoryCount, String> {
+        let open = self
+            .inventory_count_repo
+            .find_open(store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if let Some(open) = open {
+            return Err(format!("Ya hay un conteo abierto en esta tienda (#{})", open.id));
+        }
+
+        let id = self
+            .inventory_count_repo
+            .create(store_id, user_id, notes.as_deref())
+            .await
+            .map_err(|e| e.to_string())?;
+        self.find_count(id).await
+    }
+
+    /// Registers a scanned code (product code or barcode). Returns the units
+    /// counted so far for that product in the current pass.
+    pub async fn scan(&self, payload: ScanInventoryCountPayload) -> Result<i64, String> {
+        let count = self.find_count(payload.count_id).await?;
+        let code = payload.code.trim();
+        if code.is_empty() {
+            return Err("Código vacío".to_string());
+        }
+        let quantity = payload.quantity.unwrap_or(1);
+        if quantity == 0 {
+            return Err("La cantidad no puede ser cero".to_string());
+        }
+
+        let product = match self
+            .inventory_repo
+            .find_by_code(code, count.store_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            Some(product) => Some(product),
+            None => self
+                .inventory_repo
+                .find_by_barcode(code, count.store_id)
+                .await
+                .map_err(|e| e.to_string())?,
+        };
+        let product = product.ok_or_else(|| format!("No existe un producto con código \"{}\"", code))?;
+
+        let counted = self
+            .inventory_count_repo
+            .scan(count.id, product.id, product.cost, code, quantity, payload.user_id)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => "La cantidad contada no puede quedar en negativo".to_string(),
+                other => other.to_string(),
+            })?;
+        counted.ok_or_else(|| "El conteo ya fue cerrado".to_string())
+    }
+
+    /// Starts another pass: products scanned from now on replace what earlier
+    /// passes counted for them; the rest keep their previous count.
+    pub async fn start_pass(&self, count_id: i64) -> Result<i64, String> {
+        self.inventory_count_repo
+            .next_pass(count_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "El conteo ya fue cerrado".to_string())
+    }
+
+    /// Variance per product in units and at cost. While the count is open it
+    /// is a preview; once approved it shows the frozen figures.
+    pub async fn get_report(&self, count_id: i64) -> Result<InventoryCountReport, String> {
+        let count = self.find_count(count_id).await?;
+        let lines = self
+            .inventory_count_repo
+            .find_lines(count_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut report = InventoryCountReport {
+            count,
+            lines: Vec::with_capacity(lines.len()),
+            counted_products: 0,
+            uncounted_products: 0,
+            units_over: 0,
+            units_short: 0,
+            cost_over: Money::ZERO,
+            cost_short: Money::ZERO,
+            net_cost: Money::ZERO,
+        };
+
+        for line in lines {
+            let variance = match line.counted {
+                Some(counted) => {
+                    report.counted_products += 1;
+                    counted - line.expected
+                }
+                None => {
+                    report.uncounted_products += 1;
+                    0
+                }
+            };
+            let variance_cost = line.unit_cost.times(variance);
+            if variance > 0 {
+                report.units_over += variance;
+                report.cost_over += variance_cost;
+            } else if variance < 0 {
+                report.units_short -= variance;
+                report.cost_short -= variance_cost;
+            }
+            report.lines.push(InventoryCountVariance {
+                line,
+                variance,
+                variance_cost,
+            });
+        }
+        report.net_cost = report.cost_over - report.cost_short;
+
+        Ok(report)
+    }
+
+    /// Posts the variances as adjustments. Returns how many products changed.
+    pub async fn approve_count(
+        &self,
+        count_id: i64,
+        manager_id: i64,
+        zero_uncounted: bool,
+    ) -> Result<i64, String> {
+        let manager = self
+            .user_repo
+            .find_user_by_id(manager_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|u| u.is_active)
+            .ok_or_else(|| format!("El usuario #{} no existe o está inactivo", manager_id))?;
+        if !matches!(manager.cargo.as_deref(), Some("ADMIN") | Some("GERENTE")) {
+            return Err("Solo un administrador o gerente puede aprobar el conteo".to_string());
+        }
+
+        self.inventory_count_repo
+            .approve(count_id, manager_id, zero_uncounted)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "El conteo ya fue cerrado".to_string())
+    }
+
+    pub async fn cancel_count(&self, count_id: i64) -> Result<(), String> {
+        let cancelled = self
+            .inventory_count_repo
+            .cancel(count_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !cancelled {
+            return Err("El conteo ya fue cerrado".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn get_counts(&self, store_id: i64) -> Result<Vec<InventoryCount>, String> {
+        self.inventory_count_repo
+            .find_by_store(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn find_count(&self, id: i64) -> Result<InventoryCount, String> {
+        self.inventory_count_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Conteo no encontrado".to_string())
+    }
+}
@@ -1,46 +1,206 @@
-use crate::models::inventory::{Category, ProductWithCategory};
+use crate::models::inventory::{
+    Category, CategoryNode, ProductLookup, ProductSearchPage, ProductWithCategory,
+};
+use crate::models::money::Money;
+use crate::models::product_cost::ProductCostChange;
+use crate::models::stock_movement::{Kardex, MovementSource};
+use crate::repositories::catalog_repo::CatalogRepository;
 use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::product_cost_repo::ProductCostRepository;
+use crate::repositories::stock_movement_repo::StockMovementRepository;
+use crate::repositories::variant_repo::VariantRepository;
+use crate::services::barcode_service::parse_embedded_ean13;
+use crate::services::config_service::ConfigService;
+use chrono::NaiveDate;
 use sqlx::SqlitePool;
 
+const DEFAULT_SEARCH_PAGE_SIZE: i64 = 50;
+const MAX_SEARCH_PAGE_SIZE: i64 = 200;
+
 pub struct InventoryService {
     pub inventory_repo: InventoryRepository,
+    pub catalog_repo: CatalogRepository,
+    pub variant_repo: VariantRepository,
+    pub stock_movement_repo: StockMovementRepository,
+    pub product_cost_repo: ProductCostRepository,
+    pub config_service: ConfigService,
 }
 
 impl InventoryService {
     pub fn new(pool: SqlitePool) -> Self {
         Self {
-            inventory_repo: InventoryRepository::new(pool),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            catalog_repo: CatalogRepository::new(pool.clone()),
+            variant_repo: VariantRepository::new(pool.clone()),
+            stock_movement_repo: StockMovementRepository::new(pool.clone()),
+            product_cost_repo: ProductCostRepository::new(pool.clone()),
+            config_service: ConfigService::new(pool),
         }
     }
 
-    pub async fn get_categories(&self) -> Result<Vec<Category>, String> {
+    /// Category tree; with a store, only shared categories and the store's own.
+    pub async fn get_categories(&self, store_id: Option<i64>) -> Result<Vec<CategoryNode>, String> {
         self.inventory_repo
-            .get_categories()
+            .get_categories(store_id)
             .await
             .map_err(|e| e.to_string())
     }
 
-    pub async fn create_category(&self, name: &str) -> Result<Category, String> {
+    pub async fn create_category(
+        &self,
+        name: &str,
+        parent_id: Option<i64>,
+        store_id: Option<i64>,
+    ) -> Result<Category, String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("El nombre de la categoría es obligatorio".to_string());
+        }
+        if let Some(parent_id) = parent_id {
+            self.check_parent(parent_id, store_id).await?;
+        }
         self.inventory_repo
-            .create_category(name)
+            .create_category(name, parent_id, store_id)
             .await
-            .map_err(|e| e.to_string())
+            .map_err(category_error)
     }
 
     pub async fn update_category(&self, id: i64, name: &str) -> Result<(), String> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err("El nombre de la categoría es obligatorio".to_string());
+        }
         self.inventory_repo
             .update_category(id, name)
             .await
+            .map_err(category_error)
+    }
+
+    /// Moves the category (with its subtree) under `parent_id`, or to the top
+    /// level with `None`. Without `sort_order` it keeps its current one.
+    pub async fn move_category(
+        &self,
+        id: i64,
+        parent_id: Option<i64>,
+        sort_order: Option<i64>,
+    ) -> Result<(), String> {
+        let category = self.existing_category(id).await?;
+        if let Some(parent_id) = parent_id {
+            let in_subtree = self
+                .inventory_repo
+                .is_in_subtree(id, parent_id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if in_subtree {
+                return Err("Una categoría no puede moverse dentro de sí misma".to_string());
+            }
+            self.check_parent(parent_id, category.store_id).await?;
+        }
+        self.inventory_repo
+            .move_category(id, parent_id, sort_order.unwrap_or(category.sort_order))
+            .await
+            .map_err(category_error)
+    }
+
+    /// Sets the display order of sibling categories to the order given.
+    pub async fn reorder_categories(&self, ids: &[i64]) -> Result<(), String> {
+        self.inventory_repo
+            .reorder_categories(ids)
+            .await
             .map_err(|e| e.to_string())
     }
 
-    pub async fn delete_category(&self, id: i64) -> Result<(), String> {
+    /// Deletes the category. If products or styles use it they must be moved
+    /// to `reassign_to`; its subcategories move up one level.
+    pub async fn delete_category(&self, id: i64, reassign_to: Option<i64>) -> Result<(), String> {
+        self.existing_category(id).await?;
+        let usage = self
+            .inventory_repo
+            .count_category_usage(id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match reassign_to {
+            None if usage > 0 => {
+                return Err(format!(
+                    "La categoría tiene {} producto(s); elija a qué categoría reasignarlos",
+                    usage
+                ));
+            }
+            Some(target_id) if target_id == id => {
+                return Err("Elija una categoría distinta para reasignar los productos".to_string());
+            }
+            Some(target_id) => {
+                let target = self.existing_category(target_id).await?;
+                if let Some(store_id) = target.store_id {
+                    let outside = self
+                        .inventory_repo
+                        .count_category_usage_outside_store(id, store_id)
+                        .await
+                        .map_err(|e| e.to_string())?;
+                    if outside > 0 {
+                        return Err(
+                            "La categoría destino es de otra tienda que algunos de los productos".to_string(),
+                        );
+                    }
+                }
+            }
+            None => {}
+        }
+
+        self.inventory_repo
+            .delete_category(id, reassign_to)
+            .await
+            .map_err(category_error)
+    }
+
+    /// Products of the store in the category and all its subcategories.
+    pub async fn get_category_products(
+        &self,
+        store_id: i64,
+        category_id: i64,
+    ) -> Result<Vec<ProductWithCategory>, String> {
         self.inventory_repo
-            .delete_category(id)
+            .get_products_in_category(store_id, category_id)
             .await
             .map_err(|e| e.to_string())
     }
 
+    async fn existing_category(&self, id: i64) -> Result<Category, String> {
+        self.inventory_repo
+            .find_category(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Categoría no encontrada".to_string())
+    }
+
+    /// A shared category can only hang from shared ones; a store category
+    /// from shared ones or from its own store's.
+    async fn check_parent(&self, parent_id: i64, store_id: Option<i64>) -> Result<(), String> {
+        let parent = self.existing_category(parent_id).await?;
+        match (parent.store_id, store_id) {
+            (None, _) => Ok(()),
+            (Some(parent_store), Some(store)) if parent_store == store => Ok(()),
+            _ => Err("La categoría padre pertenece a otra tienda".to_string()),
+        }
+    }
+
+    async fn check_category(&self, category_id: Option<i64>, store_id: i64) -> Result<(), String> {
+        let Some(category_id) = category_id else {
+            return Ok(());
+        };
+        let visible = self
+            .inventory_repo
+            .category_visible(category_id, store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if visible {
+            Ok(())
+        } else {
+            Err("La categoría no existe o pertenece a otra tienda".to_string())
+        }
+    }
+
     pub async fn get_products(&self, store_id: i64) -> Result<Vec<ProductWithCategory>, String> {
         self.inventory_repo
             .get_products(store_id)
@@ -48,37 +208,170 @@ impl InventoryService {
             .map_err(|e| e.to_string())
     }
 
+    /// Ranked search by name, code, barcode, category, size and color. Every
+    /// word must match (as a prefix), ignoring accents and case.
+    pub async fn search_products(
+        &self,
+        store_id: i64,
+        query: &str,
+        page: Option<i64>,
+        page_size: Option<i64>,
+    ) -> Result<ProductSearchPage, String> {
+        let match_query = fts_match_query(query);
+        if match_query.is_empty() {
+            return Err("Ingrese un término de búsqueda".to_string());
+        }
+        let page = page.unwrap_or(1).max(1);
+        let page_size = page_size
+            .unwrap_or(DEFAULT_SEARCH_PAGE_SIZE)
+            .clamp(1, MAX_SEARCH_PAGE_SIZE);
+
+        let total = self
+            .inventory_repo
+            .count_search_products(store_id, &match_query)
+            .await
+            .map_err(|e| e.to_string())?;
+        let items = self
+            .inventory_repo
+            .search_products(store_id, &match_query, page_size, (page - 1) * page_size)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(ProductSearchPage {
+            items,
+            total,
+            page,
+            page_size,
+        })
+    }
+
+    /// Resolves a scanned or typed code: barcode (variants included), then
+    /// product code, then variable-measure labels (EAN-13 prefixes 21-29) whose
+    /// item code is looked up as the product code. `embedded_barcode_mode`
+    /// tells whether their value is the price in céntimos ("price", default)
+    /// or the weight in grams ("weight", priced per kilogram).
+    pub async fn lookup_product(
+        &self,
+        store_id: i64,
+        code: &str,
+    ) -> Result<Option<ProductLookup>, String> {
+        let code = code.trim();
+        if code.is_empty() {
+            return Err("Ingrese un código".to_string());
+        }
+
+        if let Some(product) = self
+            .inventory_repo
+            .find_sellable(code, store_id)
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            let matched_by = if product.barcode.as_deref() == Some(code) {
+                "barcode"
+            } else {
+                "code"
+            };
+            return Ok(Some(ProductLookup {
+                unit_price: product.price,
+                product,
+                matched_by: matched_by.to_string(),
+                embedded_weight_grams: None,
+            }));
+        }
+
+        let Some((item_code, value)) = parse_embedded_ean13(code) else {
+            return Ok(None);
+        };
+        // Scales pad the item code with zeros; the product code may not be
+        let mut product = self
+            .inventory_repo
+            .find_sellable(item_code, store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let unpadded = item_code.trim_start_matches('0');
+        if product.is_none() && !unpadded.is_empty() && unpadded != item_code {
+            product = self
+                .inventory_repo
+                .find_sellable(unpadded, store_id)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        let Some(product) = product else {
+            return Ok(None);
+        };
+
+        let mode = self
+            .config_service
+            .get_config("embedded_barcode_mode")
+            .await?
+            .unwrap_or_else(|| "price".to_string());
+        let (unit_price, embedded_weight_grams) = match mode.as_str() {
+            "weight" => (product.price.mul_div(value, 1000), Some(value)),
+            _ => (Money::from_cents(value), None),
+        };
+
+        Ok(Some(ProductLookup {
+            product,
+            matched_by: "embedded".to_string(),
+            unit_price,
+            embedded_weight_grams,
+        }))
+    }
+
     pub async fn create_product(
         &self,
         code: Option<&str>,
         name: &str,
         category_id: Option<i64>,
-        price: f64,
-        cost: f64,
+        price: Money,
+        cost: Money,
         stock: i64,
+        min_stock: Option<i64>,
         unit: Option<&str>,
         image_url: Option<&str>,
         store_id: i64,
+        user_id: Option<i64>,
     ) -> Result<i64, String> {
+        self.check_category(category_id, store_id).await?;
+        if let Some(code) = code {
+            ensure_catalog_match(&self.catalog_repo, code, name, unit, None, None).await?;
+        }
         self.inventory_repo
-            .create_product(code, name, category_id, price, cost, stock, unit, image_url, store_id)
+            .create_product(
+                code,
+                name,
+                category_id,
+                price,
+                cost,
+                stock,
+                min_stock,
+                unit,
+                image_url,
+                store_id,
+                &MovementSource::manual("adjustment", user_id, "Stock inicial"),
+            )
             .await
-            .map_err(|e| e.to_string())
+            .map_err(product_error)
     }
 
+    /// Edits the product data. Stock is not touched here: it only changes
+    /// through sales, purchases, transfers and stock adjustments.
     pub async fn update_product(
         &self,
         id: i64,
         code: Option<&str>,
         name: &str,
         category_id: Option<i64>,
-        price: f64,
-        cost: f64,
-        stock: i64,
+        price: Money,
+        cost: Money,
+        min_stock: Option<i64>,
         unit: Option<&str>,
         image_url: Option<&str>,
         store_id: i64,
+        user_id: Option<i64>,
     ) -> Result<(), String> {
+        self.check_category(category_id, store_id).await?;
+        let source = MovementSource::manual("manual", user_id, "Edición del producto");
         self.inventory_repo
             .update_product(
                 id,
@@ -87,13 +380,30 @@ impl InventoryService {
                 category_id,
                 price,
                 cost,
-                stock,
+                min_stock,
                 unit,
                 image_url,
                 store_id,
+                &source,
             )
             .await
-            .map_err(|e| e.to_string())
+            .map_err(product_error)?;
+
+        // A variant's price is kept as an override of its style price
+        let is_variant = self
+            .inventory_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .map(|p| p.style_id.is_some())
+            .unwrap_or(false);
+        if is_variant {
+            self.variant_repo
+                .set_variant_price(id, price, &source)
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(())
     }
 
     pub async fn delete_product(&self, id: i64) -> Result<(), String> {
@@ -102,4 +412,122 @@ impl InventoryService {
             .await
             .map_err(|e| e.to_string())
     }
+
+    /// Stock movements of a product between two dates with opening and closing balances.
+    pub async fn get_kardex(
+        &self,
+        product_id: i64,
+        date_from: String,
+        date_to: String,
+    ) -> Result<Kardex, String> {
+        let from = NaiveDate::parse_from_str(&date_from, "%Y-%m-%d")
+            .map_err(|_| "Fecha inicial inválida".to_string())?;
+        let to = NaiveDate::parse_from_str(&date_to, "%Y-%m-%d")
+            .map_err(|_| "Fecha final inválida".to_string())?;
+        if from > to {
+            return Err("La fecha inicial no puede ser posterior a la final".to_string());
+        }
+
+        let product = self
+            .inventory_repo
+            .find_any_by_id(product_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Producto no encontrado")?;
+        let opening_balance = self
+            .stock_movement_repo
+            .balance_before(product_id, &date_from)
+            .await
+            .map_err(|e| e.to_string())?;
+        let movements = self
+            .stock_movement_repo
+            .find_by_product(product_id, &date_from, &date_to)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let units_in = movements.iter().map(|m| m.delta).filter(|d| *d > 0).sum();
+        let units_out = -movements.iter().map(|m| m.delta).filter(|d| *d < 0).sum::<i64>();
+        let closing_balance = movements.last().map(|m| m.balance).unwrap_or(opening_balance);
+
+        Ok(Kardex {
+            product_id,
+            product_code: product.code,
+            product_name: product.name,
+            date_from,
+            date_to,
+            opening_balance,
+            units_in,
+            units_out,
+            closing_balance,
+            movements,
+        })
+    }
+
+    /// Changes of the product's average cost, newest first.
+    pub async fn get_cost_history(&self, product_id: i64) -> Result<Vec<ProductCostChange>, String> {
+        self.product_cost_repo
+            .find_by_product(product_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Turns free text into an FTS5 expression: every word quoted (so user input
+/// can't inject operators) and required. Single letters match whole words only
+/// so sizes like "s" or "m" don't match every word starting with them.
+fn fts_match_query(text: &str) -> String {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| match word.chars().count() {
+            1 => format!("\"{}\"", word),
+            _ => format!("\"{}\"*", word),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A code belongs to one catalog item, whichever store uses it.
+/// A product created with a code already in the catalog is linked to that
+/// item and takes its data (see migration 031), so it must describe the same
+/// article: same name and size/color, and the same unit when one is given.
+pub(crate) async fn ensure_catalog_match(
+    catalog_repo: &CatalogRepository,
+    code: &str,
+    name: &str,
+    unit: Option<&str>,
+    size: Option<&str>,
+    color: Option<&str>,
+) -> Result<(), String> {
+    let Some(item) = catalog_repo.find_by_code(code).await.map_err(|e| e.to_string())? else {
+        return Ok(());
+    };
+    let same = item.name == name
+        && (unit.is_none() || item.unit.as_deref() == unit)
+        && item.size.as_deref() == size
+        && item.color.as_deref() == color;
+    if !same {
+        return Err(format!(
+            "El código {} ya pertenece a \"{}\" en el catálogo. Use los mismos datos para agregarlo a esta tienda o elija otro código",
+            code, item.name
+        ));
+    }
+    Ok(())
+}
+
+fn product_error(e: sqlx::Error) -> String {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("catalog_products.code") => {
+            "Ese código ya pertenece a otro producto del catálogo".to_string()
+        }
+        other => other.to_string(),
+    }
+}
+
+fn category_error(e: sqlx::Error) -> String {
+    match e {
+        sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+            "Ya existe una categoría con ese nombre en este nivel".to_string()
+        }
+        other => other.to_string(),
+    }
 }
@@ -0,0 +1,129 @@
+use crate::models::kit::{KitComponentSales, ProductKit, ProductKitDetail, SaveKitComponentsPayload};
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::kit_repo::KitRepository;
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+pub struct KitService {
+    pub kit_repo: KitRepository,
+    pub inventory_repo: InventoryRepository,
+}
+
+impl KitService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            kit_repo: KitRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool),
+        }
+    }
+
+    pub async fn get_product_kits(&self, store_id: i64) -> Result<Vec<ProductKit>, String> {
+        self.kit_repo
+            .find_all(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_product_kit(&self, kit_id: i64) -> Result<ProductKitDetail, String> {
+        let kit = self
+            .kit_repo
+            .find_by_id(kit_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Pack no encontrado")?;
+        let components = self
+            .kit_repo
+            .find_components(kit_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(ProductKitDetail { kit, components })
+    }
+
+    /// Makes the product a pack of the given components (products of the same
+    /// store), or a regular product again with no components. Packs can't be
+    /// nested, and a product only becomes a pack once its own stock is zero.
+    pub async fn save_kit_components(&self, payload: SaveKitComponentsPayload) -> Result<Option<ProductKitDetail>, String> {
+        let kit = self
+            .inventory_repo
+            .find_by_id(payload.kit_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Producto no encontrado")?;
+
+        if !payload.components.is_empty() {
+            if kit.style_id.is_some() {
+                return Err("Una variante no puede ser un pack".to_string());
+            }
+            if !kit.is_kit && kit.stock != 0 {
+                return Err(format!(
+                    "{} tiene stock propio ({}): llévelo a cero antes de convertirlo en pack",
+                    kit.name, kit.stock
+                ));
+            }
+            if self
+                .kit_repo
+                .is_component(kit.id)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                return Err(format!("{} es componente de otro pack", kit.name));
+            }
+        }
+
+        let mut seen = HashSet::new();
+        for item in &payload.components {
+            if item.quantity <= 0 {
+                return Err("La cantidad de cada componente debe ser mayor a cero".to_string());
+            }
+            if item.product_id == kit.id {
+                return Err("Un pack no puede contenerse a sí mismo".to_string());
+            }
+            if !seen.insert(item.product_id) {
+                return Err(format!("Producto #{} repetido", item.product_id));
+            }
+            let component = self
+                .inventory_repo
+                .find_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == kit.store_id)
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", item.product_id))?;
+            if component.is_kit {
+                return Err(format!("{} es un pack: no puede ser componente de otro", component.name));
+            }
+        }
+
+        self.kit_repo
+            .save_components(kit.id, &payload.components)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if payload.components.is_empty() {
+            Ok(None)
+        } else {
+            self.get_product_kit(kit.id).await.map(Some)
+        }
+    }
+
+    /// Products sold inside packs in a period, with the part of the pack
+    /// price allocated to each and its margin.
+    pub async fn get_kit_component_sales(
+        &self,
+        store_id: i64,
+        date_from: String,
+        date_to: String,
+    ) -> Result<Vec<KitComponentSales>, String> {
+        let from = NaiveDate::parse_from_str(&date_from, "%Y-%m-%d")
+            .map_err(|_| "Fecha inicial inválida".to_string())?;
+        let to = NaiveDate::parse_from_str(&date_to, "%Y-%m-%d")
+            .map_err(|_| "Fecha final inválida".to_string())?;
+        if from > to {
+            return Err("La fecha inicial no puede ser posterior a la final".to_string());
+        }
+        self.kit_repo
+            .component_sales(store_id, &date_from, &date_to)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
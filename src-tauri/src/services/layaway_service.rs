@@ -0,0 +1,302 @@
+use crate::models::layaway::{
+    AddLayawayPaymentPayload, CreateLayawayPayload, Layaway, LayawayDetail,
+};
+use crate::models::money::Money;
+use crate::models::sales::{CreateOrderItemPayload, CreateOrderPayload};
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::layaway_repo::LayawayRepository;
+use crate::services::config_service::ConfigService;
+use crate::services::price_list_service::PriceListService;
+use crate::services::sales_service::split_igv;
+use chrono::{Duration, Local};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+const DEFAULT_EXPIRY_DAYS: i64 = 30;
+
+/// POS payment methods a deposit can be paid with: gift cards and store
+/// credit are only redeemed by sales.
+const DEPOSIT_PAYMENT_METHODS: &[&str] = &["cash", "card", "yape"];
+
+pub struct LayawayService {
+    pub layaway_repo: LayawayRepository,
+    pub inventory_repo: InventoryRepository,
+    pub cash_repo: CashRepository,
+    pub config_service: ConfigService,
+    pub price_list_service: PriceListService,
+}
+
+impl LayawayService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            layaway_repo: LayawayRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            cash_repo: CashRepository::new(pool.clone()),
+            config_service: ConfigService::new(pool.clone()),
+            price_list_service: PriceListService::new(pool),
+        }
+    }
+
+    pub async fn create_layaway(&self, payload: CreateLayawayPayload) -> Result<LayawayDetail, String> {
+        if payload.items.is_empty() {
+            return Err("La separación debe tener al menos un producto".to_string());
+        }
+        if payload.client_name.trim().is_empty() {
+            return Err("El nombre del cliente es obligatorio".to_string());
+        }
+        if payload.deposit.is_negative() {
+            return Err("El abono inicial no puede ser negativo".to_string());
+        }
+        if payload.deposit.is_positive()
+            && !DEPOSIT_PAYMENT_METHODS.contains(&payload.payment_method.as_str())
+        {
+            return Err("Método de pago no válido para el abono".to_string());
+        }
+
+        let mut items = Vec::new();
+        for item in &payload.items {
+            if item.quantity <= 0 {
+                return Err("La cantidad debe ser mayor a cero".to_string());
+            }
+            let product = self
+                .inventory_repo
+                .find_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == Some(payload.store_id))
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", item.product_id))?;
+
+            let available = self
+                .inventory_repo
+                .sellable_stock(product.id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if available < item.quantity {
+                return Err(format!(
+                    "Stock insuficiente para {}: disponible {}, requerido {}",
+                    product.name, available, item.quantity
+                ));
+            }
+
+            items.push(CreateOrderItemPayload {
+                product_id: product.id,
+                product_name: product.name,
+                unit_price: product.price,
+                quantity: item.quantity,
+                subtotal: product.price.times(item.quantity),
+                price_list_id: None,
+            });
+        }
+
+        self.price_list_service
+            .price_items(
+                &mut items,
+                payload.price_list_id,
+                payload.client_document.as_deref(),
+                payload.client_phone.as_deref(),
+            )
+            .await?;
+
+        let total: Money = items.iter().map(|i| i.subtotal).sum();
+        if payload.deposit >= total {
+            return Err("El abono cubre el total; registre una venta normal".to_string());
+        }
+
+        let cash_session_id = if payload.deposit.is_positive() {
+            Some(self.open_session_id(payload.store_id).await?)
+        } else {
+            None
+        };
+
+        let expires_at = (Local::now().naive_local() + Duration::days(self.expiry_days().await?))
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+
+        let id = self
+            .layaway_repo
+            .create_layaway(
+                &Uuid::new_v4().to_string(),
+                &payload,
+                &items,
+                total,
+                &expires_at,
+                cash_session_id,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.get_layaway_detail(id)
+            .await?
+            .ok_or_else(|| "Separación no encontrada".to_string())
+    }
+
+    /// Books a deposit into the store's open cash session. The deposit that
+    /// covers the remaining balance completes the layaway into a sale.
+    pub async fn add_payment(&self, payload: AddLayawayPaymentPayload) -> Result<LayawayDetail, String> {
+        let layaway = self.active_layaway(payload.layaway_id).await?;
+
+        let remaining = layaway.total - layaway.paid_amount;
+        if !payload.amount.is_positive() {
+            return Err("El abono debe ser mayor a cero".to_string());
+        }
+        if payload.amount > remaining {
+            return Err(format!("El abono excede el saldo pendiente (S/ {})", remaining));
+        }
+        if !DEPOSIT_PAYMENT_METHODS.contains(&payload.payment_method.as_str()) {
+            return Err("Método de pago no válido para el abono".to_string());
+        }
+
+        let cash_session_id = self.open_session_id(layaway.store_id).await?;
+
+        // The sale is only created if this deposit turns out to pay it off
+        let sale = {
+            let items = self
+                .layaway_repo
+                .find_items(layaway.id)
+                .await
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .map(|item| CreateOrderItemPayload {
+                    product_id: item.product_id,
+                    product_name: item.product_name,
+                    unit_price: item.unit_price,
+                    quantity: item.quantity,
+                    subtotal: item.subtotal,
+                    price_list_id: item.price_list_id,
+                })
+                .collect();
+            let (subtotal, igv) = split_igv(layaway.total);
+
+            CreateOrderPayload {
+                user_id: payload.user_id,
+                client_document: layaway.client_document.clone(),
+                client_phone: layaway.client_phone.clone(),
+                client_name: Some(layaway.client_name.clone()),
+                payment_method: "layaway".to_string(),
+                items,
+                subtotal,
+                igv,
+                total: layaway.total,
+                cash_session_id,
+                store_id: layaway.store_id,
+                document_type: None,
+                stored_value_code: None,
+                stored_value_amount: None,
+                loyalty_points: None,
+                price_list_id: None,
+            }
+        };
+
+        self.layaway_repo
+            .add_payment(
+                &layaway,
+                cash_session_id,
+                payload.amount,
+                &payload.payment_method,
+                payload.user_id,
+                &sale,
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    "Stock insuficiente para uno o más productos".to_string()
+                }
+                other => other.to_string(),
+            })?;
+
+        self.get_layaway_detail(layaway.id)
+            .await?
+            .ok_or_else(|| "Separación no encontrada".to_string())
+    }
+
+    /// Cancels an active layaway and releases its stock. Refunding deposits is
+    /// left to the cashier (as a cash expense).
+    pub async fn cancel_layaway(&self, id: i64) -> Result<(), String> {
+        let layaway = self.active_layaway(id).await?;
+        self.layaway_repo
+            .update_status(layaway.id, "cancelled")
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_layaways(&self, store_id: i64, status: Option<&str>) -> Result<Vec<Layaway>, String> {
+        self.layaway_repo
+            .expire_overdue(store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.layaway_repo
+            .find_by_store(store_id, status)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_layaway_detail(&self, id: i64) -> Result<Option<LayawayDetail>, String> {
+        let layaway = self
+            .layaway_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match layaway {
+            Some(layaway) => {
+                let items = self
+                    .layaway_repo
+                    .find_items(layaway.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                let payments = self
+                    .layaway_repo
+                    .find_payments(layaway.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(LayawayDetail {
+                    layaway,
+                    items,
+                    payments,
+                }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn active_layaway(&self, id: i64) -> Result<Layaway, String> {
+        let layaway = self
+            .layaway_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Separación no encontrada".to_string())?;
+
+        let now = Local::now().naive_local().format("%Y-%m-%d %H:%M:%S").to_string();
+        if layaway.status == "active" && layaway.expires_at < now {
+            self.layaway_repo
+                .update_status(layaway.id, "expired")
+                .await
+                .map_err(|e| e.to_string())?;
+            return Err("La separación ha vencido y el stock fue liberado".to_string());
+        }
+        if layaway.status != "active" {
+            return Err(format!("La separación está en estado '{}'", layaway.status));
+        }
+        Ok(layaway)
+    }
+
+    async fn open_session_id(&self, store_id: i64) -> Result<i64, String> {
+        self.cash_repo
+            .get_active_session(store_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .map(|s| s.id)
+            .ok_or_else(|| "No hay una caja abierta para registrar el abono".to_string())
+    }
+
+    async fn expiry_days(&self) -> Result<i64, String> {
+        Ok(self
+            .config_service
+            .get_config("layaway_expiry_days")
+            .await?
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(DEFAULT_EXPIRY_DAYS))
+    }
+}
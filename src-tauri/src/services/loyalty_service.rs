@@ -0,0 +1,109 @@
+use crate::models::loyalty::{CategoryMultiplier, CustomerPoints, LoyaltySettings};
+use crate::repositories::loyalty_repo::LoyaltyRepository;
+use crate::services::config_service::ConfigService;
+use sqlx::SqlitePool;
+
+pub struct LoyaltyService {
+    pub loyalty_repo: LoyaltyRepository,
+    pub config_service: ConfigService,
+}
+
+impl LoyaltyService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            loyalty_repo: LoyaltyRepository::new(pool.clone()),
+            config_service: ConfigService::new(pool),
+        }
+    }
+
+    /// Looks up a customer by DNI/RUC or phone and returns its points balance.
+    /// Lots past their expiry date are written off first.
+    pub async fn get_customer_points(
+        &self,
+        document: Option<String>,
+        phone: Option<String>,
+    ) -> Result<CustomerPoints, String> {
+        let document = document.filter(|d| !d.trim().is_empty());
+        let phone = phone.filter(|p| !p.trim().is_empty());
+        if document.is_none() && phone.is_none() {
+            return Err("Ingrese el DNI/RUC o el teléfono del cliente".to_string());
+        }
+
+        let customer = self
+            .loyalty_repo
+            .find_customer(document.as_deref(), phone.as_deref())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Cliente no encontrado")?;
+
+        self.loyalty_repo
+            .expire_points(customer.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let settings = self.get_settings().await?;
+        let balance = self
+            .loyalty_repo
+            .balance(customer.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let entries = self
+            .loyalty_repo
+            .find_entries(customer.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(CustomerPoints {
+            customer,
+            balance,
+            balance_value: settings.value_of(balance),
+            entries,
+        })
+    }
+
+    pub async fn get_settings(&self) -> Result<LoyaltySettings, String> {
+        self.loyalty_repo
+            .get_settings()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_settings(&self, settings: LoyaltySettings) -> Result<(), String> {
+        if settings.points_per_sol < 0.0 || settings.point_value < 0.0 {
+            return Err("Los valores del programa de puntos no pueden ser negativos".to_string());
+        }
+        if settings.expiry_days <= 0 {
+            return Err("Los días de vigencia deben ser mayores a cero".to_string());
+        }
+
+        self.config_service
+            .set_config("loyalty_enabled", if settings.enabled { "1" } else { "0" })
+            .await?;
+        self.config_service
+            .set_config("loyalty_points_per_sol", &settings.points_per_sol.to_string())
+            .await?;
+        self.config_service
+            .set_config("loyalty_point_value", &settings.point_value.to_string())
+            .await?;
+        self.config_service
+            .set_config("loyalty_expiry_days", &settings.expiry_days.to_string())
+            .await
+    }
+
+    pub async fn get_category_multipliers(&self) -> Result<Vec<CategoryMultiplier>, String> {
+        self.loyalty_repo
+            .get_multipliers()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_category_multiplier(&self, category_id: i64, multiplier: f64) -> Result<(), String> {
+        if multiplier < 0.0 {
+            return Err("El multiplicador no puede ser negativo".to_string());
+        }
+        self.loyalty_repo
+            .set_multiplier(category_id, multiplier)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
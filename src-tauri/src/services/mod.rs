@@ -1,6 +1,24 @@
 pub mod auth_service;
+pub mod barcode_service;
 pub mod cash_service;
+pub mod catalog_service;
+pub mod commission_service;
 pub mod config_service;
+pub mod inventory_count_service;
 pub mod inventory_service;
+pub mod kit_service;
+pub mod layaway_service;
+pub mod loyalty_service;
+pub mod pdf_service;
+pub mod price_change_service;
+pub mod price_list_service;
+pub mod product_image_service;
+pub mod product_import_service;
 pub mod purchase_order_service;
+pub mod quotation_service;
+pub mod reorder_service;
 pub mod sales_service;
+pub mod stock_adjustment_service;
+pub mod stock_transfer_service;
+pub mod stored_value_service;
+pub mod variant_service;
@@ -0,0 +1,768 @@
+use crate::models::barcode::LabelProduct;
+use crate::models::cash::CashSession;
+use crate::models::money::Money;
+use crate::models::sales::{Sale, SaleDetail};
+use crate::models::store::Store;
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::sales_repo::SalesRepository;
+use crate::repositories::store_repo::StoreRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::barcode_service::{code128_modules, ean13_modules, is_ean13};
+use printpdf::{
+    image_crate, BuiltinFont, Image, ImageTransform, IndirectFontRef, Line, Mm,
+    PdfDocument, PdfDocumentReference, PdfLayerReference, Point, Rect,
+};
+use printpdf::path::PaintMode;
+use qrcode::QrCode;
+use sqlx::SqlitePool;
+use std::fs::{self, File};
+use std::io::BufWriter;
+use std::path::Path;
+
+const IGV_RATE_LABEL: &str = "IGV (18%)";
+
+/// A4 label paper: 3 columns x 8 rows of 70 x 37.125 mm, no margins.
+const LABEL_COLUMNS: usize = 3;
+const LABEL_ROWS: usize = 8;
+const LABEL_WIDTH: f32 = 70.0;
+const LABEL_HEIGHT: f32 = 37.125;
+
+pub struct PdfService {
+    pub sales_repo: SalesRepository,
+    pub cash_repo: CashRepository,
+    pub store_repo: StoreRepository,
+    pub user_repo: UserRepository,
+}
+
+impl PdfService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            sales_repo: SalesRepository::new(pool.clone()),
+            cash_repo: CashRepository::new(pool.clone()),
+            store_repo: StoreRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool),
+        }
+    }
+
+    /// Renders a sale as an 80mm ticket ("ticket") or an A4 invoice ("a4")
+    /// inside `output_dir` and returns the path of the written file.
+    pub async fn generate_sale_pdf(
+        &self,
+        sale_id: i64,
+        layout: &str,
+        output_dir: &Path,
+    ) -> Result<String, String> {
+        let detail = self
+            .sales_repo
+            .get_sale_detail(sale_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Venta no encontrada".to_string())?;
+
+        let store = self
+            .store_repo
+            .find_by_id(detail.sale.store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let canvas = match layout {
+            "ticket" => render_ticket(&detail, store.as_ref())?,
+            "a4" => render_invoice(&detail, store.as_ref())?,
+            _ => return Err("Formato inválido. Debe ser: ticket o a4".to_string()),
+        };
+
+        let file_name = format!("{}_{}.pdf", document_identifier(&detail.sale), layout);
+        canvas.save(&output_dir.join("sales"), &file_name)
+    }
+
+    /// Renders the closing report of a cash session and returns the path of the written file.
+    pub async fn generate_cash_session_report(
+        &self,
+        session_id: i64,
+        output_dir: &Path,
+    ) -> Result<String, String> {
+        let session = self
+            .cash_repo
+            .get_session_by_id(session_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Caja no encontrada".to_string())?;
+
+        let transactions = self
+            .cash_repo
+            .get_session_transactions(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let store = self
+            .store_repo
+            .find_by_id(session.store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let opened_by = self.username(session.opened_by).await?;
+        let closed_by = match session.closed_by {
+            Some(id) => Some(self.username(id).await?),
+            None => None,
+        };
+
+        let canvas = render_cash_report(
+            &session,
+            &transactions,
+            store.as_ref(),
+            &opened_by,
+            closed_by.as_deref(),
+        )?;
+
+        let file_name = format!("cierre_caja_{:06}.pdf", session.id);
+        canvas.save(&output_dir.join("cash_sessions"), &file_name)
+    }
+
+    async fn username(&self, user_id: i64) -> Result<String, String> {
+        let user = self
+            .user_repo
+            .find_user_by_id(user_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(user
+            .map(|u| u.username)
+            .unwrap_or_else(|| format!("Usuario #{}", user_id)))
+    }
+}
+
+/// "B001-00000042" for numbered documents, "TICKET-00000042" for sales made
+/// before series numbering existed.
+pub fn document_identifier(sale: &Sale) -> String {
+    match (&sale.series, sale.number) {
+        (Some(series), Some(number)) => format!("{}-{:08}", series, number),
+        _ => format!("TICKET-{:08}", sale.id),
+    }
+}
+
+fn document_title(sale: &Sale) -> &'static str {
+    match sale.document_type.as_deref() {
+        Some("factura") => "FACTURA ELECTRÓNICA",
+        _ => "BOLETA DE VENTA ELECTRÓNICA",
+    }
+}
+
+/// QR content as defined by SUNAT for printed electronic receipts:
+/// RUC | tipo | serie | número | IGV | total | fecha | tipo doc. cliente | doc. cliente |
+fn fiscal_qr_payload(sale: &Sale, store: Option<&Store>) -> String {
+    let ruc = store.and_then(|s| s.ruc.as_deref()).unwrap_or("");
+    let doc_code = match sale.document_type.as_deref() {
+        Some("factura") => "01",
+        _ => "03",
+    };
+    let (client_doc_type, client_doc) = match sale.client_document.as_deref() {
+        Some(doc) if doc.len() == 11 => ("6", doc),
+        Some(doc) if !doc.is_empty() => ("1", doc),
+        _ => ("-", "-"),
+    };
+    let date = sale.created_at.get(..10).unwrap_or(&sale.created_at);
+
+    format!(
+        "{}|{}|{}|{}|{}|{}|{}|{}|{}|",
+        ruc,
+        doc_code,
+        sale.series.as_deref().unwrap_or(""),
+        sale.number.map(|n| n.to_string()).unwrap_or_default(),
+        sale.igv,
+        sale.total,
+        date,
+        client_doc_type,
+        client_doc
+    )
+}
+
+fn payment_method_label(method: &str) -> &str {
+    match method {
+        "cash" => "Efectivo",
+        "card" => "Tarjeta",
+        "yape" => "Yape",
+        "virtual" => "Virtual",
+        other => other,
+    }
+}
+
+fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        text.to_string()
+    } else {
+        let mut s: String = text.chars().take(max_chars.saturating_sub(1)).collect();
+        s.push('…');
+        s
+    }
+}
+
+fn render_ticket(detail: &SaleDetail, store: Option<&Store>) -> Result<Canvas, String> {
+    let sale = &detail.sale;
+    let height = 150.0 + detail.items.len() as f32 * 9.0;
+    let mut c = Canvas::new(&document_identifier(sale), 80.0, height, 4.0)?;
+
+    if let Some(path) = store.and_then(|s| s.logo_path.as_deref()) {
+        let logo_height = c.logo(path, c.margin, c.y, 30.0);
+        c.advance(logo_height + 2.0);
+    }
+
+    c.advance(4.0);
+    let store_name = store.map(|s| s.name.as_str()).unwrap_or("");
+    c.text(&truncate(store_name, 34), 10.0, c.margin, Style::Bold);
+    if let Some(ruc) = store.and_then(|s| s.ruc.as_deref()) {
+        c.advance(4.0);
+        c.text(&format!("RUC: {}", ruc), 8.0, c.margin, Style::Regular);
+    }
+    if let Some(address) = store.and_then(|s| s.address.as_deref()) {
+        c.advance(4.0);
+        c.text(&truncate(address, 48), 7.0, c.margin, Style::Regular);
+    }
+
+    c.advance(7.0);
+    c.text(document_title(sale), 8.0, c.margin, Style::Bold);
+    c.advance(4.0);
+    c.text(&document_identifier(sale), 9.0, c.margin, Style::Bold);
+    c.advance(5.0);
+    c.text(&format!("Fecha: {}", sale.created_at), 7.0, c.margin, Style::Regular);
+    if let Some(name) = sale.client_name.as_deref() {
+        c.advance(3.5);
+        c.text(&format!("Cliente: {}", truncate(name, 40)), 7.0, c.margin, Style::Regular);
+    }
+    if let Some(doc) = sale.client_document.as_deref() {
+        c.advance(3.5);
+        c.text(&format!("Doc.: {}", doc), 7.0, c.margin, Style::Regular);
+    }
+
+    c.advance(3.0);
+    c.rule();
+    for item in &detail.items {
+        c.advance(4.0);
+        c.text(&truncate(&item.product_name, 44), 7.0, c.margin, Style::Regular);
+        c.advance(3.5);
+        c.text(
+            &format!("{} x {}", item.quantity, item.unit_price),
+            7.0,
+            c.margin + 2.0,
+            Style::Regular,
+        );
+        c.amount(item.subtotal, 7.0, c.width - c.margin);
+    }
+    c.advance(2.0);
+    c.rule();
+
+    c.advance(4.5);
+    c.text("Op. Gravada", 8.0, c.margin, Style::Regular);
+    c.amount(sale.subtotal, 8.0, c.width - c.margin);
+    c.advance(4.0);
+    c.text(IGV_RATE_LABEL, 8.0, c.margin, Style::Regular);
+    c.amount(sale.igv, 8.0, c.width - c.margin);
+    c.advance(4.5);
+    c.text("TOTAL S/", 9.0, c.margin, Style::Bold);
+    c.amount(sale.total, 9.0, c.width - c.margin);
+    c.advance(5.0);
+    c.text(
+        &format!("Pago: {}", payment_method_label(&sale.payment_method)),
+        7.0,
+        c.margin,
+        Style::Regular,
+    );
+    if let Some(seller) = sale.user_name.as_deref() {
+        c.advance(3.5);
+        c.text(&format!("Vendedor: {}", seller), 7.0, c.margin, Style::Regular);
+    }
+
+    c.advance(34.0);
+    c.qr(&fiscal_qr_payload(sale, store), (c.width - 30.0) / 2.0, c.y, 30.0)?;
+    c.advance(5.0);
+    c.text("Gracias por su compra", 8.0, c.margin + 16.0, Style::Regular);
+
+    Ok(c)
+}
+
+fn render_invoice(detail: &SaleDetail, store: Option<&Store>) -> Result<Canvas, String> {
+    let sale = &detail.sale;
+    let mut c = Canvas::new(&document_identifier(sale), 210.0, 297.0, 15.0)?;
+    let right = c.width - c.margin;
+
+    // Header: logo and store on the left, fiscal box on the right
+    let top = c.y;
+    let mut header_bottom = top;
+    if let Some(path) = store.and_then(|s| s.logo_path.as_deref()) {
+        let logo_height = c.logo(path, c.margin, top, 40.0);
+        header_bottom = top - logo_height - 2.0;
+    }
+    c.y = header_bottom - 5.0;
+    c.text(
+        store.map(|s| s.name.as_str()).unwrap_or(""),
+        12.0,
+        c.margin,
+        Style::Bold,
+    );
+    if let Some(address) = store.and_then(|s| s.address.as_deref()) {
+        c.advance(5.0);
+        c.text(&truncate(address, 60), 9.0, c.margin, Style::Regular);
+    }
+
+    let box_left = right - 70.0;
+    c.frame(box_left, top - 28.0, right, top);
+    if let Some(ruc) = store.and_then(|s| s.ruc.as_deref()) {
+        c.text_at(&format!("RUC {}", ruc), 11.0, box_left + 5.0, top - 8.0, Style::Bold);
+    }
+    c.text_at(document_title(sale), 9.0, box_left + 5.0, top - 16.0, Style::Bold);
+    c.text_at(&document_identifier(sale), 11.0, box_left + 5.0, top - 24.0, Style::Bold);
+
+    // Customer block
+    c.y = c.y.min(top - 34.0) - 8.0;
+    c.text("Señor(es):", 9.0, c.margin, Style::Bold);
+    c.text(sale.client_name.as_deref().unwrap_or("-"), 9.0, c.margin + 25.0, Style::Regular);
+    c.advance(5.0);
+    c.text("RUC / DNI:", 9.0, c.margin, Style::Bold);
+    c.text(sale.client_document.as_deref().unwrap_or("-"), 9.0, c.margin + 25.0, Style::Regular);
+    c.advance(5.0);
+    c.text("Fecha:", 9.0, c.margin, Style::Bold);
+    c.text(&sale.created_at, 9.0, c.margin + 25.0, Style::Regular);
+    c.text("Moneda:", 9.0, c.margin + 100.0, Style::Bold);
+    c.text("Soles (PEN)", 9.0, c.margin + 120.0, Style::Regular);
+    c.advance(5.0);
+    c.text("Forma de pago:", 9.0, c.margin, Style::Bold);
+    c.text(payment_method_label(&sale.payment_method), 9.0, c.margin + 25.0, Style::Regular);
+
+    // Item table
+    c.advance(8.0);
+    invoice_table_header(&mut c);
+    for item in &detail.items {
+        if c.y < 75.0 {
+            c.new_page();
+            invoice_table_header(&mut c);
+        }
+        c.advance(5.5);
+        c.amount_text(&item.quantity.to_string(), 9.0, c.margin + 12.0);
+        c.text(&truncate(&item.product_name, 70), 9.0, c.margin + 18.0, Style::Regular);
+        c.amount(item.unit_price, 9.0, right - 30.0);
+        c.amount(item.subtotal, 9.0, right);
+    }
+    c.advance(3.0);
+    c.rule();
+
+    // Tax breakdown
+    c.advance(6.0);
+    c.text("Op. Gravada S/", 9.0, right - 70.0, Style::Regular);
+    c.amount(sale.subtotal, 9.0, right);
+    c.advance(5.0);
+    c.text(&format!("{} S/", IGV_RATE_LABEL), 9.0, right - 70.0, Style::Regular);
+    c.amount(sale.igv, 9.0, right);
+    c.advance(6.0);
+    c.text("Importe Total S/", 10.0, right - 70.0, Style::Bold);
+    c.amount(sale.total, 10.0, right);
+
+    // Fiscal QR
+    let qr_top = c.y - 6.0;
+    c.qr(&fiscal_qr_payload(sale, store), c.margin, qr_top - 30.0, 30.0)?;
+    c.text_at(
+        &format!("Representación impresa de la {}", document_title(sale).to_lowercase()),
+        8.0,
+        c.margin + 35.0,
+        qr_top - 12.0,
+        Style::Regular,
+    );
+
+    Ok(c)
+}
+
+fn invoice_table_header(c: &mut Canvas) {
+    let right = c.width - c.margin;
+    c.rule();
+    c.advance(5.0);
+    c.text("Cant.", 9.0, c.margin, Style::Bold);
+    c.text("Descripción", 9.0, c.margin + 18.0, Style::Bold);
+    c.text("P. Unit.", 9.0, right - 45.0, Style::Bold);
+    c.text("Importe", 9.0, right - 14.0, Style::Bold);
+    c.advance(2.0);
+    c.rule();
+}
+
+fn render_cash_report(
+    session: &CashSession,
+    transactions: &[serde_json::Value],
+    store: Option<&Store>,
+    opened_by: &str,
+    closed_by: Option<&str>,
+) -> Result<Canvas, String> {
+    let mut c = Canvas::new(&format!("Cierre de caja #{}", session.id), 210.0, 297.0, 15.0)?;
+    let right = c.width - c.margin;
+
+    // Totals per category and payment channel
+    let mut sales_cash = Money::ZERO;
+    let mut sales_virtual = Money::ZERO;
+    let mut income_cash = Money::ZERO;
+    let mut income_virtual = Money::ZERO;
+    let mut expense_cash = Money::ZERO;
+    let mut expense_virtual = Money::ZERO;
+    for t in transactions {
+        let amount = Money::from_soles(t["amount"].as_f64().unwrap_or(0.0));
+        let is_cash = t["payment_method"].as_str() == Some("cash");
+        let bucket = match (t["category"].as_str(), is_cash) {
+            (Some("Venta"), true) => &mut sales_cash,
+            (Some("Venta"), false) => &mut sales_virtual,
+            (Some("Gasto"), true) => &mut expense_cash,
+            (Some("Gasto"), false) => &mut expense_virtual,
+            (_, true) => &mut income_cash,
+            (_, false) => &mut income_virtual,
+        };
+        *bucket += amount;
+    }
+
+    if let Some(path) = store.and_then(|s| s.logo_path.as_deref()) {
+        let logo_height = c.logo(path, c.margin, c.y, 35.0);
+        c.advance(logo_height + 2.0);
+    }
+    c.advance(6.0);
+    c.text(store.map(|s| s.name.as_str()).unwrap_or(""), 12.0, c.margin, Style::Bold);
+    c.advance(7.0);
+    c.text(
+        &format!("Reporte de cierre de caja #{}", session.id),
+        14.0,
+        c.margin,
+        Style::Bold,
+    );
+
+    c.advance(9.0);
+    c.text("Apertura:", 9.0, c.margin, Style::Bold);
+    c.text(
+        &format!("{} por {}", session.opened_at, opened_by),
+        9.0,
+        c.margin + 25.0,
+        Style::Regular,
+    );
+    c.advance(5.0);
+    c.text("Cierre:", 9.0, c.margin, Style::Bold);
+    let closing = match (session.closed_at.as_deref(), closed_by) {
+        (Some(at), Some(by)) => format!("{} por {}", at, by),
+        (Some(at), None) => at.to_string(),
+        _ => "Caja abierta".to_string(),
+    };
+    c.text(&closing, 9.0, c.margin + 25.0, Style::Regular);
+
+    // Summary table
+    c.advance(8.0);
+    c.rule();
+    c.advance(5.0);
+    c.text("Concepto", 9.0, c.margin, Style::Bold);
+    c.text("Efectivo", 9.0, right - 65.0, Style::Bold);
+    c.text("Virtual", 9.0, right - 20.0, Style::Bold);
+    c.advance(2.0);
+    c.rule();
+
+    let rows: [(&str, Money, Money); 5] = [
+        ("Monto de apertura", session.opening_cash, session.opening_virtual),
+        ("Ventas", sales_cash, sales_virtual),
+        ("Otros ingresos", income_cash, income_virtual),
+        ("Gastos", -expense_cash, -expense_virtual),
+        (
+            "Saldo esperado",
+            session.expected_closing_cash,
+            session.expected_closing_virtual,
+        ),
+    ];
+    for (label, cash, virtual_amount) in rows {
+        c.advance(5.5);
+        c.text(label, 9.0, c.margin, Style::Regular);
+        c.amount(cash, 9.0, right - 45.0);
+        c.amount(virtual_amount, 9.0, right);
+    }
+    if let (Some(real_cash), Some(real_virtual)) =
+        (session.real_closing_cash, session.real_closing_virtual)
+    {
+        c.advance(5.5);
+        c.text("Saldo real contado", 9.0, c.margin, Style::Bold);
+        c.amount(real_cash, 9.0, right - 45.0);
+        c.amount(real_virtual, 9.0, right);
+    }
+    c.advance(3.0);
+    c.rule();
+    if let Some(difference) = session.difference {
+        c.advance(6.0);
+        c.text("Diferencia", 10.0, c.margin, Style::Bold);
+        c.amount(difference, 10.0, right);
+    }
+    if let Some(justification) = session.justification.as_deref() {
+        c.advance(5.5);
+        c.text(
+            &format!("Justificación: {}", truncate(justification, 90)),
+            9.0,
+            c.margin,
+            Style::Regular,
+        );
+    }
+
+    // Movement detail
+    c.advance(10.0);
+    c.text("Movimientos", 11.0, c.margin, Style::Bold);
+    c.advance(2.0);
+    c.rule();
+    for t in transactions {
+        if c.y < 20.0 {
+            c.new_page();
+        }
+        let amount = Money::from_soles(t["amount"].as_f64().unwrap_or(0.0));
+        let signed = if t["type"].as_str() == Some("expense") {
+            -amount
+        } else {
+            amount
+        };
+        c.advance(5.0);
+        c.text(t["created_at"].as_str().unwrap_or(""), 8.0, c.margin, Style::Regular);
+        c.text(t["category"].as_str().unwrap_or(""), 8.0, c.margin + 35.0, Style::Regular);
+        c.text(
+            &truncate(t["description"].as_str().unwrap_or(""), 50),
+            8.0,
+            c.margin + 55.0,
+            Style::Regular,
+        );
+        c.text(
+            payment_method_label(t["payment_method"].as_str().unwrap_or("")),
+            8.0,
+            right - 45.0,
+            Style::Regular,
+        );
+        c.amount(signed, 8.0, right);
+    }
+
+    Ok(c)
+}
+
+/// Lays the labels out on A4 label sheets, each repeated as many times as
+/// its copies, and returns the path of the written file.
+pub fn write_label_sheet(
+    labels: &[(LabelProduct, i64)],
+    dir: &Path,
+    file_name: &str,
+) -> Result<String, String> {
+    let mut c = Canvas::new("Etiquetas", 210.0, 297.0, 0.0)?;
+    let per_page = LABEL_COLUMNS * LABEL_ROWS;
+    let copies = labels
+        .iter()
+        .flat_map(|(label, copies)| std::iter::repeat_n(label, *copies as usize));
+
+    for (i, label) in copies.enumerate() {
+        if i > 0 && i % per_page == 0 {
+            c.new_page();
+        }
+        let slot = i % per_page;
+        let x = (slot % LABEL_COLUMNS) as f32 * LABEL_WIDTH;
+        let top = 297.0 - (slot / LABEL_COLUMNS) as f32 * LABEL_HEIGHT;
+        draw_label(&c, label, x, top)?;
+    }
+
+    c.save(dir, file_name)
+}
+
+fn draw_label(c: &Canvas, label: &LabelProduct, x: f32, top: f32) -> Result<(), String> {
+    let pad = 4.0;
+    c.text_at(&truncate(&label.name, 30), 8.0, x + pad, top - 6.0, Style::Bold);
+    let variant = [label.size.as_deref(), label.color.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(" / ");
+    if !variant.is_empty() {
+        c.text_at(&truncate(&variant, 34), 7.0, x + pad, top - 10.0, Style::Regular);
+    }
+    c.text_at(&format!("S/ {}", label.price), 11.0, x + pad, top - 15.5, Style::Bold);
+
+    let barcode = match label.barcode.as_deref() {
+        Some(barcode) if !barcode.is_empty() => barcode,
+        _ => return Ok(()),
+    };
+    let modules = if is_ean13(barcode) {
+        ean13_modules(barcode)
+    } else {
+        code128_modules(barcode)?
+    };
+    let max_width = LABEL_WIDTH - 2.0 * pad;
+    let module = (max_width / modules.len() as f32).min(0.5);
+    let bars_x = x + (LABEL_WIDTH - module * modules.len() as f32) / 2.0;
+    c.bars(&modules, bars_x, top - 31.0, module, 13.0);
+    c.centered_mono(barcode, 7.0, x + LABEL_WIDTH / 2.0, top - 34.0);
+    Ok(())
+}
+
+#[derive(Clone, Copy)]
+enum Style {
+    Regular,
+    Bold,
+}
+
+/// Thin wrapper around a printpdf document that keeps a top-down cursor in mm.
+struct Canvas {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    mono: IndirectFontRef,
+    width: f32,
+    height: f32,
+    margin: f32,
+    y: f32,
+}
+
+impl Canvas {
+    fn new(title: &str, width: f32, height: f32, margin: f32) -> Result<Self, String> {
+        let (doc, page, layer) = PdfDocument::new(title, Mm(width), Mm(height), "Layer 1");
+        let regular = doc
+            .add_builtin_font(BuiltinFont::Helvetica)
+            .map_err(|e| e.to_string())?;
+        let bold = doc
+            .add_builtin_font(BuiltinFont::HelveticaBold)
+            .map_err(|e| e.to_string())?;
+        let mono = doc
+            .add_builtin_font(BuiltinFont::Courier)
+            .map_err(|e| e.to_string())?;
+        let layer = doc.get_page(page).get_layer(layer);
+
+        Ok(Self {
+            doc,
+            layer,
+            regular,
+            bold,
+            mono,
+            width,
+            height,
+            margin,
+            y: height - margin,
+        })
+    }
+
+    fn advance(&mut self, dy: f32) {
+        self.y -= dy;
+    }
+
+    fn new_page(&mut self) {
+        let (page, layer) = self
+            .doc
+            .add_page(Mm(self.width), Mm(self.height), "Layer 1");
+        self.layer = self.doc.get_page(page).get_layer(layer);
+        self.y = self.height - self.margin;
+    }
+
+    fn text(&self, text: &str, size: f32, x: f32, style: Style) {
+        self.text_at(text, size, x, self.y, style);
+    }
+
+    fn text_at(&self, text: &str, size: f32, x: f32, y: f32, style: Style) {
+        let font = match style {
+            Style::Regular => &self.regular,
+            Style::Bold => &self.bold,
+        };
+        self.layer.use_text(text, size, Mm(x), Mm(y), font);
+    }
+
+    /// Right-aligns a monospaced string so its last character ends at `right_x`.
+    fn amount_text(&self, text: &str, size: f32, right_x: f32) {
+        // Courier glyphs are 0.6 em wide; 1pt = 0.3528mm
+        let width = text.chars().count() as f32 * size * 0.6 * 0.3528;
+        self.layer
+            .use_text(text, size, Mm(right_x - width), Mm(self.y), &self.mono);
+    }
+
+    fn amount(&self, value: Money, size: f32, right_x: f32) {
+        self.amount_text(&value.to_string(), size, right_x);
+    }
+
+    fn rule(&self) {
+        self.layer.add_line(Line {
+            points: vec![
+                (Point::new(Mm(self.margin), Mm(self.y)), false),
+                (Point::new(Mm(self.width - self.margin), Mm(self.y)), false),
+            ],
+            is_closed: false,
+        });
+    }
+
+    fn frame(&self, x1: f32, y1: f32, x2: f32, y2: f32) {
+        self.layer
+            .add_rect(Rect::new(Mm(x1), Mm(y1), Mm(x2), Mm(y2)).with_mode(PaintMode::Stroke));
+    }
+
+    /// Draws the QR code with its lower-left corner at (x, y).
+    fn qr(&self, data: &str, x: f32, y: f32, size: f32) -> Result<(), String> {
+        let code = QrCode::new(data.as_bytes()).map_err(|e| e.to_string())?;
+        let modules = code.width();
+        let module = size / modules as f32;
+        for row in 0..modules {
+            for col in 0..modules {
+                if code[(col, row)] == qrcode::Color::Dark {
+                    let x1 = x + col as f32 * module;
+                    let y1 = y + (modules - 1 - row) as f32 * module;
+                    self.layer.add_rect(Rect::new(
+                        Mm(x1),
+                        Mm(y1),
+                        Mm(x1 + module),
+                        Mm(y1 + module),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Draws barcode modules (true = bar) with the lower-left corner at (x, y).
+    fn bars(&self, modules: &[bool], x: f32, y: f32, module: f32, height: f32) {
+        let mut i = 0;
+        while i < modules.len() {
+            if !modules[i] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < modules.len() && modules[i] {
+                i += 1;
+            }
+            let x1 = x + start as f32 * module;
+            let x2 = x + i as f32 * module;
+            self.layer
+                .add_rect(Rect::new(Mm(x1), Mm(y), Mm(x2), Mm(y + height)));
+        }
+    }
+
+    fn centered_mono(&self, text: &str, size: f32, center_x: f32, y: f32) {
+        let width = text.chars().count() as f32 * size * 0.6 * 0.3528;
+        self.layer
+            .use_text(text, size, Mm(center_x - width / 2.0), Mm(y), &self.mono);
+    }
+
+    /// Draws the image at `path` hanging from `top`, scaled to `max_width` mm.
+    /// Returns the drawn height in mm, or 0 if the file could not be read.
+    fn logo(&self, path: &str, x: f32, top: f32, max_width: f32) -> f32 {
+        let image = match image_crate::open(path) {
+            Ok(image) => image,
+            Err(e) => {
+                log::warn!("No se pudo cargar el logo {}: {}", path, e);
+                return 0.0;
+            }
+        };
+        let (px_width, px_height) = (image.width() as f32, image.height() as f32);
+        let dpi = px_width * 25.4 / max_width;
+        let height = px_height * 25.4 / dpi;
+
+        Image::from_dynamic_image(&image).add_to_layer(
+            self.layer.clone(),
+            ImageTransform {
+                translate_x: Some(Mm(x)),
+                translate_y: Some(Mm(top - height)),
+                dpi: Some(dpi),
+                ..Default::default()
+            },
+        );
+        height
+    }
+
+    fn save(self, dir: &Path, file_name: &str) -> Result<String, String> {
+        fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        let path = dir.join(file_name);
+        let file = File::create(&path).map_err(|e| e.to_string())?;
+        self.doc
+            .save(&mut BufWriter::new(file))
+            .map_err(|e| e.to_string())?;
+        Ok(path.to_string_lossy().to_string())
+    }
+}
@@ -0,0 +1,153 @@
+use crate::models::price_change::{
+    ConfirmPurchasePricesPayload, PendingPurchasePrice, ProductPriceChange,
+    SchedulePriceChangesPayload, ScheduledPriceChange,
+};
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::price_change_repo::PriceChangeRepository;
+use chrono::{Local, NaiveDate, NaiveDateTime, NaiveTime};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+
+pub struct PriceChangeService {
+    pub price_change_repo: PriceChangeRepository,
+    pub inventory_repo: InventoryRepository,
+}
+
+impl PriceChangeService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            price_change_repo: PriceChangeRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool),
+        }
+    }
+
+    /// Price changes of a product, newest first.
+    pub async fn get_price_history(&self, product_id: i64) -> Result<Vec<ProductPriceChange>, String> {
+        self.price_change_repo
+            .find_history(product_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Schedules new prices for several products of the store from a future
+    /// date; they are applied automatically when it arrives.
+    pub async fn schedule_price_changes(
+        &self,
+        payload: SchedulePriceChangesPayload,
+    ) -> Result<Vec<ScheduledPriceChange>, String> {
+        if payload.items.is_empty() {
+            return Err("Agregue al menos un producto".to_string());
+        }
+        let effective_at = parse_effective_at(&payload.effective_at)?;
+
+        let mut seen = HashSet::new();
+        for item in &payload.items {
+            if item.new_price.is_negative() {
+                return Err("El precio no puede ser negativo".to_string());
+            }
+            if !seen.insert(item.product_id) {
+                return Err(format!("Producto #{} repetido", item.product_id));
+            }
+            self.inventory_repo
+                .find_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == Some(payload.store_id))
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", item.product_id))?;
+        }
+
+        let note = payload
+            .note
+            .as_deref()
+            .map(str::trim)
+            .filter(|n| !n.is_empty());
+        let ids = self
+            .price_change_repo
+            .schedule(payload.store_id, payload.user_id, &effective_at, note, &payload.items)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.price_change_repo
+            .find_scheduled_by_ids(&ids)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_scheduled_price_changes(
+        &self,
+        store_id: i64,
+        status: Option<String>,
+    ) -> Result<Vec<ScheduledPriceChange>, String> {
+        self.price_change_repo
+            .find_scheduled(store_id, status.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn cancel_scheduled_price_change(&self, id: i64) -> Result<(), String> {
+        let cancelled = self
+            .price_change_repo
+            .cancel_scheduled(id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !cancelled {
+            return Err("El cambio de precio ya fue aplicado o cancelado".to_string());
+        }
+        Ok(())
+    }
+
+    /// Applies the scheduled changes that are due. Returns how many were applied.
+    pub async fn apply_due_price_changes(&self) -> Result<usize, String> {
+        self.price_change_repo
+            .apply_due()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Purchase order lines whose batch price differs from the current one.
+    pub async fn get_pending_purchase_prices(&self, store_id: i64) -> Result<Vec<PendingPurchasePrice>, String> {
+        self.price_change_repo
+            .find_pending_purchase_prices(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Applies the batch prices of the chosen lines now, or schedules them.
+    /// Returns how many lines were confirmed.
+    pub async fn confirm_purchase_prices(&self, payload: ConfirmPurchasePricesPayload) -> Result<usize, String> {
+        if payload.item_ids.is_empty() {
+            return Err("Seleccione al menos un precio".to_string());
+        }
+        let effective_at = match payload.effective_at.as_deref() {
+            Some(value) => Some(parse_effective_at(value)?),
+            None => None,
+        };
+        self.price_change_repo
+            .confirm_purchase_prices(payload.store_id, payload.user_id, &payload.item_ids, effective_at.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Keeps the current prices of the chosen lines.
+    pub async fn discard_purchase_prices(&self, store_id: i64, item_ids: Vec<i64>) -> Result<usize, String> {
+        self.price_change_repo
+            .discard_purchase_prices(store_id, &item_ids)
+            .await
+            .map_err(|e| e.to_string())
+    }
+}
+
+/// Accepts a date (from midnight) or a date and time, which must be in the
+/// future, and returns it in SQLite's local datetime format.
+fn parse_effective_at(value: &str) -> Result<String, String> {
+    let value = value.trim();
+    let at = NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M"))
+        .or_else(|_| NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M"))
+        .or_else(|_| NaiveDate::parse_from_str(value, "%Y-%m-%d").map(|d| d.and_time(NaiveTime::MIN)))
+        .map_err(|_| "Fecha de vigencia inválida".to_string())?;
+    if at <= Local::now().naive_local() {
+        return Err("La fecha de vigencia debe ser futura".to_string());
+    }
+    Ok(at.format("%Y-%m-%d %H:%M:%S").to_string())
+}
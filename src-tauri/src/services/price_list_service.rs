@@ -0,0 +1,166 @@
+use crate::models::money::Money;
+use crate::models::price_list::{PriceList, PriceListItem};
+use crate::models::sales::CreateOrderItemPayload;
+use crate::repositories::loyalty_repo::LoyaltyRepository;
+use crate::repositories::price_list_repo::PriceListRepository;
+use sqlx::SqlitePool;
+
+pub struct PriceListService {
+    pub price_list_repo: PriceListRepository,
+    pool: SqlitePool,
+}
+
+impl PriceListService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            price_list_repo: PriceListRepository::new(pool.clone()),
+            pool,
+        }
+    }
+
+    pub async fn get_price_lists(&self) -> Result<Vec<PriceList>, String> {
+        self.price_list_repo
+            .get_price_lists()
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn create_price_list(
+        &self,
+        name: String,
+        description: Option<String>,
+    ) -> Result<i64, String> {
+        if name.trim().is_empty() {
+            return Err("El nombre de la lista es obligatorio".to_string());
+        }
+        self.price_list_repo
+            .create_price_list(name.trim(), description.as_deref())
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn update_price_list(
+        &self,
+        id: i64,
+        name: String,
+        description: Option<String>,
+        is_active: bool,
+    ) -> Result<(), String> {
+        if name.trim().is_empty() {
+            return Err("El nombre de la lista es obligatorio".to_string());
+        }
+        self.price_list_repo
+            .update_price_list(id, name.trim(), description.as_deref(), is_active)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_price_list_items(
+        &self,
+        price_list_id: i64,
+        store_id: Option<i64>,
+    ) -> Result<Vec<PriceListItem>, String> {
+        self.price_list_repo
+            .get_items(price_list_id, store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn set_price_list_item(
+        &self,
+        price_list_id: i64,
+        product_id: i64,
+        min_quantity: i64,
+        price: Money,
+    ) -> Result<(), String> {
+        if min_quantity < 1 {
+            return Err("La cantidad mínima debe ser al menos 1".to_string());
+        }
+        if price.is_negative() {
+            return Err("El precio no puede ser negativo".to_string());
+        }
+        self.price_list_repo
+            .set_item_price(price_list_id, product_id, min_quantity, price)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn delete_price_list_item(&self, id: i64) -> Result<(), String> {
+        self.price_list_repo
+            .delete_item(id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Assigns a price list to the customer, registering it if it is new.
+    pub async fn set_customer_price_list(
+        &self,
+        document: Option<String>,
+        phone: Option<String>,
+        name: Option<String>,
+        price_list_id: Option<i64>,
+    ) -> Result<(), String> {
+        let customer_id = {
+            let mut conn = self.pool.acquire().await.map_err(|e| e.to_string())?;
+            LoyaltyRepository::upsert_customer(
+                &mut conn,
+                document.as_deref(),
+                phone.as_deref(),
+                name.as_deref(),
+            )
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Ingrese el DNI/RUC o el teléfono del cliente")?
+        };
+
+        self.price_list_repo
+            .set_customer_price_list(customer_id, price_list_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Prices the lines with the sale's list, or the customer's list when the
+    /// sale doesn't pick one. Returns `false` (lines untouched) if no list applies.
+    pub async fn price_items(
+        &self,
+        items: &mut [CreateOrderItemPayload],
+        price_list_id: Option<i64>,
+        client_document: Option<&str>,
+        client_phone: Option<&str>,
+    ) -> Result<bool, String> {
+        let price_list_id = match price_list_id {
+            Some(id) => id,
+            None => match self
+                .price_list_repo
+                .find_customer_price_list(client_document, client_phone)
+                .await
+                .map_err(|e| e.to_string())?
+            {
+                Some(id) => id,
+                None => return Ok(false),
+            },
+        };
+
+        let list = self
+            .price_list_repo
+            .find_by_id(price_list_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Lista de precios no encontrada")?;
+        if !list.is_active {
+            return Err(format!("La lista de precios {} está desactivada", list.name));
+        }
+
+        for item in items.iter_mut() {
+            let unit_price = self
+                .price_list_repo
+                .resolve_price(list.id, item.product_id, item.quantity)
+                .await
+                .map_err(|e| e.to_string())?;
+            item.unit_price = unit_price;
+            item.subtotal = unit_price.times(item.quantity);
+            item.price_list_id = Some(list.id);
+        }
+        Ok(true)
+    }
+}
@@ -0,0 +1,188 @@
+use crate::models::product_image::{ProductImage, ProductImageRecord, LOCAL_IMAGE_PREFIX};
+use crate::repositories::product_image_repo::ProductImageRepository;
+use printpdf::image_crate::{self, ImageFormat};
+use sha2::{Digest, Sha256};
+use sqlx::SqlitePool;
+use std::collections::HashSet;
+use std::fs;
+use std::path::PathBuf;
+
+const MAX_IMAGE_BYTES: usize = 10 * 1024 * 1024;
+const THUMBNAIL_SIZE: u32 = 256;
+
+pub struct ProductImageService {
+    pub product_image_repo: ProductImageRepository,
+    /// `<app data>/images`; thumbnails live in its `thumbs` subfolder.
+    pub images_dir: PathBuf,
+}
+
+impl ProductImageService {
+    pub fn new(pool: SqlitePool, images_dir: PathBuf) -> Self {
+        Self {
+            product_image_repo: ProductImageRepository::new(pool),
+            images_dir,
+        }
+    }
+
+    /// Copies an image file into the app data dir and returns its reference.
+    pub async fn import_image(&self, file_path: &str) -> Result<ProductImage, String> {
+        let bytes =
+            fs::read(file_path).map_err(|e| format!("No se pudo leer la imagen: {}", e))?;
+        self.store(&bytes).await
+    }
+
+    /// Resolves an `image_url` to its local files. `None` for remote URLs
+    /// and for images not downloaded yet.
+    pub async fn get_image(&self, reference: &str) -> Result<Option<ProductImage>, String> {
+        let Some(hash) = reference.strip_prefix(LOCAL_IMAGE_PREFIX) else {
+            return Ok(None);
+        };
+        let record = self
+            .product_image_repo
+            .find_by_hash(hash)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(record
+            .filter(|r| self.original_path(r).exists())
+            .map(|r| self.to_image(&r)))
+    }
+
+    /// Hashes used by the catalog whose files are not on this machine, so a
+    /// replica knows what to request from the Primary after a master-data sync.
+    pub async fn get_missing_images(&self) -> Result<Vec<String>, String> {
+        let mut missing = Vec::new();
+        let mut seen = HashSet::new();
+        for reference in self
+            .product_image_repo
+            .find_referenced()
+            .await
+            .map_err(|e| e.to_string())?
+        {
+            let hash = reference.trim_start_matches(LOCAL_IMAGE_PREFIX).to_string();
+            if seen.insert(hash.clone()) && self.get_image(&reference).await?.is_none() {
+                missing.push(hash);
+            }
+        }
+        Ok(missing)
+    }
+
+    /// Original bytes of a stored image (served by the Primary during sync).
+    pub async fn read_image(&self, hash: &str) -> Result<Vec<u8>, String> {
+        let record = self
+            .product_image_repo
+            .find_by_hash(hash)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Imagen no encontrada".to_string())?;
+        fs::read(self.original_path(&record)).map_err(|e| e.to_string())
+    }
+
+    /// Stores an image received from the Primary; the content must match the hash.
+    pub async fn save_synced_image(&self, hash: &str, bytes: &[u8]) -> Result<ProductImage, String> {
+        if content_hash(bytes) != hash {
+            return Err("La imagen recibida no coincide con su hash".to_string());
+        }
+        self.store(bytes).await
+    }
+
+    /// Deletes the stored images no active product or style uses anymore.
+    /// Returns how many were removed.
+    pub async fn cleanup_orphans(&self) -> Result<usize, String> {
+        let orphans = self
+            .product_image_repo
+            .find_orphans()
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut removed = 0;
+        for record in orphans {
+            let deleted = self
+                .product_image_repo
+                .delete_if_orphaned(&record.hash)
+                .await
+                .map_err(|e| e.to_string())?;
+            if deleted {
+                for path in [self.original_path(&record), self.thumbnail_path(&record.hash)] {
+                    if let Err(e) = fs::remove_file(&path) {
+                        log::warn!("No se pudo borrar {}: {}", path.display(), e);
+                    }
+                }
+                removed += 1;
+            }
+        }
+        Ok(removed)
+    }
+
+    /// Writes the original and its thumbnail (once per content hash) and
+    /// registers the image.
+    async fn store(&self, bytes: &[u8]) -> Result<ProductImage, String> {
+        if bytes.len() > MAX_IMAGE_BYTES {
+            return Err("La imagen no puede superar 10 MB".to_string());
+        }
+        let format = image_crate::guess_format(bytes)
+            .map_err(|_| "El archivo no es una imagen válida".to_string())?;
+        let extension = match format {
+            ImageFormat::Jpeg => "jpg",
+            ImageFormat::Png => "png",
+            ImageFormat::Gif => "gif",
+            ImageFormat::Bmp => "bmp",
+            ImageFormat::Tiff => "tiff",
+            _ => return Err("Formato de imagen no soportado. Use JPG o PNG".to_string()),
+        };
+        let image = image_crate::load_from_memory_with_format(bytes, format)
+            .map_err(|_| "El archivo no es una imagen válida".to_string())?;
+
+        let record = ProductImageRecord {
+            hash: content_hash(bytes),
+            extension: extension.to_string(),
+            width: image.width() as i64,
+            height: image.height() as i64,
+            size_bytes: bytes.len() as i64,
+            created_at: None,
+        };
+
+        let original = self.original_path(&record);
+        let thumbnail = self.thumbnail_path(&record.hash);
+        fs::create_dir_all(self.images_dir.join("thumbs")).map_err(|e| e.to_string())?;
+        if !original.exists() {
+            fs::write(&original, bytes).map_err(|e| e.to_string())?;
+        }
+        if !thumbnail.exists() {
+            image
+                .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+                .save_with_format(&thumbnail, ImageFormat::Png)
+                .map_err(|e| e.to_string())?;
+        }
+
+        self.product_image_repo
+            .insert(&record)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(self.to_image(&record))
+    }
+
+    fn original_path(&self, record: &ProductImageRecord) -> PathBuf {
+        self.images_dir
+            .join(format!("{}.{}", record.hash, record.extension))
+    }
+
+    fn thumbnail_path(&self, hash: &str) -> PathBuf {
+        self.images_dir.join("thumbs").join(format!("{}.png", hash))
+    }
+
+    fn to_image(&self, record: &ProductImageRecord) -> ProductImage {
+        ProductImage {
+            reference: format!("{}{}", LOCAL_IMAGE_PREFIX, record.hash),
+            hash: record.hash.clone(),
+            path: self.original_path(record).to_string_lossy().to_string(),
+            thumbnail_path: self.thumbnail_path(&record.hash).to_string_lossy().to_string(),
+            width: record.width,
+            height: record.height,
+            size_bytes: record.size_bytes,
+        }
+    }
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
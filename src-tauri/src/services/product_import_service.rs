@@ -0,0 +1,525 @@
+use crate::models::money::Money;
+use crate::models::product_import::{
+    ImportColumnMapping, ImportFilePreview, ImportProductsPayload, ImportRowError,
+    ProductImportReport, ProductImportRow, EXPORT_HEADERS,
+};
+use crate::repositories::catalog_repo::CatalogRepository;
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::product_import_repo::ProductImportRepository;
+use crate::services::inventory_service::ensure_catalog_match;
+use calamine::{open_workbook_auto, Reader};
+use chrono::Local;
+use rust_xlsxwriter::{Format, Workbook};
+use sqlx::SqlitePool;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+const PREVIEW_ROWS: usize = 5;
+
+pub struct ProductImportService {
+    pub product_import_repo: ProductImportRepository,
+    pub inventory_repo: InventoryRepository,
+    pub catalog_repo: CatalogRepository,
+}
+
+impl ProductImportService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            product_import_repo: ProductImportRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            catalog_repo: CatalogRepository::new(pool),
+        }
+    }
+
+    /// Sheet names, headers and a few rows of the file, for the mapping screen.
+    pub fn preview_import_file(
+        &self,
+        file_path: &str,
+        sheet: Option<&str>,
+    ) -> Result<ImportFilePreview, String> {
+        let (sheets, mut rows) = read_table(Path::new(file_path), sheet)?;
+        if rows.is_empty() {
+            return Err("El archivo está vacío".to_string());
+        }
+        let headers = rows.remove(0);
+        rows.truncate(PREVIEW_ROWS);
+        Ok(ImportFilePreview {
+            sheets,
+            headers,
+            sample_rows: rows,
+        })
+    }
+
+    /// Validates every row and, unless it is a dry run or a row fails,
+    /// upserts the products by code in a single transaction.
+    pub async fn import_products(
+        &self,
+        payload: ImportProductsPayload,
+    ) -> Result<ProductImportReport, String> {
+        let (_, rows) = read_table(Path::new(&payload.file_path), payload.sheet.as_deref())?;
+        let mut rows = rows.into_iter();
+        let headers = rows.next().ok_or("El archivo está vacío")?;
+        let mapping = payload.mapping.clone().unwrap_or_default();
+        let columns = Columns::resolve(&headers, &mapping)?;
+
+        let existing = self
+            .product_import_repo
+            .find_store_products(payload.store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let existing_codes: HashSet<&str> =
+            existing.iter().filter_map(|p| p.code.as_deref()).collect();
+        let existing_barcodes: HashMap<&str, Option<&str>> = existing
+            .iter()
+            .filter_map(|p| p.barcode.as_deref().map(|b| (b, p.code.as_deref())))
+            .collect();
+        let categories = self
+            .inventory_repo
+            .get_categories(Some(payload.store_id))
+            .await
+            .map_err(|e| e.to_string())?;
+        // A category cell may hold the name or the full path ("Pantalones > Jeans")
+        let known_categories: HashSet<String> = categories
+            .iter()
+            .flat_map(|c| [c.name.to_lowercase(), c.path.to_lowercase()])
+            .collect();
+
+        let mut report = ProductImportReport {
+            total_rows: 0,
+            to_create: 0,
+            to_update: 0,
+            new_categories: Vec::new(),
+            errors: Vec::new(),
+            applied: false,
+        };
+        let mut valid_rows = Vec::new();
+        let mut seen_codes: HashMap<String, usize> = HashMap::new();
+        let mut seen_barcodes: HashMap<String, usize> = HashMap::new();
+
+        // Row 1 is the header
+        for (index, cells) in rows.enumerate() {
+            let row_number = index + 2;
+            if cells.iter().all(|c| c.trim().is_empty()) {
+                continue;
+            }
+            report.total_rows += 1;
+
+            let mut errors = Vec::new();
+            let row = columns.read_row(row_number, &cells, &mut errors);
+
+            if row.code.is_empty() {
+                errors.push("Código vacío".to_string());
+            } else if let Some(first) = seen_codes.get(&row.code) {
+                errors.push(format!("Código duplicado en el archivo (fila {})", first));
+            } else {
+                seen_codes.insert(row.code.clone(), row_number);
+            }
+
+            let is_new = !existing_codes.contains(row.code.as_str());
+            match row.name.as_deref() {
+                None if is_new => {
+                    errors.push("El nombre es obligatorio para productos nuevos".to_string());
+                }
+                Some(name) if is_new && !row.code.is_empty() => {
+                    if let Err(message) = ensure_catalog_match(
+                        &self.catalog_repo,
+                        &row.code,
+                        name,
+                        row.unit.as_deref(),
+                        row.size.as_deref(),
+                        row.color.as_deref(),
+                    )
+                    .await
+                    {
+                        errors.push(message);
+                    }
+                }
+                _ => {}
+            }
+
+            if let Some(barcode) = &row.barcode {
+                if let Some(first) = seen_barcodes.get(barcode) {
+                    errors.push(format!("Código de barras duplicado en el archivo (fila {})", first));
+                } else {
+                    seen_barcodes.insert(barcode.clone(), row_number);
+                }
+                if let Some(owner) = existing_barcodes.get(barcode.as_str()) {
+                    if *owner != Some(row.code.as_str()) {
+                        errors.push(format!(
+                            "El código de barras {} ya pertenece a {}",
+                            barcode,
+                            owner.unwrap_or("otro producto")
+                        ));
+                    }
+                }
+            }
+
+            if let Some(category) = &row.category {
+                let key = category.to_lowercase();
+                if !known_categories.contains(&key) {
+                    if payload.create_categories {
+                        if !report.new_categories.iter().any(|c| c.to_lowercase() == key) {
+                            report.new_categories.push(category.clone());
+                        }
+                    } else {
+                        errors.push(format!("Categoría desconocida: {}", category));
+                    }
+                }
+            }
+
+            if errors.is_empty() {
+                if is_new {
+                    report.to_create += 1;
+                } else {
+                    report.to_update += 1;
+                }
+                valid_rows.push(row);
+            } else {
+                let code = Some(row.code).filter(|c| !c.is_empty());
+                report.errors.extend(errors.into_iter().map(|message| ImportRowError {
+                    row: row_number,
+                    code: code.clone(),
+                    message,
+                }));
+            }
+        }
+
+        if payload.dry_run || !report.errors.is_empty() || valid_rows.is_empty() {
+            return Ok(report);
+        }
+
+        self.product_import_repo
+            .apply(
+                payload.store_id,
+                payload.user_id,
+                &valid_rows,
+                &report.new_categories,
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+        report.applied = true;
+        Ok(report)
+    }
+
+    /// Writes the store catalog with stock and cost as "csv" or "xlsx" inside
+    /// `output_dir` and returns the file path. Uses the default import headers.
+    pub async fn export_products(
+        &self,
+        store_id: i64,
+        format: &str,
+        output_dir: &Path,
+    ) -> Result<String, String> {
+        if !matches!(format, "csv" | "xlsx") {
+            return Err("Formato inválido. Debe ser: csv o xlsx".to_string());
+        }
+        let products = self
+            .inventory_repo
+            .get_products(store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let dir = output_dir.join("exports");
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let path = dir.join(format!(
+            "productos_tienda{}_{}.{}",
+            store_id,
+            Local::now().format("%Y%m%d_%H%M%S"),
+            format
+        ));
+
+        // get_products reports sellable stock; export the physical count so a
+        // round trip does not book the reserved units as a shrinkage
+        let rows: Vec<[ExportCell; 12]> = products
+            .iter()
+            .map(|p| {
+                [
+                    ExportCell::Text(p.code.clone().unwrap_or_default()),
+                    ExportCell::Text(p.name.clone()),
+                    ExportCell::Text(p.category_name.clone().unwrap_or_default()),
+                    ExportCell::Money(p.price),
+                    ExportCell::Money(p.cost),
+                    ExportCell::Number(p.stock + p.reserved_stock),
+                    ExportCell::Number(p.reserved_stock),
+                    ExportCell::Number(p.min_stock.unwrap_or(0)),
+                    ExportCell::Text(p.unit.clone().unwrap_or_default()),
+                    ExportCell::Text(p.barcode.clone().unwrap_or_default()),
+                    ExportCell::Text(p.size.clone().unwrap_or_default()),
+                    ExportCell::Text(p.color.clone().unwrap_or_default()),
+                ]
+            })
+            .collect();
+
+        match format {
+            "csv" => write_csv(&path, &EXPORT_HEADERS, &rows)?,
+            _ => write_xlsx(&path, "Productos", &EXPORT_HEADERS, &rows, 1)?,
+        }
+        Ok(path.to_string_lossy().to_string())
+    }
+}
+
+/// Position of each mapped field in the header row.
+struct Columns {
+    code: usize,
+    name: Option<usize>,
+    category: Option<usize>,
+    price: Option<usize>,
+    cost: Option<usize>,
+    stock: Option<usize>,
+    min_stock: Option<usize>,
+    unit: Option<usize>,
+    barcode: Option<usize>,
+    size: Option<usize>,
+    color: Option<usize>,
+}
+
+impl Columns {
+    fn resolve(headers: &[String], mapping: &ImportColumnMapping) -> Result<Self, String> {
+        let find = |header: &str| {
+            headers
+                .iter()
+                .position(|h| h.trim().eq_ignore_ascii_case(header.trim()))
+                .ok_or_else(|| format!("Columna '{}' no encontrada en el archivo", header))
+        };
+        let optional = |header: &Option<String>| match header {
+            Some(header) if !header.trim().is_empty() => find(header).map(Some),
+            _ => Ok(None),
+        };
+
+        Ok(Self {
+            code: find(&mapping.code)?,
+            name: optional(&mapping.name)?,
+            category: optional(&mapping.category)?,
+            price: optional(&mapping.price)?,
+            cost: optional(&mapping.cost)?,
+            stock: optional(&mapping.stock)?,
+            min_stock: optional(&mapping.min_stock)?,
+            unit: optional(&mapping.unit)?,
+            barcode: optional(&mapping.barcode)?,
+            size: optional(&mapping.size)?,
+            color: optional(&mapping.color)?,
+        })
+    }
+
+    /// Reads the mapped cells; blank cells become `None`. Parse errors are
+    /// pushed to `errors`.
+    fn read_row(&self, row: usize, cells: &[String], errors: &mut Vec<String>) -> ProductImportRow {
+        let text = |column: Option<usize>| {
+            column
+                .and_then(|c| cells.get(c))
+                .map(|v| v.trim().to_string())
+                .filter(|v| !v.is_empty())
+        };
+        let mut money = |column: Option<usize>, label: &str| {
+            let value = text(column)?;
+            match parse_money(&value) {
+                Some(m) if m.is_negative() => {
+                    errors.push(format!("{} negativo: {}", label, value));
+                    None
+                }
+                Some(m) => Some(m),
+                None => {
+                    errors.push(format!("{} inválido: {}", label, value));
+                    None
+                }
+            }
+        };
+        let price = money(self.price, "Precio");
+        let cost = money(self.cost, "Costo");
+        let mut quantity = |column: Option<usize>, label: &str| {
+            let value = text(column)?;
+            match parse_quantity(&value) {
+                Some(q) if q < 0 => {
+                    errors.push(format!("{} negativo: {}", label, value));
+                    None
+                }
+                Some(q) => Some(q),
+                None => {
+                    errors.push(format!("{} inválido: {}", label, value));
+                    None
+                }
+            }
+        };
+        let stock = quantity(self.stock, "Stock");
+        let min_stock = quantity(self.min_stock, "Stock mínimo");
+
+        ProductImportRow {
+            row,
+            code: text(Some(self.code)).unwrap_or_default(),
+            name: text(self.name),
+            category: text(self.category),
+            price,
+            cost,
+            stock,
+            min_stock,
+            unit: text(self.unit),
+            barcode: text(self.barcode),
+            size: text(self.size),
+            color: text(self.color),
+        }
+    }
+}
+
+/// "12.50", "12,50", "S/ 1,234.50" -> Money. Without a dot, a comma is the
+/// decimal separator; with both, commas are thousands separators.
+fn parse_money(value: &str) -> Option<Money> {
+    let cleaned: String = value
+        .trim()
+        .trim_start_matches("S/")
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect();
+    let normalized = if cleaned.contains('.') {
+        cleaned.replace(',', "")
+    } else {
+        cleaned.replace(',', ".")
+    };
+    normalized
+        .parse::<f64>()
+        .ok()
+        .filter(|v| v.is_finite())
+        .map(Money::from_soles)
+}
+
+/// Whole units; spreadsheets often store them as "12.0".
+fn parse_quantity(value: &str) -> Option<i64> {
+    value.parse::<i64>().ok().or_else(|| {
+        value
+            .parse::<f64>()
+            .ok()
+            .filter(|v| v.fract() == 0.0 && v.abs() < i64::MAX as f64)
+            .map(|v| v as i64)
+    })
+}
+
+/// Sheet names (empty for CSV) and all rows, header included, as text.
+fn read_table(path: &Path, sheet: Option<&str>) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+    match extension.as_str() {
+        "csv" | "txt" => read_csv(path).map(|rows| (Vec::new(), rows)),
+        "xlsx" | "xlsm" | "xls" | "ods" => read_workbook(path, sheet),
+        _ => Err("Formato de archivo no soportado. Use CSV o XLSX".to_string()),
+    }
+}
+
+fn read_csv(path: &Path) -> Result<Vec<Vec<String>>, String> {
+    let bytes = fs::read(path).map_err(|e| format!("No se pudo leer el archivo: {}", e))?;
+    // Excel saves CSV as Windows-1252 unless asked for UTF-8
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) => e.into_bytes().iter().map(|&b| b as char).collect(),
+    };
+    let text = text.trim_start_matches('\u{feff}');
+
+    // Spanish-locale Excel separates with semicolons
+    let first_line = text.lines().next().unwrap_or("");
+    let delimiter = if first_line.matches(';').count() > first_line.matches(',').count() {
+        b';'
+    } else {
+        b','
+    };
+
+    let mut reader = csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .from_reader(text.as_bytes());
+    reader
+        .records()
+        .map(|record| {
+            record
+                .map(|r| r.iter().map(|v| v.to_string()).collect())
+                .map_err(|e| e.to_string())
+        })
+        .collect()
+}
+
+fn read_workbook(path: &Path, sheet: Option<&str>) -> Result<(Vec<String>, Vec<Vec<String>>), String> {
+    let mut workbook =
+        open_workbook_auto(path).map_err(|e| format!("No se pudo leer el archivo: {}", e))?;
+    let sheets = workbook.sheet_names().to_vec();
+    let name = match sheet {
+        Some(sheet) => sheet.to_string(),
+        None => sheets.first().cloned().ok_or("El archivo no tiene hojas")?,
+    };
+    let range = workbook
+        .worksheet_range(&name)
+        .map_err(|_| format!("Hoja '{}' no encontrada", name))?;
+    let rows = range
+        .rows()
+        .map(|row| row.iter().map(|cell| cell.to_string()).collect())
+        .collect();
+    Ok((sheets, rows))
+}
+
+/// A value of an exported sheet; money keeps two decimals in XLSX.
+pub(crate) enum ExportCell {
+    Text(String),
+    Money(Money),
+    Number(i64),
+}
+
+pub(crate) fn write_csv<const N: usize>(
+    path: &Path,
+    headers: &[&str; N],
+    rows: &[[ExportCell; N]],
+) -> Result<(), String> {
+    // BOM so Excel opens the accents as UTF-8
+    let mut content = "\u{feff}".as_bytes().to_vec();
+    {
+        let mut writer = csv::Writer::from_writer(&mut content);
+        writer.write_record(headers).map_err(|e| e.to_string())?;
+        for row in rows {
+            let record = row.iter().map(|cell| match cell {
+                ExportCell::Text(text) => text.clone(),
+                ExportCell::Money(value) => value.to_string(),
+                ExportCell::Number(value) => value.to_string(),
+            });
+            writer.write_record(record).map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+    }
+    fs::write(path, content).map_err(|e| e.to_string())
+}
+
+/// `wide_column` (the name) gets room for long texts.
+pub(crate) fn write_xlsx<const N: usize>(
+    path: &Path,
+    sheet_name: &str,
+    headers: &[&str; N],
+    rows: &[[ExportCell; N]],
+    wide_column: u16,
+) -> Result<(), String> {
+    let mut workbook = Workbook::new();
+    let bold = Format::new().set_bold();
+    let money = Format::new().set_num_format("0.00");
+    let sheet = workbook.add_worksheet();
+    sheet.set_name(sheet_name).map_err(|e| e.to_string())?;
+
+    for (col, header) in headers.iter().enumerate() {
+        sheet
+            .write_string_with_format(0, col as u16, *header, &bold)
+            .map_err(|e| e.to_string())?;
+    }
+    for (index, row) in rows.iter().enumerate() {
+        let r = index as u32 + 1;
+        for (col, cell) in row.iter().enumerate() {
+            let c = col as u16;
+            match cell {
+                ExportCell::Text(text) => sheet.write_string(r, c, text),
+                ExportCell::Money(value) => {
+                    sheet.write_number_with_format(r, c, value.to_soles(), &money)
+                }
+                ExportCell::Number(value) => sheet.write_number(r, c, *value as f64),
+            }
+            .map_err(|e| e.to_string())?;
+        }
+    }
+    sheet.set_column_width(wide_column, 40).map_err(|e| e.to_string())?;
+
+    workbook.save(path).map_err(|e| e.to_string())
+}
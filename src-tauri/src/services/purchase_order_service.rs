@@ -1,15 +1,21 @@
 use crate::models::purchase_order::{
-    CreatePurchaseOrderPayload, PurchaseOrder, PurchaseOrderWithItems,
+    CreatePurchaseOrderItemPayload, CreatePurchaseOrderPayload, PurchaseOrder,
+    PurchaseOrderWithItems, ReceivePurchaseOrderPayload, ReceivedItem,
 };
+use crate::models::money::Money;
+use crate::models::stock_movement::MovementSource;
 use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::catalog_repo::CatalogRepository;
 use crate::repositories::inventory_repo::InventoryRepository;
 use crate::repositories::purchase_order_repo::PurchaseOrderRepository;
+use crate::services::inventory_service::ensure_catalog_match;
 use sqlx::SqlitePool;
 use uuid::Uuid;
 
 pub struct PurchaseOrderService {
     pub purchase_order_repo: PurchaseOrderRepository,
     pub inventory_repo: InventoryRepository,
+    pub catalog_repo: CatalogRepository,
     pub cash_repo: CashRepository,
 }
 
@@ -18,6 +24,7 @@ impl PurchaseOrderService {
         Self {
             purchase_order_repo: PurchaseOrderRepository::new(pool.clone()),
             inventory_repo: InventoryRepository::new(pool.clone()),
+            catalog_repo: CatalogRepository::new(pool.clone()),
             cash_repo: CashRepository::new(pool),
         }
     }
@@ -29,12 +36,29 @@ impl PurchaseOrderService {
         let order_uuid = Uuid::new_v4().to_string();
 
         // Calculate total cost
-        let total_cost: f64 = payload
+        let total_cost: Money = payload
             .items
             .iter()
-            .map(|item| item.unit_cost * item.quantity as f64)
+            .map(|item| item.unit_cost.times(item.quantity))
             .sum();
 
+        // Items that create a product are linked to the catalog item with
+        // the same code, so they must match it
+        for item in payload.items.iter().filter(|i| i.product_id.is_none()) {
+            let Some(sku) = item.sku.as_deref() else {
+                continue;
+            };
+            let exists = self
+                .inventory_repo
+                .find_by_code(sku, payload.store_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .is_some();
+            if !exists {
+                ensure_catalog_match(&self.catalog_repo, sku, &item.product_name, None, None, None).await?;
+            }
+        }
+
         // Create the purchase order
         let order = self
             .purchase_order_repo
@@ -46,67 +70,24 @@ impl PurchaseOrderService {
                 payload.alias.as_deref(),
                 total_cost,
                 payload.created_by,
+                "received",
             )
             .await
             .map_err(|e| e.to_string())?;
 
         let mut created_items = Vec::new();
+        let source =
+            MovementSource::document("purchase", "purchase_order", order.id, Some(payload.created_by));
 
         for item in &payload.items {
-            // Try to find existing product by SKU
-            let existing_product = if let Some(ref sku) = item.sku {
-                self.inventory_repo
-                    .find_by_code(sku, payload.store_id)
-                    .await
-                    .map_err(|e| e.to_string())?
-            } else {
-                None
-            };
-
-            let product_id = if let Some(product) = existing_product {
-                // Update existing product: sum stock, update prices
-                self.inventory_repo
-                    .update_product(
-                        product.id,
-                        item.sku.as_deref(),
-                        &item.product_name,
-                        item.category_id,
-                        item.unit_price,
-                        item.unit_cost,
-                        product.stock + item.quantity,
-                        None,
-                        item.image_url.as_deref(),
-                        payload.store_id,
-                    )
-                    .await
-                    .map_err(|e| e.to_string())?;
-                Some(product.id)
-            } else {
-                // Create new product
-                let new_id = self
-                    .inventory_repo
-                    .create_product(
-                        item.sku.as_deref(),
-                        &item.product_name,
-                        item.category_id,
-                        item.unit_price,
-                        item.unit_cost,
-                        item.quantity,
-                        None,
-                        item.image_url.as_deref(),
-                        payload.store_id,
-                    )
-                    .await
-                    .map_err(|e| e.to_string())?;
-                Some(new_id)
-            };
+            let product_id = self.stock_item(payload.store_id, item, &source).await?;
 
             // Create purchase order item
-            let po_item = self
+            let mut po_item = self
                 .purchase_order_repo
                 .create_order_item(
                     order.id,
-                    product_id,
+                    Some(product_id),
                     &item.product_name,
                     item.sku.as_deref(),
                     item.category_id,
@@ -116,6 +97,9 @@ impl PurchaseOrderService {
                 )
                 .await
                 .map_err(|e| e.to_string())?;
+            if self.flag_price_change(po_item.id).await? {
+                po_item.price_status = Some("pending".to_string());
+            }
 
             created_items.push(po_item);
         }
@@ -145,10 +129,165 @@ impl PurchaseOrderService {
             total_cost: order.total_cost,
             created_by: order.created_by,
             created_at: order.created_at,
+            status: order.status,
             items: created_items,
         })
     }
 
+    /// Saves the order as a draft: no stock moves and no expense is recorded
+    /// until it is received. Every item must point to an existing product.
+    pub async fn create_draft_purchase_order(
+        &self,
+        payload: CreatePurchaseOrderPayload,
+    ) -> Result<PurchaseOrderWithItems, String> {
+        if payload.items.is_empty() {
+            return Err("La orden debe tener al menos un producto".to_string());
+        }
+        for item in &payload.items {
+            let product_id = item
+                .product_id
+                .ok_or_else(|| format!("\"{}\" no está en el inventario", item.product_name))?;
+            self.inventory_repo
+                .find_by_id(product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == Some(payload.store_id))
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", product_id))?;
+        }
+
+        let total_cost: Money = payload
+            .items
+            .iter()
+            .map(|item| item.unit_cost.times(item.quantity))
+            .sum();
+
+        let order = self
+            .purchase_order_repo
+            .create_order(
+                &Uuid::new_v4().to_string(),
+                payload.store_id,
+                payload.supplier_name.as_deref(),
+                &payload.batch_date,
+                payload.alias.as_deref(),
+                total_cost,
+                payload.created_by,
+                "draft",
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+
+        for item in &payload.items {
+            self.purchase_order_repo
+                .create_order_item(
+                    order.id,
+                    item.product_id,
+                    &item.product_name,
+                    item.sku.as_deref(),
+                    item.category_id,
+                    item.quantity,
+                    item.unit_cost,
+                    item.unit_price,
+                )
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+
+        self.get_purchase_order_detail(order.id)
+            .await?
+            .ok_or_else(|| "Orden de compra no encontrada".to_string())
+    }
+
+    /// Receives a draft: adds the stock, averages costs and flags price
+    /// changes like a regular purchase and records the expense, all or
+    /// nothing. Lines whose product was deleted or became a pack stop it.
+    pub async fn receive_purchase_order(
+        &self,
+        payload: ReceivePurchaseOrderPayload,
+    ) -> Result<PurchaseOrderWithItems, String> {
+        let order = self
+            .get_purchase_order_detail(payload.id)
+            .await?
+            .ok_or_else(|| "Orden de compra no encontrada".to_string())?;
+        if order.status != "draft" {
+            return Err("La orden de compra ya fue recibida".to_string());
+        }
+
+        // Check every line before anything is booked
+        let mut items = Vec::with_capacity(order.items.len());
+        for item in &order.items {
+            let (quantity, unit_cost) = match payload.items.iter().find(|i| i.item_id == item.id) {
+                Some(change) => (change.quantity, change.unit_cost.unwrap_or(item.unit_cost)),
+                None => (item.quantity, item.unit_cost),
+            };
+            if quantity < 0 {
+                return Err("La cantidad no puede ser negativa".to_string());
+            }
+            let product_id = match (quantity, item.product_id) {
+                // Nothing arrived: keep the line without touching the product
+                (0, Some(product_id)) => product_id,
+                (0, None) => continue,
+                (_, None) => {
+                    return Err(format!("\"{}\" no tiene un producto asociado", item.product_name));
+                }
+                (_, Some(product_id)) => {
+                    let product = self
+                        .inventory_repo
+                        .find_any_by_id(product_id)
+                        .await
+                        .map_err(|e| e.to_string())?
+                        .filter(|p| p.store_id == Some(order.store_id))
+                        .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", product_id))?;
+                    if !product.is_active {
+                        return Err(format!(
+                            "\"{}\" fue eliminado: reactívelo o reciba la línea en cero",
+                            product.name
+                        ));
+                    }
+                    if product.is_kit {
+                        return Err(format!(
+                            "\"{}\" es un pack: reciba sus componentes en su lugar",
+                            product.name
+                        ));
+                    }
+                    product.id
+                }
+            };
+            items.push(ReceivedItem {
+                item_id: item.id,
+                product_id,
+                quantity,
+                unit_cost,
+            });
+        }
+
+        let source = MovementSource::document("purchase", "purchase_order", order.id, Some(payload.user_id));
+        let expense_uuid = Uuid::new_v4().to_string();
+        let received = self
+            .purchase_order_repo
+            .receive(&order, &items, &payload.payment_method, &expense_uuid, &source)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !received {
+            return Err("La orden de compra ya fue recibida".to_string());
+        }
+
+        self.get_purchase_order_detail(order.id)
+            .await?
+            .ok_or_else(|| "Orden de compra no encontrada".to_string())
+    }
+
+    pub async fn delete_draft_purchase_order(&self, id: i64) -> Result<(), String> {
+        let deleted = self
+            .purchase_order_repo
+            .delete_draft(id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !deleted {
+            return Err("Solo se pueden eliminar órdenes en borrador".to_string());
+        }
+        Ok(())
+    }
+
     pub async fn get_purchase_orders(
         &self,
         store_id: i64,
@@ -186,10 +325,97 @@ impl PurchaseOrderService {
                     total_cost: order.total_cost,
                     created_by: order.created_by,
                     created_at: order.created_at,
+                    status: order.status,
                     items,
                 }))
             }
             None => Ok(None),
         }
     }
+
+    async fn flag_price_change(&self, item_id: i64) -> Result<bool, String> {
+        self.purchase_order_repo
+            .flag_price_change(item_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Adds the item's units to the matching product (or creates it),
+    /// averaging the batch cost into the product's cost. The batch price of
+    /// an existing product is not applied here: the line is flagged for
+    /// confirmation instead. Returns the product id.
+    async fn stock_item(
+        &self,
+        store_id: i64,
+        item: &CreatePurchaseOrderItemPayload,
+        source: &MovementSource,
+    ) -> Result<i64, String> {
+        // Use the chosen product or variant, or try to find it by SKU
+        let existing_product = if let Some(product_id) = item.product_id {
+            Some(
+                self.inventory_repo
+                    .find_by_id(product_id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .filter(|p| p.store_id == Some(store_id))
+                    .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", product_id))?,
+            )
+        } else if let Some(ref sku) = item.sku {
+            self.inventory_repo
+                .find_by_code(sku, store_id)
+                .await
+                .map_err(|e| e.to_string())?
+        } else {
+            None
+        };
+
+        match existing_product {
+            Some(product) if product.style_id.is_some() => {
+                // Variants keep the name and category of their style
+                self.inventory_repo
+                    .receive_stock(product.id, item.quantity, item.unit_cost, source)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(product.id)
+            }
+            Some(product) => {
+                // Update existing product: sum stock and average the cost
+                self.inventory_repo
+                    .restock(
+                        product.id,
+                        item.sku.as_deref().or(product.code.as_deref()),
+                        &item.product_name,
+                        item.category_id,
+                        item.image_url.as_deref(),
+                        item.quantity,
+                        item.unit_cost,
+                        source,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(product.id)
+            }
+            None => {
+                // Create new product
+                let new_id = self
+                    .inventory_repo
+                    .create_product(
+                        item.sku.as_deref(),
+                        &item.product_name,
+                        item.category_id,
+                        item.unit_price,
+                        item.unit_cost,
+                        item.quantity,
+                        None,
+                        None,
+                        item.image_url.as_deref(),
+                        store_id,
+                        source,
+                    )
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(new_id)
+            }
+        }
+    }
 }
@@ -0,0 +1,275 @@
+use crate::models::money::Money;
+use crate::models::quotation::{
+    ConvertQuotationPayload, CreateQuotationPayload, Quotation, QuotationWithItems,
+};
+use crate::models::sales::{CreateOrderItemPayload, CreateOrderPayload};
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::quotation_repo::QuotationRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::price_list_service::PriceListService;
+use crate::services::sales_service::{ensure_sale_context, split_igv};
+use chrono::{Local, NaiveDate};
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+const QUOTATION_PAYMENT_METHODS: &[&str] = &["cash", "card", "yape"];
+
+pub struct QuotationService {
+    pub quotation_repo: QuotationRepository,
+    pub inventory_repo: InventoryRepository,
+    pub cash_repo: CashRepository,
+    pub user_repo: UserRepository,
+    pub price_list_service: PriceListService,
+}
+
+impl QuotationService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            quotation_repo: QuotationRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            cash_repo: CashRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool.clone()),
+            price_list_service: PriceListService::new(pool),
+        }
+    }
+
+    pub async fn create_quotation(
+        &self,
+        payload: CreateQuotationPayload,
+    ) -> Result<QuotationWithItems, String> {
+        if payload.items.is_empty() {
+            return Err("La cotización debe tener al menos un producto".to_string());
+        }
+        NaiveDate::parse_from_str(&payload.valid_until, "%Y-%m-%d")
+            .map_err(|_| "Fecha de validez inválida (formato YYYY-MM-DD)".to_string())?;
+
+        let mut items = Vec::new();
+        for item in &payload.items {
+            if item.quantity <= 0 {
+                return Err("La cantidad debe ser mayor a cero".to_string());
+            }
+            let product = self
+                .inventory_repo
+                .find_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == Some(payload.store_id))
+                .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", item.product_id))?;
+
+            let unit_price = item.unit_price.unwrap_or(product.price);
+            if unit_price.is_negative() {
+                return Err(format!("El precio de {} no puede ser negativo", product.name));
+            }
+            items.push(CreateOrderItemPayload {
+                product_id: product.id,
+                product_name: product.name,
+                unit_price,
+                quantity: item.quantity,
+                subtotal: unit_price.times(item.quantity),
+                price_list_id: None,
+            });
+        }
+
+        let total: Money = items.iter().map(|i| i.subtotal).sum();
+        let (subtotal, igv) = split_igv(total);
+
+        let id = self
+            .quotation_repo
+            .create_quotation(&Uuid::new_v4().to_string(), &payload, &items, subtotal, igv, total)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.get_quotation_detail(id)
+            .await?
+            .ok_or_else(|| "Cotización no encontrada".to_string())
+    }
+
+    pub async fn get_quotations(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+        client: Option<&str>,
+    ) -> Result<Vec<Quotation>, String> {
+        self.quotation_repo
+            .expire_overdue(store_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.quotation_repo
+            .find_by_store(store_id, status, client)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_quotation_detail(&self, id: i64) -> Result<Option<QuotationWithItems>, String> {
+        let quotation = self
+            .quotation_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match quotation {
+            Some(quotation) => {
+                let items = self
+                    .quotation_repo
+                    .find_items(quotation.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(QuotationWithItems { quotation, items }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Moves a quotation between 'pending', 'accepted' and 'rejected'.
+    /// Converted or expired quotations are final.
+    pub async fn update_quotation_status(&self, id: i64, status: &str) -> Result<(), String> {
+        if status != "pending" && status != "accepted" && status != "rejected" {
+            return Err("Estado inválido. Debe ser: pending, accepted o rejected".to_string());
+        }
+        let quotation = self
+            .quotation_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Cotización no encontrada".to_string())?;
+
+        if quotation.status == "converted" || quotation.status == "expired" {
+            return Err(format!(
+                "La cotización está en estado '{}' y ya no puede modificarse",
+                quotation.status
+            ));
+        }
+
+        self.quotation_repo
+            .update_status(id, status)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Turns an accepted quotation into a normal sale. Items are re-priced
+    /// against the current `products.price` and stock is checked before the
+    /// order is created; the sale and the status change commit together.
+    pub async fn convert_to_sale(&self, payload: ConvertQuotationPayload) -> Result<i64, String> {
+        let quotation = self
+            .quotation_repo
+            .find_by_id(payload.quotation_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Cotización no encontrada".to_string())?;
+
+        if quotation.status != "accepted" {
+            return Err("Solo se pueden convertir cotizaciones aceptadas".to_string());
+        }
+        // No gift card or points are taken here, and "layaway" is only set
+        // when a layaway is paid off
+        if !QUOTATION_PAYMENT_METHODS.contains(&payload.payment_method.as_str()) {
+            return Err("Método de pago no válido para convertir la cotización".to_string());
+        }
+
+        let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+        if quotation.valid_until < today {
+            self.quotation_repo
+                .update_status(quotation.id, "expired")
+                .await
+                .map_err(|e| e.to_string())?;
+            return Err("La cotización ha vencido".to_string());
+        }
+
+        ensure_sale_context(
+            &self.cash_repo,
+            &self.user_repo,
+            payload.cash_session_id,
+            quotation.store_id,
+            payload.user_id,
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let quoted_items = self
+            .quotation_repo
+            .find_items(quotation.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut items = Vec::new();
+        for item in quoted_items {
+            let product = self
+                .inventory_repo
+                .find_any_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or_else(|| format!("El producto {} ya no está disponible", item.product_name))?;
+            if !product.is_active {
+                return Err(format!("El producto {} está desactivado", product.name));
+            }
+
+            // Net of layaway reservations; a pack counts its components
+            let available = self
+                .inventory_repo
+                .sellable_stock(product.id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if available < item.quantity {
+                return Err(format!(
+                    "Stock insuficiente para {}: disponible {}, requerido {}",
+                    product.name, available, item.quantity
+                ));
+            }
+
+            items.push(CreateOrderItemPayload {
+                product_id: product.id,
+                product_name: product.name,
+                unit_price: product.price,
+                quantity: item.quantity,
+                subtotal: product.price.times(item.quantity),
+                price_list_id: None,
+            });
+        }
+
+        // Customers with an assigned price list (e.g. resellers) get their prices
+        self.price_list_service
+            .price_items(
+                &mut items,
+                None,
+                quotation.client_document.as_deref(),
+                quotation.client_phone.as_deref(),
+            )
+            .await?;
+
+        let total: Money = items.iter().map(|i| i.subtotal).sum();
+        let (subtotal, igv) = split_igv(total);
+
+        let order = CreateOrderPayload {
+            user_id: payload.user_id,
+            client_document: quotation.client_document,
+            client_phone: quotation.client_phone,
+            client_name: quotation.client_name,
+            payment_method: payload.payment_method,
+            items,
+            subtotal,
+            igv,
+            total,
+            cash_session_id: payload.cash_session_id,
+            store_id: quotation.store_id,
+            document_type: None,
+            stored_value_code: None,
+            stored_value_amount: None,
+            loyalty_points: None,
+            price_list_id: None,
+        };
+        let order_id = self
+            .quotation_repo
+            .convert(quotation.id, &order)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    "Stock insuficiente para uno o más productos".to_string()
+                }
+                other => other.to_string(),
+            })?
+            .ok_or_else(|| "La cotización ya fue convertida en venta".to_string())?;
+
+        Ok(order_id)
+    }
+}
@@ -0,0 +1,133 @@
+use crate::models::purchase_order::{
+    CreatePurchaseOrderItemPayload, CreatePurchaseOrderPayload, PurchaseOrderWithItems,
+};
+use crate::models::reorder::{
+    CreateReorderPurchaseOrderPayload, LowStockProduct, ReorderSuggestion,
+};
+use crate::repositories::reorder_repo::ReorderRepository;
+use crate::services::purchase_order_service::PurchaseOrderService;
+use chrono::Local;
+use sqlx::SqlitePool;
+
+const DEFAULT_DAYS: i64 = 30;
+
+pub struct ReorderService {
+    pub reorder_repo: ReorderRepository,
+    pub purchase_order_service: PurchaseOrderService,
+}
+
+impl ReorderService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            reorder_repo: ReorderRepository::new(pool.clone()),
+            purchase_order_service: PurchaseOrderService::new(pool),
+        }
+    }
+
+    pub async fn get_low_stock(&self, store_id: i64) -> Result<Vec<LowStockProduct>, String> {
+        self.reorder_repo
+            .low_stock(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Proposes how much to buy so that stock covers `cover_days` of sales at
+    /// the pace of the last `days` days and still stays at `min_stock`:
+    /// `ceil(velocity * cover_days) + min_stock - stock`.
+    pub async fn get_reorder_suggestions(
+        &self,
+        store_id: i64,
+        days: Option<i64>,
+        cover_days: Option<i64>,
+    ) -> Result<Vec<ReorderSuggestion>, String> {
+        let days = days.unwrap_or(DEFAULT_DAYS);
+        let cover_days = cover_days.unwrap_or(DEFAULT_DAYS);
+        if days <= 0 || cover_days <= 0 {
+            return Err("Los días deben ser mayores a 0".to_string());
+        }
+
+        let rows = self
+            .reorder_repo
+            .sales_velocity(store_id, days)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut suggestions: Vec<ReorderSuggestion> = rows
+            .into_iter()
+            .filter_map(|row| {
+                let daily_velocity = row.units_sold as f64 / days as f64;
+                let target = (daily_velocity * cover_days as f64).ceil() as i64 + row.min_stock;
+                let suggested_quantity = target - row.stock.max(0);
+                if suggested_quantity <= 0 {
+                    return None;
+                }
+                Some(ReorderSuggestion {
+                    product_id: row.product_id,
+                    product_code: row.product_code,
+                    product_name: row.product_name,
+                    category_id: row.category_id,
+                    stock: row.stock,
+                    min_stock: row.min_stock,
+                    units_sold: row.units_sold,
+                    daily_velocity,
+                    days_of_cover: (row.units_sold > 0)
+                        .then(|| row.stock.max(0) as f64 / daily_velocity),
+                    suggested_quantity,
+                    unit_cost: row.unit_cost,
+                    unit_price: row.unit_price,
+                    estimated_cost: row.unit_cost.times(suggested_quantity),
+                })
+            })
+            .collect();
+
+        // Products that run out first go on top
+        suggestions.sort_by(|a, b| {
+            let a_cover = a.days_of_cover.unwrap_or(f64::INFINITY);
+            let b_cover = b.days_of_cover.unwrap_or(f64::INFINITY);
+            a_cover
+                .total_cmp(&b_cover)
+                .then_with(|| a.product_name.cmp(&b.product_name))
+        });
+        Ok(suggestions)
+    }
+
+    /// Turns the current suggestions into a draft purchase order.
+    pub async fn create_reorder_purchase_order(
+        &self,
+        payload: CreateReorderPurchaseOrderPayload,
+    ) -> Result<PurchaseOrderWithItems, String> {
+        let suggestions = self
+            .get_reorder_suggestions(payload.store_id, payload.days, payload.cover_days)
+            .await?;
+
+        let items: Vec<CreatePurchaseOrderItemPayload> = suggestions
+            .into_iter()
+            .filter(|s| payload.product_ids.is_empty() || payload.product_ids.contains(&s.product_id))
+            .map(|s| CreatePurchaseOrderItemPayload {
+                product_id: Some(s.product_id),
+                product_name: s.product_name,
+                sku: s.product_code,
+                category_id: s.category_id,
+                quantity: s.suggested_quantity,
+                unit_cost: s.unit_cost,
+                unit_price: s.unit_price,
+                image_url: None,
+            })
+            .collect();
+        if items.is_empty() {
+            return Err("No hay productos que reponer".to_string());
+        }
+
+        self.purchase_order_service
+            .create_draft_purchase_order(CreatePurchaseOrderPayload {
+                store_id: payload.store_id,
+                created_by: payload.user_id,
+                supplier_name: payload.supplier_name,
+                batch_date: Local::now().format("%Y-%m-%d").to_string(),
+                alias: Some("Reposición sugerida".to_string()),
+                payment_method: "cash".to_string(),
+                items,
+            })
+            .await
+    }
+}
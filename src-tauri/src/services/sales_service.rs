@@ -1,26 +1,299 @@
+use crate::models::money::Money;
 use crate::models::sales::{CreateOrderPayload, OrderItemExport, Sale, SaleDetail};
-use crate::repositories::sales_repo::SalesRepository;
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::loyalty_repo::LoyaltyRepository;
+use crate::repositories::sales_repo::{
+    document_series, SalesRepository, CASH_SESSION_CLOSED, LOYALTY_POINTS_REJECTED,
+    STORED_VALUE_REJECTED, VOID_CASH_SESSION_CLOSED,
+};
+use crate::repositories::user_repo::UserRepository;
+use crate::services::price_list_service::PriceListService;
+use crate::services::stored_value_service::StoredValueService;
 use sqlx::SqlitePool;
 
+/// Peruvian IGV rate, in percent. Shelf prices already include it.
+pub const IGV_PERCENT: i64 = 18;
+
+/// Splits an IGV-inclusive total into (taxable base, IGV).
+/// The base is `total * 100 / 118` rounded half away from zero to the céntimo,
+/// and the IGV is whatever is left, so base + IGV always equals the total.
+pub fn split_igv(total: Money) -> (Money, Money) {
+    let base = total.mul_div(100, 100 + IGV_PERCENT);
+    (base, total - base)
+}
+
+/// Payment methods the POS may charge a sale with.
+const POS_PAYMENT_METHODS: &[&str] = &["cash", "card", "yape", "gift_card", "store_credit"];
+
+/// Why a sale was rejected. Commands send the message to the UI.
+#[derive(Debug, thiserror::Error)]
+pub enum SaleError {
+    #[error("La caja #{0} no existe")]
+    CashSessionNotFound(i64),
+    #[error("La caja #{0} ya fue cerrada; actualice la pantalla y abra una nueva caja")]
+    CashSessionClosed(i64),
+    #[error("La caja #{session_id} no pertenece a la tienda #{store_id}")]
+    CashSessionStoreMismatch { session_id: i64, store_id: i64 },
+    #[error("El usuario #{0} no existe o está inactivo")]
+    UserNotFound(i64),
+    #[error("El usuario #{user_id} no está asignado a la tienda #{store_id}")]
+    UserNotInStore { user_id: i64, store_id: i64 },
+    #[error("La tienda #{0} no tiene una serie de comprobantes válida (máximo 999 tiendas)")]
+    InvalidDocumentSeries(i64),
+    #[error("Tipo de comprobante inválido: {0}. Debe ser boleta o factura")]
+    InvalidDocumentType(String),
+    #[error("La factura requiere el RUC del cliente (11 dígitos)")]
+    FacturaWithoutRuc,
+    #[error("Stock insuficiente para uno o más productos")]
+    InsufficientStock,
+    #[error("{}", STORED_VALUE_REJECTED)]
+    StoredValueRejected,
+    #[error("{}", LOYALTY_POINTS_REJECTED)]
+    LoyaltyPointsRejected,
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Database(#[from] sqlx::Error),
+}
+
+/// Checks that a sale can be booked into `cash_session_id`: the session exists,
+/// is still open and belongs to `store_id`, and the seller works in that store
+/// (admins, who have no store, may sell in any).
+pub async fn ensure_sale_context(
+    cash_repo: &CashRepository,
+    user_repo: &UserRepository,
+    cash_session_id: i64,
+    store_id: i64,
+    user_id: i64,
+) -> Result<(), SaleError> {
+    let session = cash_repo
+        .get_session_by_id(cash_session_id)
+        .await?
+        .ok_or(SaleError::CashSessionNotFound(cash_session_id))?;
+    if session.status != "open" {
+        return Err(SaleError::CashSessionClosed(cash_session_id));
+    }
+    if session.store_id != store_id {
+        return Err(SaleError::CashSessionStoreMismatch {
+            session_id: cash_session_id,
+            store_id,
+        });
+    }
+    if document_series("B", store_id).is_none() {
+        return Err(SaleError::InvalidDocumentSeries(store_id));
+    }
+
+    let user = user_repo
+        .find_user_by_id(user_id)
+        .await?
+        .filter(|u| u.is_active)
+        .ok_or(SaleError::UserNotFound(user_id))?;
+    let is_admin = user.cargo.as_deref() == Some("ADMIN");
+    if !is_admin && user.store_id != Some(store_id) {
+        return Err(SaleError::UserNotInStore { user_id, store_id });
+    }
+    Ok(())
+}
+
+/// Only "boleta" and "factura" are issued (each with its own numbering), and a
+/// factura needs the customer's RUC. Without a type, the sale picks one from
+/// the customer's document.
+fn validate_document_type(payload: &CreateOrderPayload) -> Result<(), SaleError> {
+    match payload.document_type.as_deref() {
+        None | Some("boleta") => Ok(()),
+        Some("factura") => {
+            let is_ruc = payload
+                .client_document
+                .as_deref()
+                .is_some_and(|doc| doc.len() == 11 && doc.bytes().all(|b| b.is_ascii_digit()));
+            if is_ruc {
+                Ok(())
+            } else {
+                Err(SaleError::FacturaWithoutRuc)
+            }
+        }
+        Some(other) => Err(SaleError::InvalidDocumentType(other.to_string())),
+    }
+}
+
 pub struct SalesService {
     pub sales_repo: SalesRepository,
+    pub stored_value_service: StoredValueService,
+    pub loyalty_repo: LoyaltyRepository,
+    pub cash_repo: CashRepository,
+    pub user_repo: UserRepository,
+    pub price_list_service: PriceListService,
 }
 
 impl SalesService {
     pub fn new(pool: SqlitePool) -> Self {
         Self {
-            sales_repo: SalesRepository::new(pool),
+            sales_repo: SalesRepository::new(pool.clone()),
+            stored_value_service: StoredValueService::new(pool.clone()),
+            loyalty_repo: LoyaltyRepository::new(pool.clone()),
+            cash_repo: CashRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool.clone()),
+            price_list_service: PriceListService::new(pool),
         }
     }
 
-    pub async fn create_order(&self, payload: CreateOrderPayload) -> Result<i64, String> {
+    pub async fn create_order(&self, mut payload: CreateOrderPayload) -> Result<i64, SaleError> {
+        ensure_sale_context(
+            &self.cash_repo,
+            &self.user_repo,
+            payload.cash_session_id,
+            payload.store_id,
+            payload.user_id,
+        )
+        .await?;
+        validate_document_type(&payload)?;
+
+        // Volume/reseller prices are decided here, not by the POS
+        let repriced = self
+            .price_list_service
+            .price_items(
+                &mut payload.items,
+                payload.price_list_id,
+                payload.client_document.as_deref(),
+                payload.client_phone.as_deref(),
+            )
+            .await
+            .map_err(SaleError::Invalid)?;
+        if repriced {
+            payload.total = payload.items.iter().map(|i| i.subtotal).sum();
+            let (subtotal, igv) = split_igv(payload.total);
+            payload.subtotal = subtotal;
+            payload.igv = igv;
+        }
+
+        self.validate_payment(&payload)
+            .await
+            .map_err(SaleError::Invalid)?;
+
+        let cash_session_id = payload.cash_session_id;
         self.sales_repo
             .create_order(payload)
             .await
             .map_err(|e| match e {
-                sqlx::Error::RowNotFound => {
-                    "Stock insuficiente para uno o más productos".to_string()
+                sqlx::Error::RowNotFound => SaleError::InsufficientStock,
+                sqlx::Error::InvalidArgument(msg) => match msg.as_str() {
+                    STORED_VALUE_REJECTED => SaleError::StoredValueRejected,
+                    LOYALTY_POINTS_REJECTED => SaleError::LoyaltyPointsRejected,
+                    CASH_SESSION_CLOSED => SaleError::CashSessionClosed(cash_session_id),
+                    _ => SaleError::Invalid(msg),
+                },
+                other => SaleError::Database(other),
+            })
+    }
+
+    /// Checks the gift card / store credit and loyalty points parts of the payment.
+    async fn validate_payment(&self, payload: &CreateOrderPayload) -> Result<(), String> {
+        // "layaway" is only set when a layaway is paid off: such a sale books
+        // nothing into the cash session
+        if !POS_PAYMENT_METHODS.contains(&payload.payment_method.as_str()) {
+            return Err("Método de pago no válido".to_string());
+        }
+
+        let paid_by_stored_value =
+            payload.payment_method == "gift_card" || payload.payment_method == "store_credit";
+
+        match payload.stored_value_code.as_deref() {
+            Some(code) => {
+                let amount = payload.stored_value_amount.unwrap_or(Money::ZERO);
+                if amount > payload.total {
+                    return Err("El monto canjeado excede el total de la venta".to_string());
+                }
+                if paid_by_stored_value && amount != payload.total {
+                    return Err(
+                        "Indique un método de pago para el saldo no cubierto por la tarjeta".to_string(),
+                    );
                 }
+                self.stored_value_service
+                    .validate_redemption(code, amount)
+                    .await?;
+            }
+            None if paid_by_stored_value => {
+                return Err("Ingrese el código de la tarjeta o saldo a favor".to_string());
+            }
+            None => {}
+        }
+
+        let points = payload.loyalty_points.unwrap_or(0);
+        if points < 0 {
+            return Err("La cantidad de puntos no es válida".to_string());
+        }
+        if points > 0 {
+            let settings = self
+                .loyalty_repo
+                .get_settings()
+                .await
+                .map_err(|e| e.to_string())?;
+            if !settings.enabled {
+                return Err("El programa de puntos está desactivado".to_string());
+            }
+            let customer = self
+                .loyalty_repo
+                .find_customer(payload.client_document.as_deref(), payload.client_phone.as_deref())
+                .await
+                .map_err(|e| e.to_string())?
+                .ok_or("Identifique al cliente (DNI o teléfono) para canjear puntos")?;
+            let balance = self
+                .loyalty_repo
+                .balance(customer.id)
+                .await
+                .map_err(|e| e.to_string())?;
+            if balance < points {
+                return Err(format!("Puntos insuficientes (disponibles: {})", balance));
+            }
+            let stored_value = payload.stored_value_amount.unwrap_or(Money::ZERO);
+            if settings.value_of(points) > payload.total - stored_value {
+                return Err("El valor de los puntos excede el saldo de la venta".to_string());
+            }
+        }
+        Ok(())
+    }
+
+    /// Voids a completed sale from a cash session that is still open.
+    pub async fn void_sale(
+        &self,
+        sale_id: i64,
+        user_id: i64,
+        reason: Option<String>,
+    ) -> Result<(), String> {
+        let detail = self
+            .sales_repo
+            .get_sale_detail(sale_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Venta no encontrada")?;
+        let sale = detail.sale;
+
+        if sale.status != "completed" {
+            return Err("La venta ya fue anulada".to_string());
+        }
+        if sale.payment_method == "layaway" {
+            return Err("Las ventas de separaciones no se pueden anular".to_string());
+        }
+
+        let session_id: i64 = self
+            .sales_repo
+            .get_cash_session_id(sale_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        let session = self
+            .cash_repo
+            .get_session_by_id(session_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if session.map(|s| s.status != "open").unwrap_or(true) {
+            return Err(VOID_CASH_SESSION_CLOSED.to_string());
+        }
+
+        self.sales_repo
+            .void_order(sale_id, user_id, reason.as_deref())
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => "La venta ya fue anulada".to_string(),
                 other => other.to_string(),
             })
     }
@@ -0,0 +1,166 @@
+use crate::models::stock_adjustment::{
+    CreateStockAdjustmentPayload, StockAdjustment, ADJUSTMENT_REASONS,
+};
+use crate::models::user::User;
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::stock_adjustment_repo::StockAdjustmentRepository;
+use crate::repositories::user_repo::UserRepository;
+use crate::services::config_service::ConfigService;
+use sqlx::SqlitePool;
+
+fn is_manager(user: &User) -> bool {
+    matches!(user.cargo.as_deref(), Some("ADMIN") | Some("GERENTE"))
+}
+
+fn map_apply_error(e: sqlx::Error) -> String {
+    match e {
+        sqlx::Error::RowNotFound => "El ajuste dejaría el stock en negativo".to_string(),
+        other => other.to_string(),
+    }
+}
+
+pub struct StockAdjustmentService {
+    pub stock_adjustment_repo: StockAdjustmentRepository,
+    pub inventory_repo: InventoryRepository,
+    pub user_repo: UserRepository,
+    pub config_service: ConfigService,
+}
+
+impl StockAdjustmentService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            stock_adjustment_repo: StockAdjustmentRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool.clone()),
+            config_service: ConfigService::new(pool),
+        }
+    }
+
+    /// Registers a manual adjustment. It is applied right away when approval is
+    /// not required or when a manager (the requester included) authorised it
+    /// at the counter with their password; otherwise it waits as 'pending'.
+    pub async fn create_adjustment(
+        &self,
+        payload: CreateStockAdjustmentPayload,
+    ) -> Result<StockAdjustment, String> {
+        if !ADJUSTMENT_REASONS.contains(&payload.reason_code.as_str()) {
+            return Err("Motivo inválido. Debe ser: damage, theft, found o correction".to_string());
+        }
+        match payload.reason_code.as_str() {
+            "damage" | "theft" if payload.delta >= 0 => {
+                return Err("Las mermas y robos deben descontar unidades".to_string());
+            }
+            "found" if payload.delta <= 0 => {
+                return Err("Un sobrante debe sumar unidades".to_string());
+            }
+            _ if payload.delta == 0 => {
+                return Err("La cantidad del ajuste no puede ser cero".to_string());
+            }
+            _ => {}
+        }
+
+        self.inventory_repo
+            .find_by_id(payload.product_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|p| p.store_id == Some(payload.store_id))
+            .ok_or_else(|| format!("Producto #{} no encontrado en esta tienda", payload.product_id))?;
+
+        let requester = self.active_user(payload.user_id).await?;
+        let approved_by = match payload.approved_by {
+            Some(manager_id) => {
+                let password = payload.manager_password.as_deref().unwrap_or_default();
+                Some(self.verify_manager(manager_id, password).await?.id)
+            }
+            None if !self.approval_required().await? => Some(requester.id),
+            // A manager approving their own request passes their own id and password
+            None => None,
+        };
+
+        let id = self
+            .stock_adjustment_repo
+            .create(&payload, approved_by)
+            .await
+            .map_err(map_apply_error)?;
+
+        self.stock_adjustment_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Ajuste no encontrado".to_string())
+    }
+
+    pub async fn approve_adjustment(
+        &self,
+        id: i64,
+        manager_id: i64,
+        manager_password: &str,
+    ) -> Result<(), String> {
+        self.verify_manager(manager_id, manager_password).await?;
+        let applied = self
+            .stock_adjustment_repo
+            .approve(id, manager_id)
+            .await
+            .map_err(map_apply_error)?;
+        if !applied {
+            return Err("El ajuste ya fue aprobado o rechazado".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn reject_adjustment(
+        &self,
+        id: i64,
+        manager_id: i64,
+        manager_password: &str,
+    ) -> Result<(), String> {
+        self.verify_manager(manager_id, manager_password).await?;
+        let rejected = self
+            .stock_adjustment_repo
+            .reject(id, manager_id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !rejected {
+            return Err("El ajuste ya fue aprobado o rechazado".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn get_adjustments(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<StockAdjustment>, String> {
+        self.stock_adjustment_repo
+            .find_by_store(store_id, status)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    async fn approval_required(&self) -> Result<bool, String> {
+        Ok(self.config_service.get_config("stock_adjustment_approval").await?.as_deref() == Some("1"))
+    }
+
+    async fn active_user(&self, id: i64) -> Result<User, String> {
+        self.user_repo
+            .find_user_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|u| u.is_active)
+            .ok_or_else(|| format!("El usuario #{} no existe o está inactivo", id))
+    }
+
+    /// The manager reviewing an adjustment, once their password checks out.
+    async fn verify_manager(&self, id: i64, password: &str) -> Result<User, String> {
+        let user = self.active_user(id).await?;
+        if !is_manager(&user) {
+            return Err("Solo un administrador o gerente puede aprobar o rechazar ajustes".to_string());
+        }
+        let valid = bcrypt::verify(password, &user.password_hash)
+            .map_err(|e| format!("Error verificando contraseña: {}", e))?;
+        if !valid {
+            return Err("Contraseña del gerente incorrecta".to_string());
+        }
+        Ok(user)
+    }
+}
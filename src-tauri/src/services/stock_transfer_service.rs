@@ -0,0 +1,230 @@
+use crate::models::stock_transfer::{
+    CreateStockTransferPayload, ReceiveStockTransferPayload, StockTransfer, StockTransferDetail,
+};
+use crate::repositories::inventory_repo::InventoryRepository;
+use crate::repositories::stock_transfer_repo::{StockTransferRepository, TransferLine};
+use crate::repositories::store_repo::StoreRepository;
+use crate::repositories::user_repo::UserRepository;
+use sqlx::SqlitePool;
+use std::collections::HashMap;
+
+pub struct StockTransferService {
+    pub stock_transfer_repo: StockTransferRepository,
+    pub inventory_repo: InventoryRepository,
+    pub store_repo: StoreRepository,
+    pub user_repo: UserRepository,
+}
+
+impl StockTransferService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            stock_transfer_repo: StockTransferRepository::new(pool.clone()),
+            inventory_repo: InventoryRepository::new(pool.clone()),
+            store_repo: StoreRepository::new(pool.clone()),
+            user_repo: UserRepository::new(pool),
+        }
+    }
+
+    /// Creates the transfer as a draft. Nothing moves until it is dispatched.
+    pub async fn create_transfer(
+        &self,
+        payload: CreateStockTransferPayload,
+    ) -> Result<StockTransferDetail, String> {
+        if payload.origin_store_id == payload.destination_store_id {
+            return Err("La tienda de origen y destino deben ser distintas".to_string());
+        }
+        if payload.items.is_empty() {
+            return Err("La transferencia debe tener al menos un producto".to_string());
+        }
+        self.store_repo
+            .find_by_id(payload.destination_store_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|s| s.is_active)
+            .ok_or_else(|| format!("La tienda #{} no existe", payload.destination_store_id))?;
+        self.ensure_user_in_store(payload.user_id, payload.origin_store_id).await?;
+
+        // Repeated products are merged into one line
+        let mut lines: Vec<TransferLine> = Vec::new();
+        for item in &payload.items {
+            if item.quantity <= 0 {
+                return Err("La cantidad debe ser mayor a 0".to_string());
+            }
+            if let Some(line) = lines.iter_mut().find(|l| l.0 == item.product_id) {
+                line.3 += item.quantity;
+                continue;
+            }
+
+            let product = self
+                .inventory_repo
+                .find_by_id(item.product_id)
+                .await
+                .map_err(|e| e.to_string())?
+                .filter(|p| p.store_id == Some(payload.origin_store_id))
+                .ok_or_else(|| {
+                    format!("Producto #{} no encontrado en la tienda de origen", item.product_id)
+                })?;
+            let code = match product.code.as_deref().map(str::trim) {
+                Some(code) if !code.is_empty() => code.to_string(),
+                _ => {
+                    return Err(format!(
+                        "\"{}\" no tiene código; asígnele uno para poder transferirlo",
+                        product.name
+                    ))
+                }
+            };
+            lines.push((product.id, code, product.name, item.quantity));
+        }
+
+        let id = self
+            .stock_transfer_repo
+            .create(&payload, &lines)
+            .await
+            .map_err(|e| e.to_string())?;
+        self.transfer_detail(id).await
+    }
+
+    /// Takes the units out of the origin store.
+    pub async fn dispatch_transfer(&self, id: i64, user_id: i64) -> Result<StockTransferDetail, String> {
+        let transfer = self.find_transfer(id).await?;
+        self.ensure_user_in_store(user_id, transfer.origin_store_id).await?;
+
+        let dispatched = self
+            .stock_transfer_repo
+            .dispatch(id, user_id)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => {
+                    "Stock insuficiente en la tienda de origen para despachar la transferencia".to_string()
+                }
+                other => other.to_string(),
+            })?;
+        if !dispatched {
+            return Err("Solo se pueden despachar transferencias en borrador".to_string());
+        }
+        self.transfer_detail(id).await
+    }
+
+    /// Books the units in the destination store. If any item arrives short the
+    /// transfer is closed as 'discrepancy'; the missing units stay out of both
+    /// stores' stock until someone adjusts them.
+    pub async fn receive_transfer(
+        &self,
+        payload: ReceiveStockTransferPayload,
+    ) -> Result<StockTransferDetail, String> {
+        let transfer = self.find_transfer(payload.transfer_id).await?;
+        self.ensure_user_in_store(payload.user_id, transfer.destination_store_id).await?;
+
+        let items = self
+            .stock_transfer_repo
+            .find_items(transfer.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut received: HashMap<i64, i64> = HashMap::new();
+        for r in &payload.items {
+            let item = items
+                .iter()
+                .find(|i| i.id == r.item_id)
+                .ok_or_else(|| format!("El ítem #{} no pertenece a esta transferencia", r.item_id))?;
+            if r.received_quantity < 0 || r.received_quantity > item.quantity {
+                return Err(format!(
+                    "Cantidad recibida inválida para \"{}\": se enviaron {}",
+                    item.product_name, item.quantity
+                ));
+            }
+            received.insert(item.id, r.received_quantity);
+        }
+
+        let complete = items
+            .iter()
+            .all(|i| received.get(&i.id).copied().unwrap_or(i.quantity) == i.quantity);
+        let status = if complete { "received" } else { "discrepancy" };
+
+        let applied = self
+            .stock_transfer_repo
+            .receive(transfer.id, payload.user_id, &received, status)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !applied {
+            return Err("Solo se pueden recibir transferencias despachadas".to_string());
+        }
+        self.transfer_detail(transfer.id).await
+    }
+
+    pub async fn cancel_transfer(&self, id: i64) -> Result<(), String> {
+        let cancelled = self
+            .stock_transfer_repo
+            .cancel(id)
+            .await
+            .map_err(|e| e.to_string())?;
+        if !cancelled {
+            return Err("Solo se pueden anular transferencias en borrador".to_string());
+        }
+        Ok(())
+    }
+
+    pub async fn get_transfers(
+        &self,
+        store_id: i64,
+        status: Option<&str>,
+    ) -> Result<Vec<StockTransfer>, String> {
+        self.stock_transfer_repo
+            .find_by_store(store_id, status)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_transfer_detail(&self, id: i64) -> Result<Option<StockTransferDetail>, String> {
+        let transfer = self
+            .stock_transfer_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        match transfer {
+            Some(transfer) => {
+                let items = self
+                    .stock_transfer_repo
+                    .find_items(transfer.id)
+                    .await
+                    .map_err(|e| e.to_string())?;
+                Ok(Some(StockTransferDetail { transfer, items }))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn transfer_detail(&self, id: i64) -> Result<StockTransferDetail, String> {
+        self.get_transfer_detail(id)
+            .await?
+            .ok_or_else(|| "Transferencia no encontrada".to_string())
+    }
+
+    async fn find_transfer(&self, id: i64) -> Result<StockTransfer, String> {
+        self.stock_transfer_repo
+            .find_by_id(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Transferencia no encontrada".to_string())
+    }
+
+    /// Only staff of the store (or an admin) can move its stock.
+    async fn ensure_user_in_store(&self, user_id: i64, store_id: i64) -> Result<(), String> {
+        let user = self
+            .user_repo
+            .find_user_by_id(user_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .filter(|u| u.is_active)
+            .ok_or_else(|| format!("El usuario #{} no existe o está inactivo", user_id))?;
+        let is_admin = user.cargo.as_deref() == Some("ADMIN");
+        if !is_admin && user.store_id != Some(store_id) {
+            return Err(format!(
+                "El usuario #{} no está asignado a la tienda #{}",
+                user_id, store_id
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,170 @@
+use crate::models::stored_value::{
+    IssueGiftCardPayload, IssueStoreCreditPayload, StoredValueAccount, StoredValueStatement,
+};
+use crate::models::money::Money;
+use crate::repositories::cash_repo::CashRepository;
+use crate::repositories::stored_value_repo::StoredValueRepository;
+use chrono::Local;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+pub struct StoredValueService {
+    pub stored_value_repo: StoredValueRepository,
+    pub cash_repo: CashRepository,
+}
+
+impl StoredValueService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            stored_value_repo: StoredValueRepository::new(pool.clone()),
+            cash_repo: CashRepository::new(pool),
+        }
+    }
+
+    /// Sells a gift card. The money received is booked into the store's open
+    /// cash session, like `add_other_income`.
+    pub async fn issue_gift_card(&self, payload: IssueGiftCardPayload) -> Result<StoredValueStatement, String> {
+        if !payload.amount.is_positive() {
+            return Err("El monto debe ser mayor a cero".to_string());
+        }
+
+        let session = self
+            .cash_repo
+            .get_active_session(payload.store_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "No hay una caja abierta para registrar la venta de la tarjeta".to_string())?;
+
+        let code = match payload.code.as_deref().map(str::trim) {
+            Some(code) if !code.is_empty() => code.to_uppercase(),
+            _ => generate_code("GC"),
+        };
+        if self
+            .stored_value_repo
+            .find_by_code(&code)
+            .await
+            .map_err(|e| e.to_string())?
+            .is_some()
+        {
+            return Err(format!("El código {} ya está registrado", code));
+        }
+
+        let id = self
+            .stored_value_repo
+            .issue_gift_card(&Uuid::new_v4().to_string(), &code, &payload, session.id)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.statement(id).await
+    }
+
+    /// Credits a customer instead of refunding cash (e.g. on a return).
+    pub async fn issue_store_credit(
+        &self,
+        payload: IssueStoreCreditPayload,
+    ) -> Result<StoredValueStatement, String> {
+        if !payload.amount.is_positive() {
+            return Err("El monto debe ser mayor a cero".to_string());
+        }
+        if payload.customer_document.trim().is_empty() {
+            return Err("El saldo a favor debe asociarse a un documento de cliente".to_string());
+        }
+
+        let id = self
+            .stored_value_repo
+            .issue_store_credit(&Uuid::new_v4().to_string(), &generate_code("SC"), &payload)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        self.statement(id).await
+    }
+
+    /// Balance inquiry by card code.
+    pub async fn get_balance(&self, code: &str) -> Result<StoredValueStatement, String> {
+        let account = self
+            .stored_value_repo
+            .find_by_code(&code.trim().to_uppercase())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Código no encontrado".to_string())?;
+        self.statement(account.id).await
+    }
+
+    pub async fn get_store_credit(
+        &self,
+        customer_document: &str,
+    ) -> Result<Option<StoredValueStatement>, String> {
+        let account = self
+            .stored_value_repo
+            .find_store_credit(customer_document)
+            .await
+            .map_err(|e| e.to_string())?;
+        match account {
+            Some(account) => Ok(Some(self.statement(account.id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    pub async fn get_accounts(
+        &self,
+        store_id: i64,
+        kind: Option<&str>,
+    ) -> Result<Vec<StoredValueAccount>, String> {
+        self.stored_value_repo
+            .find_by_store(store_id, kind)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Checks that `code` can pay `amount` right now. Used by the sale flow
+    /// before the order transaction starts.
+    pub async fn validate_redemption(&self, code: &str, amount: Money) -> Result<(), String> {
+        if !amount.is_positive() {
+            return Err("El monto a canjear debe ser mayor a cero".to_string());
+        }
+        let account = self
+            .stored_value_repo
+            .find_by_code(&code.trim().to_uppercase())
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Tarjeta o saldo a favor no encontrado".to_string())?;
+
+        if account.status != "active" {
+            return Err("La tarjeta está anulada".to_string());
+        }
+        if let Some(expires_at) = account.expires_at.as_deref() {
+            let today = Local::now().date_naive().format("%Y-%m-%d").to_string();
+            if expires_at < today.as_str() {
+                return Err("La tarjeta ha vencido".to_string());
+            }
+        }
+        if account.balance < amount {
+            return Err(format!(
+                "Saldo insuficiente: disponible S/ {}",
+                account.balance
+            ));
+        }
+        Ok(())
+    }
+
+    async fn statement(&self, account_id: i64) -> Result<StoredValueStatement, String> {
+        let account = self
+            .stored_value_repo
+            .find_by_id(account_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| "Cuenta no encontrada".to_string())?;
+        let entries = self
+            .stored_value_repo
+            .find_entries(account.id)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok(StoredValueStatement { account, entries })
+    }
+}
+
+/// "GC-1A2B-3C4D" style codes, easy to read out loud at the counter.
+fn generate_code(prefix: &str) -> String {
+    let hex = Uuid::new_v4().simple().to_string().to_uppercase();
+    format!("{}-{}-{}", prefix, &hex[..4], &hex[4..8])
+}
@@ -0,0 +1,217 @@
+use crate::models::variant::{
+    GenerateVariantsPayload, ProductStyle, ProductVariant, SaveProductStylePayload, StyleReportRow,
+    UpdateVariantPayload,
+};
+use crate::models::money::Money;
+use crate::repositories::variant_repo::{NewVariant, VariantRepository};
+use chrono::NaiveDate;
+use sqlx::SqlitePool;
+
+/// SKU of a variant: style code, size and colour, e.g. "SHP01-M-AZUL".
+fn variant_sku(style_code: &str, size: Option<&str>, color: Option<&str>) -> String {
+    [Some(style_code), size, color]
+        .into_iter()
+        .flatten()
+        .map(|part| part.split_whitespace().collect::<Vec<_>>().join("").to_uppercase())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Trims the values and drops blanks and repeats, keeping the given order.
+fn clean_values(values: &[String]) -> Vec<String> {
+    let mut cleaned: Vec<String> = Vec::new();
+    for value in values.iter().map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        if !cleaned.iter().any(|c| c.eq_ignore_ascii_case(value)) {
+            cleaned.push(value.to_string());
+        }
+    }
+    cleaned
+}
+
+pub struct VariantService {
+    pub variant_repo: VariantRepository,
+}
+
+impl VariantService {
+    pub fn new(pool: SqlitePool) -> Self {
+        Self {
+            variant_repo: VariantRepository::new(pool),
+        }
+    }
+
+    pub async fn get_product_styles(&self, store_id: i64) -> Result<Vec<ProductStyle>, String> {
+        self.variant_repo
+            .get_styles(store_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn save_product_style(&self, payload: SaveProductStylePayload) -> Result<i64, String> {
+        if payload.code.trim().is_empty() {
+            return Err("El código del modelo es obligatorio".to_string());
+        }
+        if payload.name.trim().is_empty() {
+            return Err("El nombre del modelo es obligatorio".to_string());
+        }
+        if payload.price.is_negative() || payload.cost.is_negative() {
+            return Err("El precio y el costo no pueden ser negativos".to_string());
+        }
+
+        let result = match payload.id {
+            Some(id) => {
+                let style = self
+                    .variant_repo
+                    .find_style(id)
+                    .await
+                    .map_err(|e| e.to_string())?
+                    .ok_or("Modelo no encontrado")?;
+                if style.store_id != payload.store_id {
+                    return Err("El modelo pertenece a otra tienda".to_string());
+                }
+                self.variant_repo.update_style(id, &payload).await.map(|_| id)
+            }
+            None => self.variant_repo.create_style(&payload).await,
+        };
+        result.map_err(|e| match e {
+            sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                format!("Ya existe un modelo con el código {}", payload.code.trim())
+            }
+            other => other.to_string(),
+        })
+    }
+
+    pub async fn delete_product_style(&self, id: i64) -> Result<(), String> {
+        self.variant_repo
+            .delete_style(id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn get_style_variants(&self, style_id: i64) -> Result<Vec<ProductVariant>, String> {
+        self.variant_repo
+            .get_variants(style_id)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Creates the size × colour matrix of a style. Combinations that already
+    /// exist are left as they are, so the matrix can be extended later.
+    pub async fn generate_variant_matrix(
+        &self,
+        payload: GenerateVariantsPayload,
+    ) -> Result<Vec<ProductVariant>, String> {
+        let style = self
+            .variant_repo
+            .find_style(payload.style_id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or("Modelo no encontrado")?;
+
+        let sizes = clean_values(&payload.sizes);
+        let colors = clean_values(&payload.colors);
+        if sizes.is_empty() && colors.is_empty() {
+            return Err("Indique al menos una talla o un color".to_string());
+        }
+
+        let sizes: Vec<Option<String>> = if sizes.is_empty() {
+            vec![None]
+        } else {
+            sizes.into_iter().map(Some).collect()
+        };
+        let colors: Vec<Option<String>> = if colors.is_empty() {
+            vec![None]
+        } else {
+            colors.into_iter().map(Some).collect()
+        };
+
+        let mut variants: Vec<NewVariant> = Vec::new();
+        for size in &sizes {
+            for color in &colors {
+                let sku = variant_sku(&style.code, size.as_deref(), color.as_deref());
+                variants.push((size.clone(), color.clone(), sku));
+            }
+        }
+
+        self.variant_repo
+            .generate_variants(&style, &variants)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                    "Uno de los SKU generados ya está en uso por otro producto".to_string()
+                }
+                other => other.to_string(),
+            })?;
+
+        self.get_style_variants(style.id).await
+    }
+
+    pub async fn update_variant(&self, payload: UpdateVariantPayload) -> Result<(), String> {
+        if payload.price_override.map(Money::is_negative).unwrap_or(false) {
+            return Err("El precio no puede ser negativo".to_string());
+        }
+        let payload = UpdateVariantPayload {
+            code: payload.code.map(|c| c.trim().to_string()).filter(|c| !c.is_empty()),
+            barcode: payload.barcode.map(|b| b.trim().to_string()).filter(|b| !b.is_empty()),
+            ..payload
+        };
+        self.variant_repo
+            .update_variant(&payload)
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db) if db.message().contains("UNIQUE") => {
+                    "El SKU o código de barras ya está en uso".to_string()
+                }
+                other => other.to_string(),
+            })
+    }
+
+    /// Stock, sales and purchases per variant in a period, rolled up to the style.
+    pub async fn get_style_stock_report(
+        &self,
+        store_id: i64,
+        date_from: String,
+        date_to: String,
+    ) -> Result<Vec<StyleReportRow>, String> {
+        let from = NaiveDate::parse_from_str(&date_from, "%Y-%m-%d")
+            .map_err(|_| "Fecha inicial inválida".to_string())?;
+        let to = NaiveDate::parse_from_str(&date_to, "%Y-%m-%d")
+            .map_err(|_| "Fecha final inválida".to_string())?;
+        if from > to {
+            return Err("La fecha inicial no puede ser posterior a la final".to_string());
+        }
+
+        let styles = self.get_product_styles(store_id).await?;
+        let variants = self
+            .variant_repo
+            .get_variant_report(store_id, &date_from, &date_to)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut rows: Vec<StyleReportRow> = styles
+            .into_iter()
+            .map(|style| StyleReportRow {
+                style_id: style.id,
+                code: style.code,
+                name: style.name,
+                stock: 0,
+                reserved_stock: 0,
+                units_sold: 0,
+                sales_amount: Money::ZERO,
+                units_purchased: 0,
+                variants: Vec::new(),
+            })
+            .collect();
+
+        for variant in variants {
+            if let Some(row) = rows.iter_mut().find(|r| r.style_id == variant.style_id) {
+                row.stock += variant.stock;
+                row.reserved_stock += variant.reserved_stock;
+                row.units_sold += variant.units_sold;
+                row.sales_amount += variant.sales_amount;
+                row.units_purchased += variant.units_purchased;
+                row.variants.push(variant);
+            }
+        }
+        Ok(rows)
+    }
+}
@@ -3,6 +3,7 @@ import { X, Search, Package, DollarSign, Truck, BarChart3, Plus } from 'lucide-r
 import { motion, AnimatePresence } from 'motion/react';
 import { invoke } from '@tauri-apps/api/core';
 import { useNotification } from '@/context/NotificationContext';
+import { useAuth } from '@/context/AuthContext';
 
 interface Product {
     id: number;
@@ -26,6 +27,7 @@ interface AddStockModalProps {
 
 export default function AddStockModal({ isOpen, onClose, onSubmit, products, storeId }: AddStockModalProps) {
     const { showNotification } = useNotification();
+    const { user } = useAuth();
     const [isSubmitting, setIsSubmitting] = useState(false);
     const [searchTerm, setSearchTerm] = useState('');
     const [selectedProduct, setSelectedProduct] = useState<Product | null>(null);
@@ -65,36 +67,36 @@ export default function AddStockModal({ isOpen, onClose, onSubmit, products, sto
             return;
         }
 
-        if (!storeId) {
+        if (!storeId || !user) {
             showNotification('error', 'Error', 'No hay tienda seleccionada');
             return;
         }
 
         const newStock = selectedProduct.stock + qty;
-        const expenseAmount = selectedProduct.cost * qty;
 
         setIsSubmitting(true);
         try {
-            await invoke('update_product', {
-                id: selectedProduct.id,
-                code: selectedProduct.code || null,
-                name: selectedProduct.name,
-                categoryId: selectedProduct.category_id || null,
-                price: selectedProduct.price,
-                cost: selectedProduct.cost,
-                stock: newStock,
-                unit: selectedProduct.unit || null,
-                imageUrl: selectedProduct.image_url || null,
-                storeId
-            });
-
-            await invoke('add_expense_standalone', {
-                description: `Ingreso mercadería: ${selectedProduct.name}`,
-                amount: expenseAmount,
-                paymentMethod,
-                category: 'Mercadería',
-                supplier: supplierName || null,
-                storeId
+            // A supplier receipt is a purchase: the receive flow books the
+            // kardex entry, the average cost and the expense together
+            await invoke('create_purchase_order', {
+                payload: {
+                    storeId,
+                    createdBy: user.id,
+                    supplierName: supplierName || null,
+                    batchDate: new Date().toISOString().split('T')[0],
+                    alias: `Ingreso mercadería: ${selectedProduct.name}`,
+                    paymentMethod,
+                    items: [{
+                        productId: selectedProduct.id,
+                        productName: selectedProduct.name,
+                        sku: selectedProduct.code || null,
+                        categoryId: selectedProduct.category_id,
+                        quantity: qty,
+                        unitCost: selectedProduct.cost,
+                        unitPrice: selectedProduct.price,
+                        imageUrl: selectedProduct.image_url || null,
+                    }],
+                }
             });
 
             showNotification('success', 'Stock actualizado', `Se agregaron ${qty} unidades de "${selectedProduct.name}". Stock total: ${newStock}`);
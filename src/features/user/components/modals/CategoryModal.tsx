@@ -7,6 +7,11 @@ import { useNotification } from '@/context/NotificationContext';
 interface Category {
     id: number;
     name: string;
+    parent_id: number | null;
+    store_id: number | null;
+    path: string;
+    depth: number;
+    product_count: number;
 }
 
 interface CategoryModalProps {
@@ -86,16 +91,34 @@ export default function CategoryModal({ isOpen, onClose, onCategoryChange, store
         }
     };
 
-    const handleDelete = async (id: number) => {
+    const handleDelete = async (category: Category) => {
         if (!confirm('¿Estás seguro de eliminar esta categoría?')) return;
+
+        // Products must be moved to another category before deleting
+        let reassignTo: number | null = null;
+        if (category.product_count > 0) {
+            const others = categories.filter(c => c.id !== category.id);
+            const answer = prompt(
+                `La categoría tiene productos. Escriba la categoría a la que se moverán:\n${others.map(c => c.path).join('\n')}`
+            );
+            if (answer === null) return;
+            const target = others.find(c => c.path.toLowerCase() === answer.trim().toLowerCase()
+                || c.name.toLowerCase() === answer.trim().toLowerCase());
+            if (!target) {
+                showNotification('warning', 'Categoría no encontrada', `No existe la categoría "${answer}"`);
+                return;
+            }
+            reassignTo = target.id;
+        }
+
         try {
-            await invoke('delete_category', { id });
+            await invoke('delete_category', { id: category.id, reassignTo });
             showNotification('success', 'Éxito', 'Categoría eliminada');
             loadCategories();
             onCategoryChange();
         } catch (error) {
             console.error(error);
-            showNotification('error', 'Error', 'Error al eliminar categoría');
+            showNotification('error', 'Error', String(error));
         }
     };
 
@@ -172,7 +195,7 @@ export default function CategoryModal({ isOpen, onClose, onCategoryChange, store
                                                 </div>
                                             ) : (
                                                 <>
-                                                    <span className="font-medium text-gray-700">{category.name}</span>
+                                                    <span className="font-medium text-gray-700" style={{ paddingLeft: `${category.depth}rem` }} title={category.path}>{category.name}</span>
                                                     <div className="flex gap-1 opacity-0 group-hover:opacity-100 transition-opacity">
                                                         <button
                                                             onClick={() => startEdit(category)}
@@ -181,7 +204,7 @@ export default function CategoryModal({ isOpen, onClose, onCategoryChange, store
                                                             <Edit2 className="w-4 h-4" />
                                                         </button>
                                                         <button
-                                                            onClick={() => handleDelete(category.id)}
+                                                            onClick={() => handleDelete(category)}
                                                             className="p-1.5 text-red-600 hover:bg-red-100 rounded-lg transition-colors"
                                                         >
                                                             <Trash2 className="w-4 h-4" />
@@ -3,6 +3,7 @@ import { X, Package, DollarSign, BarChart3, Layers, Image as ImageIcon, Tag, Per
 import { motion, AnimatePresence } from 'motion/react';
 import { invoke } from '@tauri-apps/api/core';
 import { useNotification } from '@/context/NotificationContext';
+import { useAuth } from '@/context/AuthContext';
 
 interface Category {
     id: number;
@@ -44,6 +45,7 @@ interface ProductModalProps {
 
 export default function ProductModal({ isOpen, onClose, onSubmit, initialData, categories, storeId }: ProductModalProps) {
     const { showNotification } = useNotification();
+    const { user } = useAuth();
     const [isSubmitting, setIsSubmitting] = useState(false);
 
     // Batch items list
@@ -219,10 +221,10 @@ export default function ProductModal({ isOpen, onClose, onSubmit, initialData, c
                     categoryId: categoryId || null,
                     price: parseFloat(price) || 0,
                     cost: parseFloat(cost) || 0,
-                    stock: parseInt(stock) || 0,
                     unit: null,
                     imageUrl: imageUrl || null,
-                    storeId
+                    storeId,
+                    userId: user?.id ?? null
                 });
                 showNotification('success', 'Éxito', 'Producto actualizado');
                 onSubmit();
@@ -251,7 +253,7 @@ export default function ProductModal({ isOpen, onClose, onSubmit, initialData, c
         try {
             const payload = {
                 storeId,
-                createdBy: 1, // TODO: get from auth context
+                createdBy: user?.id ?? 1,
                 supplierName: supplierName || null,
                 batchDate,
                 alias: alias || null,
@@ -267,8 +269,12 @@ export default function ProductModal({ isOpen, onClose, onSubmit, initialData, c
                 })),
             };
 
-            await invoke('create_purchase_order', { payload });
+            const order = await invoke<{ items: { price_status: string | null }[] }>('create_purchase_order', { payload });
             showNotification('success', 'Éxito', `Lote registrado: ${batchItems.length} productos`);
+            const pendingPrices = order.items.filter(item => item.price_status === 'pending').length;
+            if (pendingPrices > 0) {
+                showNotification('info', 'Precios por confirmar', `${pendingPrices} producto(s) tienen un precio de lote distinto al actual. Confírmelos para aplicarlos.`);
+            }
             onSubmit();
             onClose();
         } catch (error) {
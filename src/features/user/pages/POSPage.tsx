@@ -29,6 +29,12 @@ interface Product {
     is_active: boolean;
 }
 
+interface ProductLookup extends Product {
+    matched_by: 'barcode' | 'code' | 'embedded';
+    unit_price: number;
+    embedded_weight_grams: number | null;
+}
+
 interface CartItem {
     id: string;       // uuid for cart key
     product: Product;
@@ -76,13 +82,13 @@ const POSPage = () => {
 
     const loadCategories = useCallback(async () => {
         try {
-            const data = await invoke<Category[]>('get_categories');
+            const data = await invoke<Category[]>('get_categories', { storeId: activeStoreId });
             setCategories(data);
         } catch (error) {
             console.error(error);
             showNotification('error', 'Error', 'No se pudieron cargar las categorías');
         }
-    }, [showNotification]);
+    }, [showNotification, activeStoreId]);
 
     useEffect(() => {
         const init = async () => {
@@ -95,12 +101,25 @@ const POSPage = () => {
     }, [loadProducts, loadCategories, activeStoreId]);
 
     // ─── Cart helpers ────────────────────────────────────────
-    const addToCart = (product: Product) => {
+    const addToCart = (product: Product, customPrice?: number) => {
         if (product.stock <= 0) {
             showNotification('warning', 'Sin stock', `"${product.name}" no tiene stock disponible`);
             return;
         }
 
+        // Scale / price labels carry their own amount: keep each one as its own line
+        if (customPrice !== undefined) {
+            const inCart = cart
+                .filter(item => item.product.id === product.id)
+                .reduce((sum, item) => sum + item.quantity, 0);
+            if (inCart >= product.stock) {
+                showNotification('warning', 'Stock máximo', `Solo hay ${product.stock} unidad(es) disponibles de "${product.name}"`);
+                return;
+            }
+            setCart([...cart, { product, quantity: 1, id: uuidv4(), customPrice }]);
+            return;
+        }
+
         const existingItem = cart.find(item => item.product.id === product.id);
         if (existingItem) {
             if (existingItem.quantity >= product.stock) {
@@ -117,6 +136,24 @@ const POSPage = () => {
         }
     };
 
+    // Scanners type the code and send Enter
+    const handleScan = async () => {
+        const code = searchQuery.trim();
+        if (!code || !activeStoreId) return;
+        try {
+            const found = await invoke<ProductLookup | null>('lookup_product', { storeId: activeStoreId, code });
+            if (!found) {
+                showNotification('warning', 'No encontrado', `No hay un producto con el código "${code}"`);
+                return;
+            }
+            addToCart(found, found.matched_by === 'embedded' ? found.unit_price : undefined);
+            setSearchQuery('');
+        } catch (error) {
+            console.error(error);
+            showNotification('error', 'Error', String(error));
+        }
+    };
+
     const removeFromCart = (id: string) => {
         setCart(cart.filter(item => item.id !== id));
     };
@@ -237,6 +274,7 @@ const POSPage = () => {
                             placeholder="Buscar productos..."
                             value={searchQuery}
                             onChange={(e) => setSearchQuery(e.target.value)}
+                            onKeyDown={(e) => { if (e.key === 'Enter') handleScan(); }}
                             className="w-full pl-10 pr-4 py-3 bg-gray-50 border border-gray-200 rounded-xl focus:outline-none focus:ring-2 focus:ring-blue-500"
                         />
                     </div>
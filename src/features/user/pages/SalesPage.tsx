@@ -22,6 +22,7 @@ interface OrderItemExport {
   unit_price: number;
   quantity: number;
   subtotal: number;
+  unit_cost: number;
 }
 
 type SortField = 'id' | 'total' | 'created_at' | 'payment_method';
@@ -134,7 +135,7 @@ const SalesPage = () => {
         const to = dateTo ? new Date(dateTo + 'T23:59:59') : null;
         return (!from || d >= from) && (!to || d <= to);
       });
-      const headers = ['N° Orden', 'Fecha', 'Cliente', 'Documento', 'Método de Pago', 'Prenda', 'Precio Unit.', 'Cantidad', 'Subtotal'];
+      const headers = ['N° Orden', 'Fecha', 'Cliente', 'Documento', 'Método de Pago', 'Prenda', 'Precio Unit.', 'Cantidad', 'Subtotal', 'Costo Unit.', 'Costo Total'];
       const rows = filtered.map(item => [
         item.order_id,
         formatDateTime(item.created_at),
@@ -145,6 +146,8 @@ const SalesPage = () => {
         item.unit_price.toFixed(2),
         item.quantity,
         item.subtotal.toFixed(2),
+        item.unit_cost.toFixed(2),
+        (item.unit_cost * item.quantity).toFixed(2),
       ]);
       const csv = [headers, ...rows]
         .map(row => row.map(v => `"${String(v).replace(/"/g, '""')}"`).join(','))